@@ -0,0 +1,306 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use numbers::{ArithmeticError, Notation, Number};
+
+use std::fmt::{self, Display, Formatter};
+
+/// Result of a builtin, some of them give several numbers at once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(Number),
+    Tuple(Vec<Number>),
+    Bool(bool),
+    /// A number to be shown in its own notation.
+    Formatted(Number, Notation),
+}
+
+impl Value {
+    pub fn normalize(self) -> Value {
+        match self {
+            Value::Number(number) => Value::Number(number.normalize()),
+            Value::Tuple(numbers) => Value::Tuple(numbers.into_iter().map(Number::normalize).collect()),
+            Value::Formatted(number, notation) => Value::Formatted(number.normalize(), notation),
+            value => value,
+        }
+    }
+
+    /// An error for infinities and NaN among the numbers when the session
+    /// is strict about them.
+    pub fn check_finite(&self) -> Result<(), ArithmeticError> {
+        match self {
+            Value::Number(number) | Value::Formatted(number, _) => number.check_finite(),
+            Value::Tuple(numbers) => numbers.iter().try_for_each(Number::check_finite),
+            Value::Bool(_) => Ok(()),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Value::Number(number) => number.fmt(f),
+            Value::Tuple(numbers) => {
+                let numbers: Vec<String> = numbers.iter().map(|number| number.to_string()).collect();
+                write!(f, "({})", numbers.join(", "))
+            }
+            Value::Bool(value) => value.fmt(f),
+            Value::Formatted(number, notation) => number.display(*notation).fmt(f),
+        }
+    }
+}
+
+enum Function {
+    Number(fn(Vec<Number>) -> Result<Number, ArithmeticError>),
+    Tuple(fn(Vec<Number>) -> Result<Vec<Number>, ArithmeticError>),
+    Bool(fn(Vec<Number>) -> Result<bool, ArithmeticError>),
+    Format(fn(&[Number]) -> Result<Notation, ArithmeticError>),
+}
+
+/// Flag of builtins that take modular numbers.
+const MODULAR: u8 = 1;
+
+/// Flag of builtins that follow the derivatives of dual numbers, the
+/// integer ones are constant between their jumps.
+const DIFFERENTIABLE: u8 = 2;
+
+/// Flag of builtins that convert to a type of the tower, their results
+/// keep it instead of being shown in the simplest type.
+const CONVERSION: u8 = 4;
+
+/// Native function callable from the language, with the accepted
+/// numbers of arguments.
+pub struct Builtin {
+    pub name: &'static str,
+    pub arity: &'static [usize],
+    flags: u8,
+    function: Function,
+}
+
+impl Builtin {
+    pub fn check_arity(&self, count: usize) -> Result<(), ArithmeticError> {
+        if self.arity.contains(&count) {
+            return Ok(());
+        }
+        let counts: Vec<String> = self.arity.iter().map(|count| count.to_string()).collect();
+        let noun = if self.arity == [1] { "argument" } else { "arguments" };
+        Err(ArithmeticError::Domain(format!(
+            "{} takes {} {}, found {}",
+            self.name,
+            counts.join(" or "),
+            noun,
+            count
+        )))
+    }
+
+    /// Whether `diff` and `grad` take the builtin itself, a function of
+    /// numbers that follows the derivatives of dual numbers.
+    pub fn is_differentiable(&self) -> bool {
+        self.flags & DIFFERENTIABLE != 0 && matches!(self.function, Function::Number(_))
+    }
+
+    pub fn call(&self, arguments: Vec<Number>) -> Result<Value, ArithmeticError> {
+        self.check_arity(arguments.len())?;
+        let modular = arguments.iter().any(|argument| matches!(argument, Number::Modular(..)));
+        if modular && self.flags & MODULAR == 0 {
+            return Err(ArithmeticError::Domain(format!(
+                "{} is not defined for Modular numbers",
+                self.name
+            )));
+        }
+        let dual = arguments.iter().any(|argument| matches!(argument, Number::Dual(..)));
+        if dual && self.flags & DIFFERENTIABLE == 0 {
+            return Err(ArithmeticError::Domain(format!("{} can't be differentiated", self.name)));
+        }
+        let value = match self.function {
+            Function::Number(function) => function(arguments).map(Value::Number),
+            Function::Tuple(function) => function(arguments).map(Value::Tuple),
+            Function::Bool(function) => function(arguments).map(Value::Bool),
+            Function::Format(function) => {
+                let notation = function(&arguments)?;
+                Ok(Value::Formatted(arguments.into_iter().next().unwrap(), notation))
+            }
+        }?;
+        Ok(if self.flags & CONVERSION == 0 { value.normalize() } else { value })
+    }
+}
+
+macro_rules! builtins {
+    (
+        numbers { $($name:ident($($arity:expr),*) $([$($flag:ident)|*])* => $function:expr),* $(,)* }
+        tuples { $($tuple:ident($($tuple_arity:expr),*) => $tuple_function:expr),* $(,)* }
+        bools { $($bool:ident($($bool_arity:expr),*) => $bool_function:expr),* $(,)* }
+        formats { $($format:ident($($format_arity:expr),*) => $format_function:expr),* $(,)* }
+    ) => {
+        const BUILTINS: &[Builtin] = &[
+            $(Builtin {
+                name: stringify!($name),
+                arity: &[$($arity),*],
+                flags: 0 $($(| $flag)*)*,
+                function: Function::Number($function),
+            },)*
+            $(Builtin {
+                name: stringify!($tuple),
+                arity: &[$($tuple_arity),*],
+                flags: 0,
+                function: Function::Tuple($tuple_function),
+            },)*
+            $(Builtin {
+                name: stringify!($bool),
+                arity: &[$($bool_arity),*],
+                flags: 0,
+                function: Function::Bool($bool_function),
+            },)*
+            // formats only change how their argument is shown
+            $(Builtin {
+                name: stringify!($format),
+                arity: &[$($format_arity),*],
+                flags: MODULAR | DIFFERENTIABLE,
+                function: Function::Format($format_function),
+            },)*
+        ];
+    };
+}
+
+builtins! {
+    numbers {
+        sin(1) [DIFFERENTIABLE] => |mut args| args.remove(0).sin(),
+        cos(1) [DIFFERENTIABLE] => |mut args| args.remove(0).cos(),
+        tan(1) [DIFFERENTIABLE] => |mut args| args.remove(0).tan(),
+        asin(1) [DIFFERENTIABLE] => |mut args| args.remove(0).asin(),
+        acos(1) [DIFFERENTIABLE] => |mut args| args.remove(0).acos(),
+        atan(1) [DIFFERENTIABLE] => |mut args| args.remove(0).atan(),
+        atan2(2) [DIFFERENTIABLE] => |mut args| args.remove(0).atan2(args.remove(0)),
+        sinh(1) [DIFFERENTIABLE] => |mut args| args.remove(0).sinh(),
+        cosh(1) [DIFFERENTIABLE] => |mut args| args.remove(0).cosh(),
+        tanh(1) [DIFFERENTIABLE] => |mut args| args.remove(0).tanh(),
+        asinh(1) [DIFFERENTIABLE] => |mut args| args.remove(0).asinh(),
+        acosh(1) [DIFFERENTIABLE] => |mut args| args.remove(0).acosh(),
+        atanh(1) [DIFFERENTIABLE] => |mut args| args.remove(0).atanh(),
+        exp(1) [DIFFERENTIABLE] => |mut args| args.remove(0).exp(),
+        ln(1) [DIFFERENTIABLE] => |mut args| args.remove(0).ln(),
+        log(1, 2) [DIFFERENTIABLE] => |mut args| match args.len() {
+            1 => args.remove(0).log(),
+            _ => args.remove(0).log_base(args.remove(0)),
+        },
+        sqrt(1) [DIFFERENTIABLE] => |mut args| args.remove(0).sqrt(),
+        cbrt(1) [DIFFERENTIABLE] => |mut args| args.remove(0).cbrt(),
+        root(2) [DIFFERENTIABLE] => |mut args| args.remove(0).root(args.remove(0)),
+        abs(1) [DIFFERENTIABLE] => |mut args| Ok(args.remove(0).abs()),
+        arg(1) [DIFFERENTIABLE] => |mut args| Ok(args.remove(0).arg()),
+        re(1) [DIFFERENTIABLE] => |mut args| Ok(args.remove(0).re()),
+        im(1) [DIFFERENTIABLE] => |mut args| Ok(args.remove(0).im()),
+        conj(1) [DIFFERENTIABLE] => |mut args| Ok(args.remove(0).conj()),
+        polar(2) => |mut args| Number::from_polar(args.remove(0), args.remove(0)),
+        cis(1) => |mut args| Number::cis(args.remove(0)),
+        div(2) [DIFFERENTIABLE] => |mut args| args.remove(0).checked_euclid_div(args.remove(0)),
+        mod(2) [DIFFERENTIABLE] => |mut args| args.remove(0).checked_euclid_rem(args.remove(0)),
+        rem(2) [DIFFERENTIABLE] => |mut args| args.remove(0).checked_rem(args.remove(0)),
+        floor_div(2) [DIFFERENTIABLE] => |mut args| args.remove(0).checked_floor_div(args.remove(0)),
+        floor(1) [DIFFERENTIABLE] => |mut args| args.remove(0).floor(),
+        ceil(1) [DIFFERENTIABLE] => |mut args| args.remove(0).ceil(),
+        round(1) [DIFFERENTIABLE] => |mut args| args.remove(0).round(),
+        trunc(1) [DIFFERENTIABLE] => |mut args| args.remove(0).trunc(),
+        frac(1) [DIFFERENTIABLE] => |mut args| args.remove(0).frac(),
+        nat(1) [CONVERSION] => |mut args| args.remove(0).into_natural(),
+        int(1) [CONVERSION] => |mut args| args.remove(0).into_integer(),
+        rat(1) [CONVERSION] => |mut args| args.remove(0).into_rational(),
+        real(1) [CONVERSION] => |mut args| args.remove(0).into_real(),
+        complex(1) [CONVERSION] => |mut args| args.remove(0).into_complex(),
+        rationalize(2) => |mut args| args.remove(0).rationalize(args.remove(0)),
+        quantize(2) => |mut args| args.remove(0).quantize(args.remove(0)),
+        modular(2) [MODULAR] => |mut args| Number::modular(args.remove(0), args.remove(0)),
+        interval(2) => |mut args| Number::interval(args.remove(0), args.remove(0)),
+        lo(1) => |mut args| args.remove(0).lo(),
+        hi(1) => |mut args| args.remove(0).hi(),
+        mid(1) => |mut args| args.remove(0).mid(),
+        width(1) => |mut args| args.remove(0).width(),
+        nominal(1) => |mut args| args.remove(0).nominal(),
+        uncertainty(1) => |mut args| args.remove(0).uncertainty(),
+        gcd(2) => |mut args| args.remove(0).gcd(args.remove(0)),
+        lcm(2) => |mut args| args.remove(0).lcm(args.remove(0)),
+        modpow(3) => |mut args| args.remove(0).modpow(args.remove(0), args.remove(0)),
+        modinv(2) => |mut args| args.remove(0).modinv(args.remove(0)),
+        next_prime(1) => |mut args| args.remove(0).next_prime(),
+        totient(1) => |mut args| args.remove(0).totient(),
+        isqrt(1) => |mut args| args.remove(0).isqrt(),
+        iroot(2) => |mut args| args.remove(0).iroot(args.remove(0)),
+    }
+    tuples {
+        divmod(2) => |mut args| {
+            let (quot, rem) = args.remove(0).checked_divmod(args.remove(0))?;
+            Ok(vec![quot, rem])
+        },
+        xgcd(2) => |mut args| args.remove(0).xgcd(args.remove(0)),
+        factor(1) => |mut args| args.remove(0).factor(),
+        divisors(1) => |mut args| args.remove(0).divisors(),
+        cfrac(2) => |mut args| {
+            let count = count(&args[1], "terms")?;
+            args.remove(0).cfrac(count)
+        },
+        convergents(2) => |mut args| {
+            let count = count(&args[1], "terms")?;
+            args.remove(0).convergents(count)
+        },
+        minpoly(1) => |mut args| args.remove(0).minimal_polynomial(),
+    }
+    bools {
+        approx(3) => |mut args| args.remove(0).approx(args.remove(0), args.remove(0)),
+        is_prime(1) => |args| args[0].is_prime(),
+        is_finite(1) => |args| Ok(args[0].is_finite()),
+        is_nan(1) => |args| Ok(args[0].is_nan()),
+    }
+    formats {
+        fraction(1) => |args| Notation::Fraction.check(&args[0]),
+        mixed(1) => |args| Notation::Mixed.check(&args[0]),
+        decimal(1) => |args| Notation::Decimal.check(&args[0]),
+        fixed(2) => |args| Notation::Fixed(count(&args[1], "digits")?).check(&args[0]),
+        sci(1, 2) => |args| Notation::Scientific(significant(args)?).check(&args[0]),
+        eng(1, 2) => |args| Notation::Engineering(significant(args)?).check(&args[0]),
+        hex(1) => |args| Notation::Hex.check(&args[0]),
+        oct(1) => |args| Notation::Octal.check(&args[0]),
+        bin(1) => |args| Notation::Binary.check(&args[0]),
+    }
+}
+
+/// Most digits or terms a builtin can be asked for.
+const MAX_COUNT: usize = 10000;
+
+/// Number of `what` asked for by an argument.
+fn count(number: &Number, what: &str) -> Result<usize, ArithmeticError> {
+    match number {
+        Number::Natural(nat) if nat.value().bits() <= 16 && nat.value().low_u64() as usize <= MAX_COUNT => {
+            Ok(nat.value().low_u64() as usize)
+        }
+        _ => Err(ArithmeticError::Domain(format!(
+            "number of {} must be a natural number up to {}",
+            what, MAX_COUNT
+        ))),
+    }
+}
+
+/// Optional second argument of `sci` and `eng`, at least one digit.
+fn significant(args: &[Number]) -> Result<Option<usize>, ArithmeticError> {
+    match args.get(1) {
+        Some(number) if number.is_zero() => Err(ArithmeticError::Domain(
+            "at least one significant digit is needed".to_owned(),
+        )),
+        Some(number) => count(number, "digits").map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn lookup(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
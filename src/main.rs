@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use numbers::Number;
+use builtins::{Builtin, Value};
+use numbers::{ArithmeticError, Certainty, Decimal, Dual, Long, Notation, Number, Real, Rounding, Tolerance, Uncertain};
 
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
 use std::io::Write;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -50,9 +53,17 @@ enum Token {
     MUL,
     DIV,
     MOD,
+    POW, // **
+    CIS, // r cis angle
+    PLUSMINUS {
+        source: u64,
+    }, // ± or +/-, every one a measurement of its own
     /// parentheses
     LPAREN,
     RPAREN,
+    /// brackets of an interval
+    LBRACKET,
+    RBRACKET,
     SEMI,
     COLON,
     COMMA,
@@ -85,20 +96,25 @@ impl Token {
             MUL => "MUL",
             DIV => "DIV",
             MOD => "MOD",
+            POW => "POW",
+            CIS => "CIS",
+            PLUSMINUS { .. } => "PLUSMINUS",
             SEMI => "SEMI",
             COLON => "COLON",
             COMMA => "COMMA",
             EOF => "EOF",
             LPAREN => "LPAREN",
             RPAREN => "RPAREN",
+            LBRACKET => "LBRACKET",
+            RBRACKET => "RBRACKET",
             EMPTY => "EMPTY",
         }.to_string()
     }
 
     fn value(&self) -> Option<Number> {
         use Token::*;
-        match *self {
-            NUMBER { value } => Some(value),
+        match self {
+            NUMBER { value } => Some(value.clone()),
             _ => None,
         }
     }
@@ -111,6 +127,11 @@ impl Token {
         }
     }
 
+    fn is_comparison(&self) -> bool {
+        use Token::*;
+        matches!(self, GREATER | LESS | EQUAL | NEQUAL | LEQUAL | GEQUAL)
+    }
+
     fn name(&self) -> Option<String> {
         use Token::*;
         match self {
@@ -120,10 +141,66 @@ impl Token {
     }
 }
 
+/// Literals with more exact digits than this are refused rather than
+/// expanded, a tenth of the bits exact numbers may have.
+const MAX_LITERAL_EXPONENT: u64 = 100_000;
+
+/// Refuses exact literals whose exponent would spell out too many digits.
+fn exact_exponent(literal: &str) -> Result<(), LiteralProblem> {
+    let exponent = literal.split('e').nth(1).unwrap_or("0");
+    match exponent.parse::<i64>() {
+        Ok(exponent) if exponent.unsigned_abs() <= MAX_LITERAL_EXPONENT => Ok(()),
+        _ => Err(LiteralProblem::ExponentTooLarge),
+    }
+}
+
+/// What is wrong with a malformed numeric literal.
+#[derive(Clone, Debug, PartialEq)]
+enum LiteralProblem {
+    /// `0x`, `1e` or `1//`, nothing after the marker.
+    MissingDigits(&'static str),
+    /// `0b102`
+    InvalidDigit { digit: char, base: &'static str },
+    /// `1__000`, `1_` or `1_.5`
+    Separator,
+    /// `.5`
+    LeadingPoint,
+    /// `1//0`
+    ZeroDenominator,
+    /// `1e999999r`
+    ExponentTooLarge,
+    /// `1.2.3`, `12a` or `0.5ri`
+    Unexpected(char),
+}
+
+/// Malformed numeric literal, reported in place of the line's result.
+#[derive(Clone, Debug, PartialEq)]
+struct LiteralError {
+    literal: String,
+    problem: LiteralProblem,
+}
+
+impl Display for LiteralError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "bad number {}: ", self.literal)?;
+        match &self.problem {
+            LiteralProblem::MissingDigits(marker) => write!(f, "expected digits after {}", marker),
+            LiteralProblem::InvalidDigit { digit, base } => write!(f, "{} is not a {} digit", digit, base),
+            LiteralProblem::Separator => "_ must stand between two digits".fmt(f),
+            LiteralProblem::LeadingPoint => "expected digits before .".fmt(f),
+            LiteralProblem::ZeroDenominator => "zero denominator".fmt(f),
+            LiteralProblem::ExponentTooLarge => "exponent too large for an exact number".fmt(f),
+            LiteralProblem::Unexpected(c) => write!(f, "unexpected {}", c),
+        }
+    }
+}
+
 struct Lexer {
     text: String,
     pos: usize,
     current_token: Token,
+    /// First malformed literal of the current line.
+    literal_error: Option<LiteralError>,
 }
 
 impl Lexer {
@@ -132,6 +209,7 @@ impl Lexer {
             text: text.into(),
             pos: 0,
             current_token: Token::EMPTY,
+            literal_error: None,
         }
     }
 
@@ -177,10 +255,21 @@ impl Lexer {
                 if id == "true" || id == "false" {
                     return Token::BOOL { value: id == "true" };
                 }
+                // polar form between two factors, the builtin before parentheses
+                if id == "cis" {
+                    return Token::CIS;
+                }
+                if id == "inf" || id == "nan" {
+                    let value = if id == "inf" { f64::INFINITY } else { f64::NAN };
+                    return Token::NUMBER {
+                        value: Number::Real(Real::new(value)),
+                    };
+                }
                 return Token::IDENT { name: id };
             }
 
-            if cs.is_digit(10) {
+            // `.5` is read as a literal to report the missing digits
+            if cs.is_digit(10) || cs == '.' {
                 return Token::NUMBER {
                     value: self.number(),
                 };
@@ -245,9 +334,21 @@ impl Lexer {
                     return Token::XOR;
                 }
                 '+' => {
+                    if self.peek() == Some('/') && self.get_char(self.pos + 2) == Some('-') {
+                        self.pos += 3;
+                        return Token::PLUSMINUS {
+                            source: Uncertain::source(),
+                        };
+                    }
                     self.advance();
                     return Token::PLUS;
                 }
+                '±' => {
+                    self.pos += cs.len_utf8();
+                    return Token::PLUSMINUS {
+                        source: Uncertain::source(),
+                    };
+                }
                 ';' => {
                     self.advance();
                     return Token::SEMI;
@@ -265,8 +366,14 @@ impl Lexer {
                     return Token::MINUS;
                 }
                 '*' => {
-                    self.advance();
-                    return Token::MUL;
+                    if self.peek() == Some('*') {
+                        self.advance();
+                        self.advance();
+                        return Token::POW;
+                    } else {
+                        self.advance();
+                        return Token::MUL;
+                    }
                 }
                 '/' => {
                     self.advance();
@@ -284,6 +391,14 @@ impl Lexer {
                     self.advance();
                     return Token::RPAREN;
                 }
+                '[' => {
+                    self.advance();
+                    return Token::LBRACKET;
+                }
+                ']' => {
+                    self.advance();
+                    return Token::RBRACKET;
+                }
                 _ => self.error(""),
             }
         }
@@ -328,7 +443,7 @@ impl Lexer {
     fn ident(&mut self) -> String {
         let mut result = String::new();
         while let Some(cs) = self.current_char() {
-            if cs.is_alphanumeric() {
+            if cs.is_alphanumeric() || cs == '_' {
                 result.push(cs);
                 self.advance();
             } else {
@@ -338,77 +453,166 @@ impl Lexer {
         result
     }
 
+    /// Reads a numeric literal. A malformed one is recorded in
+    /// `literal_error` and read as `0`, so that parsing can go on.
     fn number(&mut self) -> Number {
-        let mut num = String::new();
-        let mut den = String::new();
-        let mut rat = false;
-        let mut rea = false;
-        let mut com = false;
-
-        while let Some(cs) = self.current_char() {
-            if cs.is_digit(10) {
-                if rat {
-                    den.push(cs);
-                } else {
-                    num.push(cs);
-                }
-                self.advance();
-            } else {
-                if cs == '/' && self.peek() == Some('/') {
-                    if rat || rea {
-                        if rat {
-                            self.error(format!("expected 0..9 found //"))
-                        } else {
-                            self.error(format!("expected 0..9 or i found ."))
-                        }
-                    } else {
-                        rat = true;
-                        self.advance();
+        let start = self.pos;
+        match self.literal() {
+            Ok(number) => number,
+            Err(problem) => {
+                while let Some(cs) = self.current_char() {
+                    if cs.is_alphanumeric() || "_./".contains(cs) {
                         self.advance();
-                        continue;
-                    }
-                }
-                if cs == '.' {
-                    if rat || rea {
-                        if rat {
-                            self.error(format!("expected 0..9 found ."))
-                        } else {
-                            self.error(format!("expected 0..9 or i found ."))
-                        }
                     } else {
-                        num.push(cs);
-                        rea = true;
-                        self.advance();
-                        continue;
+                        break;
                     }
                 }
-                if cs == 'i' {
-                    if rat {
-                        self.error(format!("expected 0..9 found i"))
-                    } else {
-                        rea = true;
-                        com = true;
-                        self.advance();
-                        continue;
-                    }
+                if self.literal_error.is_none() {
+                    self.literal_error = Some(LiteralError {
+                        literal: self.text[start..self.pos].to_owned(),
+                        problem,
+                    });
+                }
+                Number::natural("0".to_owned()).unwrap()
+            }
+        }
+    }
+
+    fn literal(&mut self) -> Result<Number, LiteralProblem> {
+        if self.current_char() == Some('.') {
+            return Err(LiteralProblem::LeadingPoint);
+        }
+        let radix = match (self.current_char(), self.peek()) {
+            (Some('0'), Some('x')) | (Some('0'), Some('X')) => Some((16, "0x", "hexadecimal")),
+            (Some('0'), Some('o')) | (Some('0'), Some('O')) => Some((8, "0o", "octal")),
+            (Some('0'), Some('b')) | (Some('0'), Some('B')) => Some((2, "0b", "binary")),
+            _ => None,
+        };
+        if let Some((radix, prefix, base)) = radix {
+            self.advance();
+            self.advance();
+            let digits = self.digits(radix)?;
+            if digits.is_empty() {
+                return Err(LiteralProblem::MissingDigits(prefix));
+            }
+            match self.current_char() {
+                Some(digit) if digit.is_alphanumeric() => {
+                    return Err(LiteralProblem::InvalidDigit { digit, base });
+                }
+                _ => {}
+            }
+            self.end_of_literal()?;
+            let value = Long::from_str_radix(&digits, radix).unwrap();
+            return Ok(Number::natural(value.to_string()).unwrap());
+        }
+
+        let mut num = self.digits(10)?;
+        if self.current_char() == Some('/') && self.peek() == Some('/') {
+            self.advance();
+            self.advance();
+            let den = self.digits(10)?;
+            if den.is_empty() {
+                return Err(LiteralProblem::MissingDigits("//"));
+            }
+            self.end_of_literal()?;
+            if den.trim_start_matches('0').is_empty() {
+                return Err(LiteralProblem::ZeroDenominator);
+            }
+            return Ok(Number::rational(num, den).unwrap());
+        }
+
+        let mut rea = false;
+        if self.current_char() == Some('.') {
+            self.advance();
+            if self.current_char() == Some('_') {
+                return Err(LiteralProblem::Separator);
+            }
+            num.push('.');
+            num.push_str(&self.digits(10)?);
+            rea = true;
+        }
+        if let Some(marker @ 'e') | Some(marker @ 'E') = self.current_char() {
+            self.advance();
+            num.push('e');
+            if let Some(sign @ '+') | Some(sign @ '-') = self.current_char() {
+                self.advance();
+                num.push(sign);
+            }
+            let exponent = self.digits(10)?;
+            if exponent.is_empty() {
+                return Err(LiteralProblem::MissingDigits(if marker == 'e' { "e" } else { "E" }));
+            }
+            num.push_str(&exponent);
+            rea = true;
+        }
+
+        match self.current_char() {
+            Some('i') => {
+                self.advance();
+                self.end_of_literal()?;
+                if rea {
+                    Ok(Number::complex(num).unwrap())
+                } else {
+                    Ok(Number::imaginary(num).unwrap())
                 }
-                if cs.is_digit(10) {
-                    self.error(format!("expected \" \" found {}", cs))
-                } else if cs.is_alphabetic() {
-                    self.error(format!("expected 0..9 found {}", cs))
+            }
+            Some('r') => {
+                self.advance();
+                self.end_of_literal()?;
+                exact_exponent(&num)?;
+                Ok(Number::decimal(num).unwrap())
+            }
+            Some('d') => {
+                self.advance();
+                self.end_of_literal()?;
+                exact_exponent(&num)?;
+                Ok(Number::fixed_point(num).unwrap())
+            }
+            _ => {
+                self.end_of_literal()?;
+                if rea && Decimal::literals() {
+                    exact_exponent(&num)?;
+                    Ok(Number::fixed_point(num).unwrap())
+                } else if rea {
+                    Ok(Number::real(num).unwrap())
                 } else {
-                    break;
+                    Ok(Number::natural(num).unwrap())
                 }
             }
         }
-        if com {
-            Number::complex(num).unwrap()
-        } else if rea {
-            Number::real(num).unwrap()
-        } else if rat {
-            Number::rational(num, den).unwrap()
-        } else {
-            Number::natural(num).unwrap()
+    }
+
+    /// Digits in base `radix` with single `_` separators between them,
+    /// which are dropped.
+    fn digits(&mut self, radix: u32) -> Result<String, LiteralProblem> {
+        let mut digits = String::new();
+        let mut separated = false;
+        while let Some(cs) = self.current_char() {
+            if cs == '_' {
+                if digits.is_empty() || separated {
+                    return Err(LiteralProblem::Separator);
+                }
+                separated = true;
+            } else if cs.is_digit(radix) {
+                digits.push(cs);
+                separated = false;
+            } else {
+                break;
+            }
+            self.advance();
+        }
+        if separated {
+            return Err(LiteralProblem::Separator);
+        }
+        Ok(digits)
+    }
+
+    /// A literal must not run into letters, digits, another point or `//`.
+    fn end_of_literal(&self) -> Result<(), LiteralProblem> {
+        match self.current_char() {
+            Some(cs) if cs.is_alphanumeric() || cs == '_' || cs == '.' => Err(LiteralProblem::Unexpected(cs)),
+            Some('/') if self.get_char(self.pos + 1) == Some('/') => Err(LiteralProblem::Unexpected('/')),
+            _ => Ok(()),
         }
     }
 
@@ -453,10 +657,22 @@ enum Node {
     },
     FunctionCall {
         name: String,
-        arguments: Vec<Box<Node>>,
+        arguments: Vec<Node>,
         body: Box<Node>,
         scope: BTreeMap<String, Box<Node>>,
     },
+    BuiltinCall {
+        name: String,
+        arguments: Vec<Node>,
+    },
+    /// `diff(f, x)` or `grad(f, x, y)`, the derivatives of the function
+    /// `f` at a point.
+    Derivative {
+        name: String,
+        parameters: Vec<String>,
+        body: Box<Node>,
+        point: Vec<Node>,
+    },
     Statement {
         statement: Statement,
     },
@@ -481,6 +697,8 @@ impl Node {
             | Number { token }
             | Bool { token } => token.token_type(),
             FunctionCall { .. } => "FUNCTION_CALL".to_string(),
+            BuiltinCall { .. } => "BUILTIN_CALL".to_string(),
+            Derivative { .. } => "DERIVATIVE".to_string(),
             Function { .. } => "FUNCTION".to_string(),
             Statement { .. } => "STATEMENT".to_string(),
             Variable { .. } => "VARIABLE".to_string(),
@@ -495,10 +713,12 @@ impl Node {
             | BinaryOperation { .. }
             | Number { .. }
             | Statement { .. }
-            | FunctionCall { .. } => (
-                "< ".to_owned(),
-                format!("{}", self.value(BTreeMap::new()).unwrap()),
-            ),
+            | FunctionCall { .. }
+            | BuiltinCall { .. }
+            | Derivative { .. } => match self.evaluate(BTreeMap::new()) {
+                Ok(value) => ("< ".to_owned(), format!("{}", value)),
+                Err(err) => ("! ".to_owned(), format!("{}", err)),
+            },
             Variable { .. } => (
                 "< ".to_owned(),
                 format!("{}", self.is_true(BTreeMap::new()).unwrap()),
@@ -518,85 +738,192 @@ impl Node {
     }
 
     fn is_true(&self, parent_scope: BTreeMap<String, Box<Node>>) -> Option<bool> {
+        self.truth(parent_scope).unwrap_or(None)
+    }
+
+    /// Like `is_true`, but reports arithmetic errors of compared expressions.
+    fn truth(&self, parent_scope: BTreeMap<String, Box<Node>>) -> Result<Option<bool>, ArithmeticError> {
         use Node::*;
+        let both = |f: fn(bool, bool) -> bool, left: &Node, right: &Node| {
+            Ok(match (left.truth(parent_scope.clone())?, right.truth(parent_scope.clone())?) {
+                (Some(a), Some(b)) => Some(f(a, b)),
+                _ => None,
+            })
+        };
         match self {
             UnaryOperation { token, right } => match token {
-                Token::NOT => right.is_true(parent_scope.clone()).map(|b| !b),
-                _ => None,
+                Token::NOT => Ok(right.truth(parent_scope.clone())?.map(|b| !b)),
+                Token::IDENT { name } => {
+                    let certainty = right.certainty(parent_scope.clone())?;
+                    Ok(Some(match name.as_str() {
+                        "possibly" => certainty != Certainty::Never,
+                        _ => certainty == Certainty::Certainly,
+                    }))
+                }
+                _ => Ok(None),
             },
+            BinaryOperation { left, token, right } if token.is_comparison() => {
+                let a = left.value(parent_scope.clone())?;
+                let b = right.value(parent_scope.clone())?;
+                match comparison(token, &a, &b)? {
+                    Certainty::Certainly => Ok(Some(true)),
+                    Certainty::Never => Ok(Some(false)),
+                    Certainty::Possibly => Err(ArithmeticError::Domain(format!(
+                        "comparison of {} and {} is uncertain",
+                        a, b
+                    ))),
+                }
+            }
             BinaryOperation { left, token, right } => match token {
-                Token::AND => left.is_true(parent_scope.clone())
-                    .and_then(|a| right.is_true(parent_scope.clone()).map(|b| a && b)),
-                Token::OR => left.is_true(parent_scope.clone())
-                    .and_then(|a| right.is_true(parent_scope.clone()).map(|b| a || b)),
-                Token::XOR => left.is_true(parent_scope.clone())
-                    .and_then(|a| right.is_true(parent_scope.clone()).map(|b| a ^ b)),
-                Token::EQUAL => left.value(parent_scope.clone())
-                    .and_then(|a| right.value(parent_scope.clone()).map(|b| a == b)),
-                Token::NEQUAL => left.value(parent_scope.clone())
-                    .and_then(|a| right.value(parent_scope.clone()).map(|b| a != b)),
-                Token::LESS => left.value(parent_scope.clone())
-                    .and_then(|a| right.value(parent_scope.clone()).map(|b| a < b)),
-                Token::GREATER => left.value(parent_scope.clone())
-                    .and_then(|a| right.value(parent_scope.clone()).map(|b| a > b)),
-                Token::LEQUAL => left.value(parent_scope.clone())
-                    .and_then(|a| right.value(parent_scope.clone()).map(|b| a <= b)),
-                Token::GEQUAL => left.value(parent_scope.clone())
-                    .and_then(|a| right.value(parent_scope.clone()).map(|b| a >= b)),
-                _ => None,
+                Token::AND => both(|a, b| a && b, left, right),
+                Token::OR => both(|a, b| a || b, left, right),
+                Token::XOR => both(|a, b| a ^ b, left, right),
+                _ => Ok(None),
             },
-            Function { .. } => None,
+            Function { .. } => Ok(None),
             Statement { statement } => {
                 use Statement::*;
                 match statement {
-                    Expression(expr) => expr.is_true(parent_scope.clone()),
+                    Expression(expr) => expr.truth(parent_scope.clone()),
                     Condition {
                         condition,
                         statement,
                         statement_else,
                     } => {
-                        if condition.is_true(parent_scope.clone()) == Some(true) {
-                            statement.is_true(parent_scope.clone())
+                        if condition.truth(parent_scope.clone())? == Some(true) {
+                            statement.truth(parent_scope.clone())
                         } else {
-                            statement_else.is_true(parent_scope.clone())
+                            statement_else.truth(parent_scope.clone())
                         }
                     }
                 }
             }
-            Bool { token: val } => val.is_true(),
-            Number { .. } => None,
+            Bool { token: val } => Ok(val.is_true()),
+            Number { .. } => Ok(None),
             Variable { name } => {
                 let value = parent_scope.get(name).unwrap().to_owned();
-                value.is_true(parent_scope.clone())
+                value.truth(parent_scope.clone())
             }
-            FunctionCall { body, scope, .. } => body.is_true(scope.clone()),
+            FunctionCall { body, scope, .. } => body.truth(scope.clone()),
+            BuiltinCall { .. } => match self.evaluate(parent_scope.clone())? {
+                Value::Bool(value) => Ok(Some(value)),
+                _ => Ok(None),
+            },
+            Derivative { .. } => Ok(None),
             Empty => unreachable!()
         }
     }
 
-    fn value(&self, parent_scope: BTreeMap<String, Box<Node>>) -> Option<Number> {
+    /// Three-valued truth of a condition, a comparison of overlapping
+    /// intervals is `Possibly` true.
+    fn certainty(&self, parent_scope: BTreeMap<String, Box<Node>>) -> Result<Certainty, ArithmeticError> {
         use Node::*;
         match self {
+            BinaryOperation { left, token, right } if token.is_comparison() => {
+                let a = left.value(parent_scope.clone())?;
+                let b = right.value(parent_scope.clone())?;
+                comparison(token, &a, &b)
+            }
+            BinaryOperation { left, token, right } if [Token::AND, Token::OR, Token::XOR].contains(token) => {
+                let a = left.certainty(parent_scope.clone())?;
+                let b = right.certainty(parent_scope.clone())?;
+                Ok(match token {
+                    Token::AND => a.min(b),
+                    Token::OR => a.max(b),
+                    _ if a == Certainty::Possibly || b == Certainty::Possibly => Certainty::Possibly,
+                    _ => Certainty::from(a != b),
+                })
+            }
+            UnaryOperation { token: Token::NOT, right } => Ok(right.certainty(parent_scope.clone())?.not()),
+            _ => Ok(Certainty::from(self.truth(parent_scope)? == Some(true))),
+        }
+    }
+
+    /// Like `value`, but also accepts builtins giving several numbers.
+    fn evaluate(&self, parent_scope: BTreeMap<String, Box<Node>>) -> Result<Value, ArithmeticError> {
+        use Node::*;
+        match self {
+            Statement { statement } => {
+                use Statement::*;
+                match statement {
+                    Expression(expr) => expr.evaluate(parent_scope.clone()),
+                    Condition {
+                        condition,
+                        statement,
+                        statement_else,
+                    } => {
+                        if condition.truth(parent_scope.clone())? == Some(true) {
+                            statement.evaluate(parent_scope.clone())
+                        } else {
+                            statement_else.evaluate(parent_scope.clone())
+                        }
+                    }
+                }
+            }
+            Variable { name } => {
+                let value = parent_scope.get(name).unwrap().to_owned();
+                value.evaluate(parent_scope.clone())
+            }
+            FunctionCall { body, scope, .. } => body.evaluate(scope.clone()),
+            BuiltinCall { name, arguments } => {
+                let mut values = Vec::new();
+                for argument in arguments {
+                    values.push(argument.value(parent_scope.clone())?);
+                }
+                let value = builtins::lookup(name).unwrap().call(values)?;
+                value.check_finite()?;
+                Ok(value)
+            }
+            Derivative { name, parameters, body, point } => {
+                // every parameter is a variable of its own, one evaluation
+                // gives all the partial derivatives
+                let mut scope = BTreeMap::new();
+                let mut variables = Vec::new();
+                for (parameter, argument) in parameters.iter().zip(point) {
+                    let variable = Dual::next_variable();
+                    let value = numbers::Number::dual(argument.value(parent_scope.clone())?, variable)?;
+                    scope.insert(parameter.clone(), Box::new(Number { token: Token::NUMBER { value } }));
+                    variables.push(variable);
+                }
+                let result = body.value(scope)?;
+                let mut derivatives = Vec::new();
+                for variable in variables {
+                    derivatives.push(result.derivative(variable)?.normalize());
+                }
+                Ok(match name.as_str() {
+                    "grad" => Value::Tuple(derivatives),
+                    _ => Value::Number(derivatives.remove(0)),
+                })
+            }
+            _ => self.value(parent_scope).map(Value::Number),
+        }
+    }
+
+    fn value(&self, parent_scope: BTreeMap<String, Box<Node>>) -> Result<Number, ArithmeticError> {
+        use Node::*;
+        let number = match self {
             UnaryOperation { token, right } => match token {
                 Token::PLUS => right.value(parent_scope.clone()),
                 Token::MINUS => right.value(parent_scope.clone()).map(|n| -n),
-                _ => None,
+                _ => Err(not_a_number()),
             },
-            BinaryOperation { left, token, right } => match token {
-                Token::PLUS => left.value(parent_scope.clone())
-                    .and_then(|x| right.value(parent_scope.clone()).map(|y| x + y)),
-                Token::MINUS => left.value(parent_scope.clone())
-                    .and_then(|x| right.value(parent_scope.clone()).map(|y| x - y)),
-                Token::MUL => left.value(parent_scope.clone())
-                    .and_then(|x| right.value(parent_scope.clone()).map(|y| x * y)),
-                Token::DIV => left.value(parent_scope.clone())
-                    .and_then(|x| right.value(parent_scope.clone()).map(|y| x / y)),
-                Token::MOD => left.value(parent_scope.clone())
-                    .and_then(|x| right.value(parent_scope.clone()).map(|y| x % y)),
-                _ => None,
-            },
-            Number { token } => token.value(),
-            Function { .. } => None,
+            BinaryOperation { left, token, right } => {
+                let x = left.value(parent_scope.clone())?;
+                let y = right.value(parent_scope.clone())?;
+                match token {
+                    Token::PLUS => x.checked_add(y),
+                    Token::MINUS => x.checked_sub(y),
+                    Token::MUL => x.checked_mul(y),
+                    Token::DIV => x.checked_div(y),
+                    Token::MOD => x.checked_rem(y),
+                    Token::POW => x.checked_pow(y),
+                    Token::CIS => x.checked_mul(numbers::Number::cis(y)?),
+                    Token::PLUSMINUS { source } => numbers::Number::uncertain(x, y, *source),
+                    _ => Err(not_a_number()),
+                }
+            }
+            Number { token } => token.value().ok_or_else(not_a_number),
+            Function { .. } => Err(not_a_number()),
             Statement { statement } => {
                 use Statement::*;
                 match statement {
@@ -606,7 +933,7 @@ impl Node {
                         statement,
                         statement_else,
                     } => {
-                        if condition.is_true(parent_scope.clone()) == Some(true) {
+                        if condition.truth(parent_scope.clone())? == Some(true) {
                             statement.value(parent_scope.clone())
                         } else {
                             statement_else.value(parent_scope.clone())
@@ -614,20 +941,61 @@ impl Node {
                     }
                 }
             }
-            Bool { .. } => None,
+            Bool { .. } => Err(not_a_number()),
             Variable { name } => {
                 let value = parent_scope.get(name).unwrap().to_owned();
                 value.value(parent_scope.clone())
             }
             FunctionCall { body, scope, .. } => body.value(scope.clone()),
+            BuiltinCall { name, .. } | Derivative { name, .. } => match self.evaluate(parent_scope.clone())? {
+                Value::Number(number) | Value::Formatted(number, _) => Ok(number),
+                Value::Tuple(_) => Err(ArithmeticError::Domain(format!(
+                    "{} gives several numbers",
+                    name
+                ))),
+                Value::Bool(_) => Err(ArithmeticError::Domain(format!(
+                    "{} gives a truth value",
+                    name
+                ))),
+            },
             Empty => unreachable!()
+        }?;
+        let number = number.normalize();
+        number.check_finite()?;
+        Ok(number)
+    }
+}
+
+/// Outcome of comparing two numbers, only intervals can leave it
+/// uncertain.
+fn comparison(token: &Token, a: &Number, b: &Number) -> Result<Certainty, ArithmeticError> {
+    let holds: fn(Ordering) -> bool = match token {
+        Token::EQUAL => |o| o == Ordering::Equal,
+        Token::NEQUAL => |o| o != Ordering::Equal,
+        Token::LESS => |o| o == Ordering::Less,
+        Token::GREATER => |o| o == Ordering::Greater,
+        Token::LEQUAL => |o| o != Ordering::Greater,
+        Token::GEQUAL => |o| o != Ordering::Less,
+        _ => unreachable!(),
+    };
+    match token {
+        // equality is also defined for numbers that can't be ordered
+        Token::EQUAL | Token::NEQUAL if !a.is_interval() && !b.is_interval() => {
+            Ok(Certainty::from((a == b) == (*token == Token::EQUAL)))
         }
+        _ => a.relation(b, holds),
     }
 }
 
+fn not_a_number() -> ArithmeticError {
+    ArithmeticError::Domain("expected a number".to_owned())
+}
+
 struct Parser {
     lexer: Lexer,
     functions: BTreeMap<String, Node>,
+    /// First call of the current line whose arguments don't fit.
+    call_error: Option<String>,
 }
 
 impl Parser {
@@ -635,6 +1003,7 @@ impl Parser {
         Self {
             lexer: Lexer::new(""),
             functions: BTreeMap::new(),
+            call_error: None,
         }
     }
 
@@ -642,6 +1011,7 @@ impl Parser {
         Self {
             lexer: Lexer::new(text),
             functions: BTreeMap::new(),
+            call_error: None,
         }
     }
 
@@ -649,6 +1019,8 @@ impl Parser {
         self.lexer.text = text.into();
         self.lexer.pos = 0;
         self.lexer.current_token = Token::EMPTY;
+        self.lexer.literal_error = None;
+        self.call_error = None;
     }
 
     fn eat<T: Into<String>>(&mut self, tt: T) -> Token {
@@ -671,6 +1043,14 @@ impl Parser {
         self.lexer.error(message);
     }
 
+    /// Records a bad call, reported in place of the line's result
+    /// once the line is parsed.
+    fn bad_call(&mut self, message: String) {
+        if self.call_error.is_none() {
+            self.call_error = Some(message);
+        }
+    }
+
     fn wait(&mut self) {
         if self.lexer.get_current_token() == Token::EOF {
             if let Ok(_) = self.lexer.read_text() {}
@@ -829,7 +1209,7 @@ impl Parser {
     }
 
     fn term(&mut self) -> Node {
-        let mut node = self.factor();
+        let mut node = self.measurement();
 
         while !self.lexer.eof() {
             let op = self.lexer.get_current_token();
@@ -841,19 +1221,42 @@ impl Parser {
                 Token::DIV => {
                     self.eat("DIV");
                 }
+                Token::MOD => {
+                    self.eat("MOD");
+                }
+                Token::CIS => {
+                    self.eat("CIS");
+                }
                 _ => break,
             }
 
             node = Node::BinaryOperation {
                 left: Box::new(node),
                 token: op,
-                right: Box::new(self.factor()),
+                right: Box::new(self.measurement()),
             }
         }
 
         node
     }
 
+    /// `value ± error` binds tighter than a product, `2 * 9.81 ± 0.02`
+    /// doubles the error too.
+    fn measurement(&mut self) -> Node {
+        let node = self.factor();
+
+        if let Token::PLUSMINUS { .. } = self.lexer.get_current_token() {
+            let op = self.eat("PLUSMINUS");
+            return Node::BinaryOperation {
+                left: Box::new(node),
+                token: op,
+                right: Box::new(self.factor()),
+            };
+        }
+
+        node
+    }
+
     fn factor(&mut self) -> Node {
         let token = self.lexer.get_current_token();
 
@@ -872,6 +1275,31 @@ impl Parser {
                     right: Box::new(self.factor()),
                 }
             }
+            _ => self.power(),
+        }
+    }
+
+    /// Right-associative and binds tighter than a unary sign on its left,
+    /// `-2 ** 2` is `-(2 ** 2)`.
+    fn power(&mut self) -> Node {
+        let node = self.atom();
+
+        if self.lexer.get_current_token() == Token::POW {
+            let op = self.eat("POW");
+            return Node::BinaryOperation {
+                left: Box::new(node),
+                token: op,
+                right: Box::new(self.factor()),
+            };
+        }
+
+        node
+    }
+
+    fn atom(&mut self) -> Node {
+        let token = self.lexer.get_current_token();
+
+        match token.clone() {
             Token::NUMBER { .. } => {
                 self.eat("NUMBER");
                 Node::Number { token }
@@ -889,6 +1317,11 @@ impl Parser {
                 self.eat("RPAREN");
                 node
             }
+            Token::LBRACKET => self.interval(),
+            Token::CIS => {
+                self.eat("CIS");
+                self.builtin_call("cis".to_owned(), builtins::lookup("cis").unwrap())
+            }
             _ => unreachable!(format!(
                 "{:?} {:?} {:?}",
                 token.clone(),
@@ -898,11 +1331,31 @@ impl Parser {
         }
     }
 
+    /// `[a, b]`, the interval from `a` to `b`, even where a function
+    /// named `interval` hides the builtin.
+    fn interval(&mut self) -> Node {
+        self.eat("LBRACKET");
+        let lo = self.expression();
+        self.eat("COMMA");
+        let hi = self.expression();
+        self.eat("RBRACKET");
+
+        Node::BuiltinCall {
+            name: "interval".to_owned(),
+            arguments: vec![lo, hi],
+        }
+    }
+
     fn simple_condition(&mut self) -> Node {
         let node = self.expression();
 
         let op = self.lexer.get_current_token();
 
+        // a builtin like `approx` is a condition on its own
+        if node.node_type() == "BUILTIN_CALL" && !op.is_comparison() {
+            return node;
+        }
+
         match op {
             Token::EQUAL => {
                 self.eat("EQUAL");
@@ -954,6 +1407,17 @@ impl Parser {
                     right: Box::new(self.compound_condition()),
                 }
             }
+            // `possibly x < y` and `certainly x < y` settle an uncertain
+            // comparison of intervals
+            Token::IDENT { ref name }
+                if (name == "possibly" || name == "certainly") && !self.functions.contains_key(name) =>
+            {
+                self.eat("IDENT");
+                Node::UnaryOperation {
+                    token,
+                    right: Box::new(self.compound_condition()),
+                }
+            }
             _ => self.simple_condition(),
         }
     }
@@ -994,6 +1458,16 @@ impl Parser {
     }
 
     fn function_call(&mut self, name: String) -> Node {
+        if !self.functions.contains_key(&name) {
+            if name == "diff" || name == "grad" {
+                return self.derivative(name);
+            }
+            if let Some(builtin) = builtins::lookup(&name) {
+                self.eat("IDENT");
+                return self.builtin_call(name, builtin);
+            }
+        }
+
         self.eat("IDENT");
 
         if !self.functions.contains_key(&name) {
@@ -1026,11 +1500,126 @@ impl Parser {
         }
     }
 
+    /// Builtins are used only when no user function has their name.
+    fn builtin_call(&mut self, name: String, builtin: &Builtin) -> Node {
+        self.eat("LPAREN");
+
+        let mut arguments = Vec::new();
+        if self.lexer.get_current_token() != Token::RPAREN {
+            arguments.push(self.expression());
+            while self.lexer.get_current_token() == Token::COMMA {
+                self.eat("COMMA");
+                arguments.push(self.expression());
+            }
+        }
+        self.eat("RPAREN");
+
+        if let Err(err) = builtin.check_arity(arguments.len()) {
+            self.bad_call(err.to_string());
+        }
+
+        Node::BuiltinCall { name, arguments }
+    }
+
+    /// `diff(f, x)` takes a function of one argument, `grad(f, x, y)`
+    /// a point with as many coordinates as `f` has arguments.
+    fn derivative(&mut self, name: String) -> Node {
+        self.eat("IDENT");
+        self.eat("LPAREN");
+
+        let function = match self.lexer.get_current_token() {
+            Token::IDENT { name: function } => {
+                self.eat("IDENT");
+                function
+            }
+            _ => String::new(),
+        };
+        let end = matches!(self.lexer.get_current_token(), Token::COMMA | Token::RPAREN);
+        if function.is_empty() || !end {
+            self.bad_call(format!("{} needs the name of a function", name));
+            self.skip_argument();
+        }
+        let defined = match self.functions.get(&function) {
+            Some(Node::Function { arguments, body, .. }) => Some((arguments.clone(), body.clone())),
+            _ => None,
+        };
+
+        let mut point = Vec::new();
+        while self.lexer.get_current_token() == Token::COMMA {
+            self.eat("COMMA");
+            point.push(self.expression());
+        }
+        self.eat("RPAREN");
+
+        let (parameters, body) = match (defined, builtins::lookup(&function)) {
+            (Some(defined), _) => defined,
+            (None, Some(builtin)) => self.builtin_derivative(builtin, point.len()),
+            (None, None) => {
+                if !function.is_empty() {
+                    self.bad_call(format!("function {} is not exist", function));
+                }
+                (Vec::new(), Box::new(Node::Empty))
+            }
+        };
+
+        if name == "diff" && parameters.len() != 1 {
+            self.bad_call(format!(
+                "diff needs a function of one argument, {} takes {}",
+                function,
+                parameters.len()
+            ));
+        }
+        if point.len() != parameters.len() {
+            self.bad_call(format!(
+                "function {} takes {} {}, found {}",
+                function,
+                parameters.len(),
+                if parameters.len() == 1 { "argument" } else { "arguments" },
+                point.len()
+            ));
+        }
+
+        Node::Derivative {
+            name,
+            parameters,
+            body,
+            point,
+        }
+    }
+
+    /// Parameters and body of a function calling a builtin, for a point
+    /// of `count` coordinates.
+    fn builtin_derivative(&mut self, builtin: &Builtin, count: usize) -> (Vec<String>, Box<Node>) {
+        if !builtin.is_differentiable() {
+            self.bad_call(format!("{} can't be differentiated", builtin.name));
+            return (Vec::new(), Box::new(Node::Empty));
+        }
+        let count = if builtin.arity.contains(&count) { count } else { builtin.arity[0] };
+        let parameters: Vec<String> = (0..count).map(|i| format!("x{}", i)).collect();
+        let arguments = parameters.iter().map(|name| Node::Variable { name: name.clone() }).collect();
+        let name = builtin.name.to_owned();
+        (parameters, Box::new(Node::BuiltinCall { name, arguments }))
+    }
+
+    /// Skips the tokens up to the end of the current argument.
+    fn skip_argument(&mut self) {
+        let mut depth = 0;
+        while !self.lexer.eof() {
+            match self.lexer.get_current_token() {
+                Token::COMMA | Token::RPAREN if depth == 0 => break,
+                Token::LPAREN => depth += 1,
+                Token::RPAREN => depth -= 1,
+                _ => (),
+            }
+            self.lexer.next_token();
+        }
+    }
+
     fn parse(&mut self) -> Node {
         self.line()
     }
 
-    fn arguments(&mut self, args: Vec<String>) -> (Vec<Box<Node>>, BTreeMap<String, Box<Node>>) {
+    fn arguments(&mut self, args: Vec<String>) -> (Vec<Node>, BTreeMap<String, Box<Node>>) {
         let mut scope = BTreeMap::new();
 
         let mut ans = Vec::new();
@@ -1039,9 +1628,9 @@ impl Parser {
             return (ans, scope);
         }
         let mut i = 0;
-        let value = Box::new(self.expression());
+        let value = self.expression();
         ans.push(value.clone());
-        scope.insert(args[i].clone(), value.clone());
+        scope.insert(args[i].clone(), Box::new(value));
         i = i + 1;
 
         while !self.lexer.eof() {
@@ -1050,9 +1639,9 @@ impl Parser {
                 break;
             }
             self.eat("COMMA");
-            let value = Box::new(self.expression());
+            let value = self.expression();
             ans.push(value.clone());
-            scope.insert(args[i].clone(), value.clone());
+            scope.insert(args[i].clone(), Box::new(value));
             i = i + 1
         }
 
@@ -1060,20 +1649,63 @@ impl Parser {
     }
 }
 
+/// Settings of one session. Numbers read them from the thread they are
+/// computed on, so an interpreter puts its own in place before every
+/// line and sessions on one thread don't see each other's settings.
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    precision: Option<usize>,
+    tolerance: Option<Tolerance>,
+    strict: bool,
+    notation: Notation,
+    decimal_literals: bool,
+    scale: u32,
+    rounding: Rounding,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            tolerance: None,
+            strict: false,
+            notation: Notation::Default,
+            decimal_literals: false,
+            scale: numbers::DEFAULT_SCALE,
+            rounding: Rounding::HalfEven,
+        }
+    }
+}
+
+impl Settings {
+    fn install(&self) {
+        Real::set_precision(self.precision);
+        Real::set_tolerance(self.tolerance);
+        Real::set_strict(self.strict);
+        Notation::set_session(self.notation);
+        Decimal::set_literals(self.decimal_literals);
+        Decimal::set_scale(self.scale);
+        Decimal::set_rounding(self.rounding);
+    }
+}
+
 struct Interpreter {
     parser: Parser,
+    settings: Settings,
 }
 
 impl Interpreter {
     fn new() -> Self {
         Self {
             parser: Parser::new(),
+            settings: Settings::default(),
         }
     }
 
     fn with_text<T: Into<String> + Clone>(text: T) -> Self {
         Self {
             parser: Parser::with_text(text),
+            settings: Settings::default(),
         }
     }
 
@@ -1082,10 +1714,165 @@ impl Interpreter {
     }
 
     fn interpret(&mut self) -> String {
-        let res = self.parse().format();
+        self.settings.install();
+        let text = self.parser.lexer.text.trim().to_owned();
+        if let Some(command) = text.strip_prefix(':') {
+            return self.command(command);
+        }
+        let node = self.parse();
+        if let Some(err) = self.parser.lexer.literal_error.take() {
+            return format!("! {}", err);
+        }
+        if let Some(err) = self.parser.call_error.take() {
+            return format!("! {}", err);
+        }
+        let res = node.format();
         format!("{}{}", res.0, res.1)
     }
 
+    /// Session settings, entered as `:name value` lines.
+    fn command(&mut self, command: &str) -> String {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.first() {
+            Some(&"precision") => self.precision_command(&words[1..]),
+            Some(&"tolerance") => self.tolerance_command(&words[1..]),
+            Some(&"format") => self.format_command(&words[1..]),
+            Some(&"decimal") => self.decimal_command(&words[1..]),
+            Some(&"strict") => self.strict_command(&words[1..]),
+            _ => format!("# unknown command :{}", command),
+        }
+    }
+
+    fn precision_command(&mut self, words: &[&str]) -> String {
+        match words {
+            [] => {}
+            ["double"] => self.set_precision(None),
+            [digits] => match digits.parse::<usize>() {
+                Ok(digits) if digits > numbers::MAX_PRECISION => {
+                    return format!(
+                        "# precision {} is above the limit of {} digits",
+                        digits,
+                        numbers::MAX_PRECISION
+                    )
+                }
+                Ok(digits) if digits > 0 => self.set_precision(Some(digits)),
+                _ => return format!("# bad precision {}", digits),
+            },
+            _ => return format!("# bad precision {}", words.join(" ")),
+        }
+        match Real::precision() {
+            Some(digits) => format!("# precision {}", digits),
+            None => "# precision double".to_owned(),
+        }
+    }
+
+    fn tolerance_command(&mut self, words: &[&str]) -> String {
+        let eps = |text: &str| text.parse::<f64>().ok().filter(|eps| *eps >= 0.0);
+        let tolerance = match words {
+            [] => Some(Real::tolerance()),
+            ["default"] => Some(None),
+            ["strict"] => Some(Some(Tolerance::Strict)),
+            ["absolute", text] | [text] => eps(text).map(|eps| Some(Tolerance::Absolute(eps))),
+            ["relative", text] => eps(text).map(|eps| Some(Tolerance::Relative(eps))),
+            ["ulp", text] => text.parse::<u64>().ok().map(|ulps| Some(Tolerance::Ulps(ulps))),
+            _ => None,
+        };
+        match tolerance {
+            Some(tolerance) => {
+                self.set_tolerance(tolerance);
+                match tolerance {
+                    Some(tolerance) => format!("# tolerance {}", tolerance),
+                    None => "# tolerance default".to_owned(),
+                }
+            }
+            None => format!("# bad tolerance {}", words.join(" ")),
+        }
+    }
+
+    fn format_command(&mut self, words: &[&str]) -> String {
+        if !words.is_empty() {
+            match Notation::parse(words) {
+                Some(notation) => self.set_format(notation),
+                None => return format!("# bad format {}", words.join(" ")),
+            }
+        }
+        format!("# format {}", Notation::session())
+    }
+
+    fn decimal_command(&mut self, words: &[&str]) -> String {
+        match words {
+            [] => {}
+            ["on"] => self.set_decimal_literals(true),
+            ["off"] => self.set_decimal_literals(false),
+            ["scale", places] => match places.parse::<u32>() {
+                Ok(places) if places <= numbers::MAX_SCALE => self.set_scale(places),
+                _ => return format!("# bad scale {}", places),
+            },
+            ["rounding", mode] => match Rounding::parse(mode) {
+                Some(rounding) => self.set_rounding(rounding),
+                None => return format!("# bad rounding {}", mode),
+            },
+            _ => return format!("# bad decimal {}", words.join(" ")),
+        }
+        format!(
+            "# decimal {}, scale {}, rounding {}",
+            if Decimal::literals() { "on" } else { "off" },
+            Decimal::scale(),
+            Decimal::rounding()
+        )
+    }
+
+    fn strict_command(&mut self, words: &[&str]) -> String {
+        match words {
+            [] => {}
+            ["on"] => self.set_strict(true),
+            ["off"] => self.set_strict(false),
+            _ => return format!("# bad strict {}", words.join(" ")),
+        }
+        format!("# strict {}", if self.settings.strict { "on" } else { "off" })
+    }
+
+    /// Number of decimal digits of reals, `None` for plain `f64`.
+    fn set_precision(&mut self, digits: Option<usize>) {
+        self.settings.precision = digits;
+        self.settings.install()
+    }
+
+    /// Tolerance of `==` between reals, `None` for the default.
+    fn set_tolerance(&mut self, tolerance: Option<Tolerance>) {
+        self.settings.tolerance = tolerance;
+        self.settings.install()
+    }
+
+    /// Notation results are shown in.
+    fn set_format(&mut self, notation: Notation) {
+        self.settings.notation = notation;
+        self.settings.install()
+    }
+
+    /// Whether literals like `0.1` are decimals rather than reals.
+    fn set_decimal_literals(&mut self, literals: bool) {
+        self.settings.decimal_literals = literals;
+        self.settings.install()
+    }
+
+    /// Most digits after the point decimal results keep.
+    fn set_scale(&mut self, places: u32) {
+        self.settings.scale = places;
+        self.settings.install()
+    }
+
+    fn set_rounding(&mut self, rounding: Rounding) {
+        self.settings.rounding = rounding;
+        self.settings.install()
+    }
+
+    /// Whether infinities and NaN are errors rather than values.
+    fn set_strict(&mut self, strict: bool) {
+        self.settings.strict = strict;
+        self.settings.install()
+    }
+
     fn append_text<T: Into<String> + Clone>(&mut self, text: T) {
         self.parser.append_text(text)
     }
@@ -1095,6 +1882,7 @@ impl Interpreter {
 mod tests;
 #[macro_use]
 mod numbers;
+mod builtins;
 mod utils;
 
 fn main() -> std::io::Result<()> {
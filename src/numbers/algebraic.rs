@@ -0,0 +1,612 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Exact algebraic numbers built from radicals.
+//!
+//! A number is kept as a sum of rational multiples of surds rather than
+//! as a minimal polynomial with an isolating interval. Everything the
+//! language makes exact comes from `sqrt`, `cbrt` and `root` of rationals
+//! and the four operations on them, and for those numbers the sum is
+//! canonical: equality is comparing the terms, addition merges them and
+//! multiplication multiplies surds, with no resultants to compute and
+//! no intervals to refine before two numbers can be told apart. Both
+//! parts of the polynomial form are still there when asked for,
+//! `Algebraic::minimal_polynomial` finds the polynomial by linear algebra
+//! over the surds and `Algebraic::bounds` narrows the value down to any
+//! number of places. Roots of sums, which would need the general form,
+//! are given as reals.
+
+use super::theory::{factorize, iroot, is_prime, powers, trial_division};
+use super::{ArithmeticError, Integer, Long, Natural, Number, Operation, Rational, Real};
+use utils::gcd_nat;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Largest degree of the field of an algebraic number, results beyond it
+/// are given as reals.
+pub(crate) const MAX_DEGREE: usize = 64;
+
+/// Radicands are factored to find their surds: small primes are divided
+/// out and what is left must be a prime or at most this large.
+const MAX_COFACTOR_BITS: usize = 64;
+
+/// Real root `radicand^(1/index)` of a natural without `index`-th powers
+/// and with the smallest index, so that different surds are linearly
+/// independent over the rationals. The surd of index 1 is `1`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Surd {
+    index: u32,
+    radicand: Long,
+    /// Prime factors of the radicand and their multiplicities.
+    factors: Vec<(Long, u32)>,
+}
+
+/// Greatest common divisor of the indices and multiplicities of surds.
+fn gcd(a: u64, b: u64) -> u64 {
+    gcd_nat(Natural::new(a), Natural::new(b)).value().low_u64()
+}
+
+impl Surd {
+    fn one() -> Surd {
+        Surd {
+            index: 1,
+            radicand: Long::one(),
+            factors: Vec::new(),
+        }
+    }
+
+    /// `index`-th root of the product of `powers`, as the whole part
+    /// taken out of the root and the surd left.
+    fn reduce(index: u64, powers: BTreeMap<Long, u64>) -> (Long, Surd) {
+        let mut whole = Long::one();
+        let mut factors = Vec::new();
+        for (p, count) in powers {
+            whole = whole * p.pow((count / index) as u32);
+            if count % index != 0 {
+                factors.push((p, count % index));
+            }
+        }
+        let common = factors.iter().fold(index, |common, (_, count)| gcd(common, *count));
+        let factors: Vec<(Long, u32)> = factors
+            .into_iter()
+            .map(|(p, count)| (p, (count / common) as u32))
+            .collect();
+        let radicand = factors.iter().fold(Long::one(), |radicand, (p, count)| radicand * p.pow(*count));
+        let index = (index / common) as u32;
+        (whole, Surd { index, radicand, factors })
+    }
+
+    /// Product of two surds, with the whole part of it.
+    fn times(&self, other: &Surd) -> (Long, Surd) {
+        let index = self.index as u64 / gcd(self.index as u64, other.index as u64) * other.index as u64;
+        let mut powers = BTreeMap::new();
+        for surd in [self, other] {
+            for (p, count) in &surd.factors {
+                *powers.entry(p.clone()).or_insert(0) += *count as u64 * (index / surd.index as u64);
+            }
+        }
+        Surd::reduce(index, powers)
+    }
+
+    /// Smallest index of a root of each prime of the radicand.
+    fn degrees(&self) -> impl Iterator<Item = (&Long, u64)> {
+        let index = self.index as u64;
+        self.factors
+            .iter()
+            .map(move |(p, count)| (p, index / gcd(index, *count as u64)))
+    }
+
+    /// Floor and ceiling of the surd times `10^places`.
+    fn bounds(&self, places: u32) -> (Long, Long) {
+        let scale = Long::from(10).pow(places);
+        if self.index == 1 {
+            return (scale.clone(), scale);
+        }
+        let floor = iroot(&(&self.radicand * &scale.pow(self.index)), self.index);
+        let ceil = &floor + &Long::one();
+        (floor, ceil)
+    }
+}
+
+/// `sqrt(2)`, `cbrt(5)`, `root(7, 4)`, and roots of different indices
+/// multiplied, `sqrt(2)*cbrt(5)`.
+impl Display for Surd {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut radicands: BTreeMap<u64, Long> = BTreeMap::new();
+        for ((p, count), (_, index)) in self.factors.iter().zip(self.degrees()) {
+            let power = p.pow((*count as u64 * index / self.index as u64) as u32);
+            let radicand = radicands.entry(index).or_insert_with(Long::one);
+            *radicand = &*radicand * &power;
+        }
+        let roots: Vec<String> = radicands
+            .into_iter()
+            .map(|(index, radicand)| match index {
+                2 => format!("sqrt({})", radicand),
+                3 => format!("cbrt({})", radicand),
+                _ => format!("root({}, {})", radicand, index),
+            })
+            .collect();
+        roots.join("*").fmt(f)
+    }
+}
+
+/// Exact real algebraic number, a sum of rational multiples of surds like
+/// `1 + 2*sqrt(3)`.
+///
+/// Distinct surds are linearly independent, so a number has just one
+/// such sum and equal numbers are equal sums; the minimal polynomial is
+/// found when it is needed, and so are rational bounds of the value to
+/// any number of places.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Algebraic {
+    terms: BTreeMap<Surd, Rational>,
+}
+
+impl Algebraic {
+    fn from_terms(terms: BTreeMap<Surd, Rational>) -> Algebraic {
+        let zero = Rational::default();
+        Algebraic {
+            terms: terms.into_iter().filter(|(_, coefficient)| *coefficient != zero).collect(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// The value when it is rational.
+    pub fn to_rational(&self) -> Option<Rational> {
+        match self.terms.iter().next() {
+            None => Some(Rational::default()),
+            Some((surd, coefficient)) if self.terms.len() == 1 && surd.index == 1 => Some(coefficient.clone()),
+            _ => None,
+        }
+    }
+
+    /// Degree of the field generated by the surds, a bound of the degree
+    /// of the number itself.
+    pub fn degree(&self) -> usize {
+        let mut degrees: BTreeMap<&Long, u64> = BTreeMap::new();
+        for surd in self.terms.keys() {
+            for (p, index) in surd.degrees() {
+                let degree = degrees.entry(p).or_insert(1);
+                *degree = *degree / gcd(*degree, index) * index;
+            }
+        }
+        degrees
+            .values()
+            .fold(1usize, |degree, index| degree.saturating_mul(*index as usize))
+    }
+
+    pub(crate) fn bits(&self) -> usize {
+        self.terms
+            .iter()
+            .map(|(surd, coefficient)| {
+                surd.radicand.bits() + coefficient.numer.value().bits() + coefficient.denom.value().bits()
+            })
+            .sum()
+    }
+
+    /// `index`-th real root of a multiple of one surd, `None` for sums,
+    /// for even roots of negative numbers and when the radicand can't be
+    /// factored.
+    pub fn root(&self, index: u32) -> Option<Algebraic> {
+        if self.is_zero() {
+            return Some(self.clone());
+        }
+        if index == 0 || self.terms.len() > 1 {
+            return None;
+        }
+        let (surd, coefficient) = self.terms.iter().next().unwrap();
+        let negative = coefficient.numer.value().is_negative();
+        if negative && index.is_multiple_of(2) {
+            return None;
+        }
+        // (a / b)^(1/k) is (a * b^(k - 1))^(1/k) / b
+        let (numer, denom) = (coefficient.numer.value().abs(), coefficient.denom.value());
+        // and the root of the surd has the index `index * surd.index`
+        let (index, inner) = (index as u64, surd.index as u64);
+        let mut counts = BTreeMap::new();
+        for (p, count) in factors(&numer)? {
+            *counts.entry(p).or_insert(0) += count as u64 * inner;
+        }
+        for (p, count) in factors(&denom)? {
+            *counts.entry(p).or_insert(0) += count as u64 * (index - 1) * inner;
+        }
+        for (p, count) in &surd.factors {
+            *counts.entry(p.clone()).or_insert(0) += *count as u64;
+        }
+        let (whole, root) = Surd::reduce(index * inner, counts);
+        let coefficient = Rational::new(Integer::new(whole), Integer::new(denom));
+        let root = Algebraic::from_terms(BTreeMap::from([(root, coefficient)]));
+        Some(if negative { -root } else { root })
+    }
+
+    /// Rational bounds of the value to `places` decimal places.
+    pub fn bounds(&self, places: u32) -> (Rational, Rational) {
+        let scale = Integer::new(Long::from(10).pow(places));
+        let (mut lo, mut hi) = (Rational::default(), Rational::default());
+        for (surd, coefficient) in &self.terms {
+            let (floor, ceil) = surd.bounds(places);
+            let floor = coefficient.clone() * Rational::new(Integer::new(floor), scale.clone());
+            let ceil = coefficient.clone() * Rational::new(Integer::new(ceil), scale.clone());
+            if coefficient.numer.value().is_negative() {
+                lo += ceil;
+                hi += floor;
+            } else {
+                lo += floor;
+                hi += ceil;
+            }
+        }
+        (lo, hi)
+    }
+
+    /// `f` of the value, found as `f` of bounds close enough to agree.
+    /// `f` must be constant on an interval around an irrational value.
+    pub fn refined<T: PartialEq, F: Fn(&Rational) -> T>(&self, f: F) -> T {
+        if let Some(rat) = self.to_rational() {
+            return f(&rat);
+        }
+        let mut places = 16;
+        loop {
+            let (lo, hi) = self.bounds(places);
+            let value = f(&lo);
+            if value == f(&hi) {
+                return value;
+            }
+            places *= 2;
+        }
+    }
+
+    pub fn signum(&self) -> Ordering {
+        if self.is_zero() {
+            return Ordering::Equal;
+        }
+        self.refined(|bound| bound.cmp(&Rational::default()))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.signum() == Ordering::Less
+    }
+
+    pub fn abs(&self) -> Algebraic {
+        if self.is_negative() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Largest integer not above `self`.
+    pub fn floor(&self) -> Integer {
+        match self.to_rational() {
+            Some(rat) => rat.floor(),
+            None => self.refined(Rational::floor),
+        }
+    }
+
+    /// Integer part, rounded toward zero.
+    pub fn trunc(&self) -> Integer {
+        match self.to_rational() {
+            Some(rat) => rat.trunc(),
+            None => self.refined(Rational::trunc),
+        }
+    }
+
+    /// Rational within `10^-digits` of the value relative to it.
+    pub fn approximate(&self, digits: u32) -> Rational {
+        let mut places = digits + 2;
+        loop {
+            let (lo, hi) = self.bounds(places);
+            let width = (hi.clone() - lo.clone()) * Rational::new(Integer::new(Long::from(10).pow(digits)), 1);
+            if self.is_zero() || width <= lo.clone() || -width >= hi.clone() {
+                return (lo + hi) / Rational::new(2, 1);
+            }
+            places *= 2;
+        }
+    }
+
+    /// First `count` terms of the continued fraction, the ones shared by
+    /// rational bounds on both sides.
+    pub fn continued_fraction(&self, count: usize) -> Vec<Integer> {
+        if let Some(rat) = self.to_rational() {
+            return rat.continued_fraction(count);
+        }
+        let mut places = 16;
+        loop {
+            let (lo, hi) = self.bounds(places);
+            // the last term of a finite fraction may also be written as
+            // a term one less and a `1`, so it is not shared
+            let (mut lo, mut hi) = (lo.continued_fraction(count + 1), hi.continued_fraction(count + 1));
+            lo.pop();
+            hi.pop();
+            let shared = lo.iter().zip(hi.iter()).take_while(|(a, b)| a == b).count();
+            if shared >= count {
+                lo.truncate(count);
+                return lo;
+            }
+            places *= 2;
+        }
+    }
+
+    /// Powers `1, x, x^2, ...` up to the first one that is a rational
+    /// combination of the ones before, and the combination: the monic
+    /// minimal polynomial, lowest coefficient first.
+    fn minimal(&self) -> (Vec<Algebraic>, Vec<Rational>) {
+        let mut powers = vec![Algebraic::from(rational!(1))];
+        // echelon form of the powers so far, each row with its pivot and
+        // the combination of powers it stands for
+        let mut rows: Vec<(Surd, Algebraic, Vec<Rational>)> = Vec::new();
+        loop {
+            let degree = powers.len() - 1;
+            let mut vector = powers[degree].clone();
+            let mut combination = vec![Rational::default(); degree + 1];
+            combination[degree] = rational!(1);
+            for (pivot, row, row_combination) in &rows {
+                if let Some(coefficient) = vector.terms.get(pivot) {
+                    let factor = coefficient.clone() / row.terms[pivot].clone();
+                    vector -= row.clone() * Algebraic::from(factor.clone());
+                    for (entry, term) in combination.iter_mut().zip(row_combination) {
+                        *entry = entry.clone() - factor.clone() * term.clone();
+                    }
+                }
+            }
+            if vector.is_zero() {
+                return (powers, combination);
+            }
+            let pivot = vector.terms.keys().next().unwrap().clone();
+            rows.push((pivot, vector, combination));
+            powers.push(powers[degree].clone() * self.clone());
+        }
+    }
+
+    /// Minimal polynomial with coprime integer coefficients and a positive
+    /// leading one, highest coefficient first.
+    pub fn minimal_polynomial(&self) -> Vec<Integer> {
+        let (_, polynomial) = self.minimal();
+        let denom = polynomial.iter().fold(Long::one(), |denom, coefficient| {
+            let value = coefficient.denom.value();
+            let common = gcd_nat(Natural::new(denom.clone()), Natural::new(value.clone())).value();
+            &denom / &common * value
+        });
+        let scale = Rational::new(Integer::new(denom), 1);
+        let integers: Vec<Long> = polynomial
+            .into_iter()
+            .rev()
+            .map(|coefficient| (coefficient * scale.clone()).numer.value())
+            .collect();
+        let content = integers.iter().fold(Long::zero(), |content, value| {
+            gcd_nat(Natural::new(content), Natural::new(value.abs())).value()
+        });
+        integers
+            .into_iter()
+            .map(|value| Integer::new(&value / &content))
+            .collect()
+    }
+
+    /// `1 / self` from the minimal polynomial: when
+    /// `x^n + ... + a1 x + a0 = 0`, then `1 / x = -(x^(n-1) + ... + a1) / a0`.
+    pub fn inv(&self) -> Algebraic {
+        let (powers, polynomial) = self.minimal();
+        let mut sum = Algebraic::from(Rational::default());
+        for (power, coefficient) in powers.into_iter().zip(polynomial.iter().skip(1)) {
+            sum += power * Algebraic::from(coefficient.clone());
+        }
+        sum * Algebraic::from(-polynomial[0].inv())
+    }
+}
+
+/// Prime factors of a positive `n` and their multiplicities, `None` when
+/// they are out of reach.
+fn factors(n: &Long) -> Option<Vec<(Long, u32)>> {
+    let (mut factors, rest) = trial_division(n);
+    if rest.bits() > MAX_COFACTOR_BITS && !is_prime(&rest) {
+        return None;
+    }
+    factors.extend(factorize(&rest)?);
+    factors.sort();
+    Some(powers(factors))
+}
+
+/// Terms in the order of their surds, the rational one first, with
+/// fractions after the surds, `1 / 2 + 3*sqrt(5) / 2`.
+impl Display for Algebraic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return "0".fmt(f);
+        }
+        for (position, (surd, coefficient)) in self.terms.iter().enumerate() {
+            let negative = coefficient.numer.value().is_negative();
+            match (position, negative) {
+                (0, true) => "-".fmt(f)?,
+                (0, false) => {}
+                (_, true) => " - ".fmt(f)?,
+                (_, false) => " + ".fmt(f)?,
+            }
+            let (numer, denom) = (coefficient.numer.value().abs(), coefficient.denom.value());
+            if surd.index == 1 {
+                numer.fmt(f)?;
+            } else if numer == Long::one() {
+                surd.fmt(f)?;
+            } else {
+                write!(f, "{}*{}", numer, surd)?;
+            }
+            if denom != Long::one() {
+                write!(f, " / {}", denom)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Rational> for Algebraic {
+    fn from(rat: Rational) -> Algebraic {
+        Algebraic::from_terms(BTreeMap::from([(Surd::one(), rat)]))
+    }
+}
+
+/// Midpoint of bounds in the session precision.
+impl From<Algebraic> for Real {
+    fn from(alg: Algebraic) -> Real {
+        let digits = Real::precision().unwrap_or(17) as u32 + 2;
+        Real::from(alg.approximate(digits))
+    }
+}
+
+impl Neg for Algebraic {
+    type Output = Algebraic;
+
+    fn neg(self) -> Algebraic {
+        Algebraic::from_terms(self.terms.into_iter().map(|(surd, coefficient)| (surd, -coefficient)).collect())
+    }
+}
+
+impl Add for Algebraic {
+    type Output = Algebraic;
+
+    fn add(self, other: Algebraic) -> Algebraic {
+        let mut terms = self.terms;
+        for (surd, coefficient) in other.terms {
+            let sum = match terms.remove(&surd) {
+                Some(term) => term + coefficient,
+                None => coefficient,
+            };
+            terms.insert(surd, sum);
+        }
+        Algebraic::from_terms(terms)
+    }
+}
+
+impl Sub for Algebraic {
+    type Output = Algebraic;
+
+    fn sub(self, other: Algebraic) -> Algebraic {
+        self + -other
+    }
+}
+
+impl Mul for Algebraic {
+    type Output = Algebraic;
+
+    fn mul(self, other: Algebraic) -> Algebraic {
+        let mut product = Algebraic::from_terms(BTreeMap::new());
+        for (a, x) in &self.terms {
+            for (b, y) in &other.terms {
+                let (whole, surd) = a.times(b);
+                let coefficient = x.clone() * y.clone() * Rational::new(Integer::new(whole), 1);
+                product += Algebraic::from_terms(BTreeMap::from([(surd, coefficient)]));
+            }
+        }
+        product
+    }
+}
+
+impl Div for Algebraic {
+    type Output = Algebraic;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Algebraic) -> Algebraic {
+        self * other.inv()
+    }
+}
+
+impl Number {
+    pub(crate) fn is_algebraic(&self) -> bool {
+        matches!(self, Number::Algebraic(_))
+    }
+
+    /// Exact real value of naturals, integers, decimals, rationals and
+    /// algebraic numbers.
+    pub(crate) fn to_algebraic(&self) -> Option<Algebraic> {
+        match self {
+            Number::Algebraic(alg) => Some(alg.clone()),
+            number => number.to_rational().map(Algebraic::from),
+        }
+    }
+
+    /// Exact real `index`-th root of an exact number, when it is not of
+    /// too high a degree.
+    pub(crate) fn exact_root(&self, index: u32) -> Option<Number> {
+        let root = self.to_algebraic()?.root(index)?;
+        if root.degree() > MAX_DEGREE {
+            return None;
+        }
+        Some(Number::Algebraic(root))
+    }
+
+    /// Real `index`-th root, exact for exact numbers; an even root of a
+    /// negative number is the principal complex value.
+    pub fn root(self, index: Number) -> Result<Number, ArithmeticError> {
+        let index = match index {
+            Number::Natural(ref nat) if !nat.value().is_zero() && nat.value() <= Long::from(MAX_DEGREE) => {
+                nat.value().low_u64() as u32
+            }
+            _ => {
+                return Err(ArithmeticError::Domain(format!(
+                    "index of a root must be a natural number from 1 to {}",
+                    MAX_DEGREE
+                )))
+            }
+        };
+        match index {
+            2 => return self.sqrt(),
+            3 => return self.cbrt(),
+            _ => {}
+        }
+        if let Some(root) = self.exact_root(index) {
+            return Ok(root);
+        }
+        if (self.to_real().is_some() || self.is_dual()) && self.is_negative() && index % 2 == 1 {
+            return Ok(-(-self).root(Number::Natural(natural!(index)))?);
+        }
+        self.checked_pow(Number::Rational(Rational::new(1, index)))
+    }
+
+    /// Minimal polynomial of an exact real number, highest coefficient
+    /// first.
+    pub fn minimal_polynomial(self) -> Result<Vec<Number>, ArithmeticError> {
+        match self.to_algebraic() {
+            Some(alg) => Ok(alg.minimal_polynomial().into_iter().map(Number::Integer).collect()),
+            None => Err(ArithmeticError::Domain(format!(
+                "minimal polynomial is not defined for {} numbers",
+                self.kind()
+            ))),
+        }
+    }
+}
+
+/// Whole power of an algebraic base, a negative one divides.
+pub(crate) fn algebraic_pow(base: Algebraic, exp: Long, max_bits: usize) -> Result<Number, ArithmeticError> {
+    if exp.bits() > 31 || base.bits().saturating_mul(exp.abs().low_u64() as usize) > max_bits {
+        return Err(ArithmeticError::Overflow {
+            kind: "Algebraic",
+            operation: Operation::Power,
+        });
+    }
+    let (mut result, mut base) = (Algebraic::from(rational!(1)), base);
+    let mut magnitude = exp.abs().low_u64();
+    while magnitude > 0 {
+        if magnitude & 1 == 1 {
+            result *= base.clone();
+        }
+        magnitude >>= 1;
+        if magnitude > 0 {
+            base = base.clone() * base;
+        }
+    }
+    Ok(Number::Algebraic(if exp.is_negative() { result.inv() } else { result }))
+}
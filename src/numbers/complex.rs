@@ -12,13 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::Real;
+use super::{ArithmeticError, Real};
 
 use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Complex {
     pub(crate) real: Real,
     pub(crate) imag: Real,
@@ -36,16 +36,167 @@ impl Complex {
         }
     }
 
+    /// Complex infinity, the one point beyond all complex numbers: a
+    /// direction is not kept, so that `inf * 1i` is not `nan + inf i`.
+    pub fn infinity() -> Complex {
+        Complex::new(f64::INFINITY, f64::INFINITY)
+    }
+
+    /// Infinite when either part is, even if the other one is NaN.
+    pub fn is_infinite(&self) -> bool {
+        self.real.value().is_infinite() || self.imag.value().is_infinite()
+    }
+
+    /// `r * (cos(theta) + i sin(theta))`
+    pub fn from_polar<U, V>(modulus: U, angle: V) -> Complex
+    where
+        U: Into<Real>,
+        V: Into<Real>,
+    {
+        let (modulus, angle) = (modulus.into(), angle.into());
+        Complex::new(modulus.clone() * angle.cos(), modulus * angle.sin())
+    }
+
+    /// Modulus `|z|`.
     pub fn abs(&self) -> Real {
-        self.real * self.real + self.imag * self.imag
+        self.real.hypot(&self.imag)
+    }
+
+    /// Squared modulus `re^2 + im^2`, exact where `abs` rounds.
+    pub fn norm_sqr(&self) -> Real {
+        self.real.clone() * self.real.clone() + self.imag.clone() * self.imag.clone()
+    }
+
+    /// Principal argument, in `(-pi, pi]`, NaN for complex infinity.
+    pub fn arg(&self) -> Real {
+        if self.is_infinite() {
+            return Real::new(f64::NAN);
+        }
+        self.imag.atan2(&self.real)
     }
 
     pub fn inv(&self) -> Complex {
-        Complex::new(Real::new(1.), Real::new(0.)) / *self
+        Complex::new(Real::new(1.), Real::new(0.)) / self.clone()
     }
 
     pub fn conj(&self) -> Complex {
-        Complex::new(self.real, -self.imag)
+        Complex::new(self.real.clone(), -self.imag.clone())
+    }
+
+    /// Principal value of `self^exp`, `exp(exp * ln(self))`.
+    pub fn pow(&self, exp: &Complex) -> Complex {
+        if self.real.is_zero() && self.imag.is_zero() {
+            return Complex::new(0, 0);
+        }
+        (exp.clone() * self.ln()).exp()
+    }
+
+    fn parts(&self) -> (Real, Real) {
+        (self.real.clone(), self.imag.clone())
+    }
+
+    pub fn exp(&self) -> Complex {
+        let (a, b) = self.parts();
+        Complex::from_polar(a.exp(), b)
+    }
+
+    /// Principal logarithm, the imaginary part lies in `(-pi, pi]`.
+    pub fn ln(&self) -> Complex {
+        Complex::new(self.abs().ln(), self.arg())
+    }
+
+    /// Principal square root, the real part is non-negative.
+    pub fn sqrt(&self) -> Complex {
+        let (a, b) = self.parts();
+        let modulus = self.abs();
+        let real = ((modulus.clone() + a.clone()) / Real::new(2)).sqrt_abs();
+        let imag = ((modulus - a) / Real::new(2)).sqrt_abs();
+        Complex::new(real, if b.is_sign_negative() { -imag } else { imag })
+    }
+
+    /// Principal cube root.
+    pub fn cbrt(&self) -> Complex {
+        if self.real.is_zero() && self.imag.is_zero() {
+            return self.clone();
+        }
+        (self.ln() / Complex::new(3, 0)).exp()
+    }
+
+    pub fn sin(&self) -> Complex {
+        let (a, b) = self.parts();
+        Complex::new(a.sin() * b.cosh(), a.cos() * b.sinh())
+    }
+
+    pub fn cos(&self) -> Complex {
+        let (a, b) = self.parts();
+        Complex::new(a.cos() * b.cosh(), -a.sin() * b.sinh())
+    }
+
+    pub fn tan(&self) -> Complex {
+        self.sin() / self.cos()
+    }
+
+    pub fn sinh(&self) -> Complex {
+        let (a, b) = self.parts();
+        Complex::new(a.sinh() * b.cos(), a.cosh() * b.sin())
+    }
+
+    pub fn cosh(&self) -> Complex {
+        let (a, b) = self.parts();
+        Complex::new(a.cosh() * b.cos(), a.sinh() * b.sin())
+    }
+
+    pub fn tanh(&self) -> Complex {
+        self.sinh() / self.cosh()
+    }
+
+    /// `-i ln(iz + sqrt(1 - z^2))`
+    pub fn asin(&self) -> Complex {
+        let i = Complex::new(0, 1);
+        let one = Complex::new(1, 0);
+        let root = (one - self.clone() * self.clone()).sqrt();
+        -(i.clone() * (i * self.clone() + root).ln())
+    }
+
+    /// `pi/2 - asin(z)`
+    pub fn acos(&self) -> Complex {
+        Complex::new(Real::pi() / Real::new(2), 0) - self.asin()
+    }
+
+    /// `i/2 (ln(1 - iz) - ln(1 + iz))`
+    pub fn atan(&self) -> Complex {
+        let i = Complex::new(0, 1);
+        let one = Complex::new(1, 0);
+        let iz = i * self.clone();
+        let diff = (one.clone() - iz.clone()).ln() - (one + iz).ln();
+        Complex::new(0, 0.5) * diff
+    }
+
+    /// `ln(z + sqrt(z^2 + 1))`
+    pub fn asinh(&self) -> Complex {
+        let one = Complex::new(1, 0);
+        (self.clone() + (self.clone() * self.clone() + one).sqrt()).ln()
+    }
+
+    /// `ln(z + sqrt(z + 1) sqrt(z - 1))`
+    pub fn acosh(&self) -> Complex {
+        let one = Complex::new(1, 0);
+        let root = (self.clone() + one.clone()).sqrt() * (self.clone() - one).sqrt();
+        (self.clone() + root).ln()
+    }
+
+    /// `(ln(1 + z) - ln(1 - z)) / 2`. On the cuts beyond `+-1` the value
+    /// is the limit from above, as in C99 `catanh`, so both `atanh(2)` and
+    /// `atanh(-2)` have the imaginary part `pi/2`.
+    pub fn atanh(&self) -> Complex {
+        let one = Complex::new(1, 0);
+        if self.imag.is_zero() && self.real.abs() > Real::new(1) {
+            let x = self.real.clone();
+            let ratio = (x.clone() + Real::new(1)) / (x - Real::new(1));
+            return Complex::new(ratio.ln() / Real::new(2), Real::pi() / Real::new(2));
+        }
+        let diff = (one.clone() + self.clone()).ln() - (one - self.clone()).ln();
+        Complex::new(0.5, 0) * diff
     }
 
     pub fn is_real(&self) -> bool {
@@ -55,27 +206,38 @@ impl Complex {
 
 impl Display for Complex {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_infinite() {
+            return "complex infinity".fmt(f);
+        }
         self.real.fmt(f)?;
         if self.imag >= Real::zero() {
             " + ".fmt(f)?;
             self.imag.fmt(f)?;
         } else {
             " - ".fmt(f)?;
-            (-self.imag).fmt(f)?;
+            (-self.imag.clone()).fmt(f)?;
         }
         "i".fmt(f)
     }
 }
 
-impl PartialOrd for Complex {
-    fn partial_cmp(&self, other: &Complex) -> Option<Ordering> {
-        if self.real == other.real {
-            return self.imag.partial_cmp(&other.imag);
+impl Complex {
+    /// Complex numbers are not ordered, only equal ones and ones on the
+    /// real line can be compared.
+    pub fn checked_cmp(&self, other: &Complex) -> Result<Ordering, ArithmeticError> {
+        if self == other {
+            return Ok(Ordering::Equal);
         }
-        if self.imag == other.imag {
-            return self.real.partial_cmp(&other.real);
+        match (self.imag.is_zero() && other.imag.is_zero(), self.real.partial_cmp(&other.real)) {
+            (true, Some(ordering)) => Ok(ordering),
+            _ => Err(ArithmeticError::Domain("cannot order complex numbers".to_owned())),
         }
-        return None;
+    }
+}
+
+impl PartialOrd for Complex {
+    fn partial_cmp(&self, other: &Complex) -> Option<Ordering> {
+        self.checked_cmp(other).ok()
     }
 }
 
@@ -114,7 +276,7 @@ impl Mul for Complex {
 
     fn mul(self, other: Complex) -> Complex {
         Complex {
-            real: self.real * other.real - self.imag * other.imag,
+            real: self.real.clone() * other.real.clone() - self.imag.clone() * other.imag.clone(),
             imag: self.imag * other.real + other.imag * self.real,
         }
     }
@@ -124,9 +286,11 @@ impl Div for Complex {
     type Output = Complex;
 
     fn div(self, other: Complex) -> Complex {
+        let numer = self * other.conj();
+        let denom = other.norm_sqr();
         Complex {
-            real: (self * other.conj()).real / (other.abs()),
-            imag: (self * other.conj()).imag / (other.abs()),
+            real: numer.real / denom.clone(),
+            imag: numer.imag / denom,
         }
     }
 }
@@ -146,7 +310,7 @@ macro_rules! complex {
         $crate::numbers::Complex::new(-$a as f64, -$b as f64)
     };
     ($a:tt) => {
-        $crate::numbers::Complex::new($a as f64, 0 as f64)
+        $crate::numbers::Complex::new($a, 0)
     };
     (- $a:tt) => {
         $crate::numbers::Complex::new(-$a as f64, 0 as f64)
@@ -157,6 +321,30 @@ macro_rules! complex {
     (- $b:tt.i) => {
         $crate::numbers::Complex::new(0 as f64, -$b as f64)
     };
+    (exact $($a:tt)/+ + $($b:tt)/+ .i) => {
+        $crate::numbers::Gaussian::new(rational!($($a)/+), rational!($($b)/+))
+    };
+    (exact $($a:tt)/+ - $($b:tt)/+ .i) => {
+        $crate::numbers::Gaussian::new(rational!($($a)/+), -rational!($($b)/+))
+    };
+    (exact - $($a:tt)/+ + $($b:tt)/+ .i) => {
+        $crate::numbers::Gaussian::new(-rational!($($a)/+), rational!($($b)/+))
+    };
+    (exact - $($a:tt)/+ - $($b:tt)/+ .i) => {
+        $crate::numbers::Gaussian::new(-rational!($($a)/+), -rational!($($b)/+))
+    };
+    (exact $($b:tt)/+ .i) => {
+        $crate::numbers::Gaussian::new(rational!(0), rational!($($b)/+))
+    };
+    (exact - $($b:tt)/+ .i) => {
+        $crate::numbers::Gaussian::new(rational!(0), -rational!($($b)/+))
+    };
+    (exact $($a:tt)/+) => {
+        $crate::numbers::Gaussian::new(rational!($($a)/+), rational!(0))
+    };
+    (exact - $($a:tt)/+) => {
+        $crate::numbers::Gaussian::new(-rational!($($a)/+), rational!(0))
+    };
 }
 
 impl_default! { Complex, complex!(0) }
@@ -12,12 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Complex, Integer, Natural, Rational, Real};
+use super::{Algebraic, Complex, Decimal, Float, Gaussian, Integer, Interval, Natural, Rational, Real, Uncertain};
 
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
 
-use std::convert::Into;
-
 macro_rules! impl_cross_type_ops {
     ($(($lhs:ty; $rhs:ty))*) => {
         $(
@@ -70,7 +68,7 @@ macro_rules! impl_cross_type_assign_ops {
             fn $meth(&mut self, other: $rhs) {
                 let other: $lhs = other.into();
 
-                *self = *self $op other;
+                *self = self.clone() $op other;
             }
         }
     }
@@ -83,9 +81,9 @@ macro_rules! impl_cross_types_into {
         )*
     };
     (@impl $rhs:ty; @for $lhs:ty; @macro $mcr:ident) => {
-        impl Into<$lhs> for $rhs {
-            fn into(self) -> $lhs {
-                let val = self.value();
+        impl From<$rhs> for $lhs {
+            fn from(from: $rhs) -> $lhs {
+                let val = from.value();
                 $mcr!(val)
             }
         }
@@ -106,12 +104,145 @@ macro_rules! impl_ops {
 
 impl_ops! { (Natural; Integer; integer) (Natural; Rational; rational) (Natural; Real; real) }
 impl_ops! { (Natural; Complex; complex) (Integer; Rational; rational) (Integer; Real; real) }
-impl_ops! { (Integer; Complex; complex) (Rational; Real; real) (Rational; Complex; complex) }
-impl_ops! { (Real; Complex; complex) }
+impl_ops! { (Integer; Complex; complex) (Real; Complex; complex) }
+
+// `Rational::value` is an `f64`, so rationals are divided out in the
+// session precision instead of going through `impl_cross_types_into!`.
+impl_cross_type_ops! { (Rational; Real) (Rational; Complex) }
+impl_cross_type_assign_ops! { (Rational; Real) (Rational; Complex) }
+
+impl From<Rational> for Real {
+    fn from(rat: Rational) -> Real {
+        match Real::precision() {
+            Some(digits) => Real::from(Float::from_ratio(rat.numer.value(), rat.denom.value(), digits)),
+            None => Real::new(rat.value()),
+        }
+    }
+}
+
+impl From<Rational> for Complex {
+    fn from(rat: Rational) -> Complex {
+        complex!(rat)
+    }
+}
+
+// Exact numbers keep complex arithmetic exact, a real turns it into `Complex`.
+impl_cross_type_ops! { (Natural; Gaussian) (Integer; Gaussian) (Rational; Gaussian) (Gaussian; Complex) }
+impl_cross_type_ops! {
+    @impl Real; @for Gaussian; @ret Complex; @ops ((Add; add; +); (Sub; sub; -); (Mul; mul; *); (Div; div; /))
+}
+impl_cross_type_ops! {
+    @impl Gaussian; @for Real; @ret Complex; @ops ((Add; add; +); (Sub; sub; -); (Mul; mul; *); (Div; div; /))
+}
+
+impl From<Natural> for Gaussian {
+    fn from(nat: Natural) -> Gaussian {
+        Gaussian::new(nat, rational!(0))
+    }
+}
+
+impl From<Integer> for Gaussian {
+    fn from(int: Integer) -> Gaussian {
+        Gaussian::new(int, rational!(0))
+    }
+}
+
+impl From<Rational> for Gaussian {
+    fn from(rat: Rational) -> Gaussian {
+        Gaussian::new(rat, rational!(0))
+    }
+}
+
+impl From<Gaussian> for Complex {
+    fn from(gau: Gaussian) -> Complex {
+        Complex::new(Real::from(gau.real), Real::from(gau.imag))
+    }
+}
+
+// Intervals enclose exact numbers, so rationals are rounded outward
+// instead of going through `impl_cross_types_into!`.
+impl_ops! { (Natural; Interval; interval) (Integer; Interval; interval) (Real; Interval; interval) }
+impl_cross_type_ops! { (Rational; Interval) }
+impl_cross_type_assign_ops! { (Rational; Interval) }
+
+// Decimals stay decimal with whole numbers and give way to any other
+// number, `Decimal::value` is the exact rational.
+impl_ops! { (Natural; Decimal; decimal) (Integer; Decimal; decimal) (Decimal; Real; real) }
+impl_ops! { (Decimal; Complex; complex) (Decimal; Interval; interval) (Decimal; Uncertain; uncertain) }
+impl_cross_type_ops! { (Decimal; Rational) (Decimal; Gaussian) }
+impl_cross_type_assign_ops! { (Decimal; Rational) }
+
+impl From<Decimal> for Rational {
+    fn from(dec: Decimal) -> Rational {
+        dec.to_rational()
+    }
+}
+
+impl From<Decimal> for Gaussian {
+    fn from(dec: Decimal) -> Gaussian {
+        Gaussian::new(dec.to_rational(), rational!(0))
+    }
+}
+
+// Exact numbers stay exact with algebraic ones, anything else takes in
+// their value in the session precision.
+impl_cross_type_ops! { (Natural; Algebraic) (Integer; Algebraic) (Decimal; Algebraic) (Rational; Algebraic) }
+impl_cross_type_ops! { (Algebraic; Real) (Algebraic; Complex) (Algebraic; Interval) (Algebraic; Uncertain) }
+impl_cross_type_ops! {
+    @impl Algebraic; @for Gaussian; @ret Complex; @ops ((Add; add; +); (Sub; sub; -); (Mul; mul; *); (Div; div; /))
+}
+impl_cross_type_ops! {
+    @impl Gaussian; @for Algebraic; @ret Complex; @ops ((Add; add; +); (Sub; sub; -); (Mul; mul; *); (Div; div; /))
+}
+
+impl From<Natural> for Algebraic {
+    fn from(nat: Natural) -> Algebraic {
+        let rat: Rational = nat.into();
+        Algebraic::from(rat)
+    }
+}
+
+impl From<Integer> for Algebraic {
+    fn from(int: Integer) -> Algebraic {
+        let rat: Rational = int.into();
+        Algebraic::from(rat)
+    }
+}
+
+impl From<Decimal> for Algebraic {
+    fn from(dec: Decimal) -> Algebraic {
+        Algebraic::from(dec.to_rational())
+    }
+}
+
+impl From<Algebraic> for Complex {
+    fn from(alg: Algebraic) -> Complex {
+        Complex::new(Real::from(alg), 0)
+    }
+}
+
+/// Bounds of the value rounded outward.
+impl From<Algebraic> for Interval {
+    fn from(alg: Algebraic) -> Interval {
+        let (lo, hi) = alg.bounds(20);
+        Interval::new(Interval::from(lo).lo(), Interval::from(hi).hi())
+    }
+}
+
+impl From<Algebraic> for Uncertain {
+    fn from(alg: Algebraic) -> Uncertain {
+        Uncertain::from(Real::from(alg).value())
+    }
+}
+
+// A real number is a measurement without error.
+impl_ops! { (Natural; Uncertain; uncertain) (Integer; Uncertain; uncertain) (Real; Uncertain; uncertain) }
+impl_cross_type_ops! { (Rational; Uncertain) }
+impl_cross_type_assign_ops! { (Rational; Uncertain) }
 
 impl_cross_type_ops! { @impl Integer; @for Natural; @ret Integer; @op Rem; rem; % }
 impl_cross_type_ops! { @impl Natural; @for Integer; @ret Integer; @op Rem; rem; % }
 
 impl_cross_type_assign_ops! { @impl Natural; @for Integer; @op RemAssign; rem_assign; % }
 
-impl_type_assign_ops! { Natural Integer Rational Real Complex }
+impl_type_assign_ops! { Natural Integer Decimal Rational Algebraic Real Complex Gaussian Interval Uncertain }
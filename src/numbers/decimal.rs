@@ -0,0 +1,422 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Integer, Long, Number, Operation, Rational};
+
+use std::cell::Cell;
+use std::cmp::{max, Ordering};
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Exact base-10 number `unscaled / 10^scale`, the scale is the number of
+/// digits after the point and is kept, so `1.50` is not `1.5`.
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    unscaled: Long,
+    scale: u32,
+}
+
+/// How a decimal loses the digits beyond its scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// To the nearest, ties to the even digit.
+    HalfEven,
+    /// To the nearest, ties away from zero.
+    HalfUp,
+    /// Toward zero.
+    Down,
+}
+
+/// Most digits after the point a result keeps unless the session
+/// asks for another scale.
+pub const DEFAULT_SCALE: u32 = 20;
+
+/// Largest scale a session may ask for.
+pub const MAX_SCALE: u32 = 1000;
+
+thread_local! {
+    static SCALE: Cell<u32> = const { Cell::new(DEFAULT_SCALE) };
+    static ROUNDING: Cell<Rounding> = const { Cell::new(Rounding::HalfEven) };
+    static LITERALS: Cell<bool> = const { Cell::new(false) };
+}
+
+impl Rounding {
+    pub fn parse(text: &str) -> Option<Rounding> {
+        match text {
+            "half-even" => Some(Rounding::HalfEven),
+            "half-up" => Some(Rounding::HalfUp),
+            "down" => Some(Rounding::Down),
+            _ => None,
+        }
+    }
+
+    /// `numer / denom` rounded to an integer, for a positive `denom`.
+    fn divide(self, numer: &Long, denom: &Long) -> Long {
+        let (quot, rem) = numer.abs().div_rem(denom);
+        let half = (rem << 1).cmp(denom);
+        let up = match self {
+            Rounding::Down => false,
+            Rounding::HalfUp => half != Ordering::Less,
+            Rounding::HalfEven => half == Ordering::Greater || half == Ordering::Equal && quot.is_odd(),
+        };
+        let quot = if up { quot + Long::one() } else { quot };
+        if numer.is_negative() {
+            -quot
+        } else {
+            quot
+        }
+    }
+}
+
+impl Display for Rounding {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Rounding::HalfEven => "half-even".fmt(f),
+            Rounding::HalfUp => "half-up".fmt(f),
+            Rounding::Down => "down".fmt(f),
+        }
+    }
+}
+
+fn ten_to(exp: u32) -> Long {
+    Long::from(10).pow(exp)
+}
+
+impl Decimal {
+    pub fn new<T: Into<Long>>(unscaled: T, scale: u32) -> Decimal {
+        Decimal {
+            unscaled: unscaled.into(),
+            scale,
+        }
+    }
+
+    /// Most digits after the point results keep, set for the session.
+    pub fn scale() -> u32 {
+        SCALE.with(|scale| scale.get())
+    }
+
+    pub fn set_scale(scale: u32) {
+        SCALE.with(|cell| cell.set(scale.min(MAX_SCALE)))
+    }
+
+    /// Rounding of results to the session scale.
+    pub fn rounding() -> Rounding {
+        ROUNDING.with(|rounding| rounding.get())
+    }
+
+    pub fn set_rounding(rounding: Rounding) {
+        ROUNDING.with(|cell| cell.set(rounding))
+    }
+
+    /// Whether literals with a point or an exponent are decimals rather
+    /// than reals.
+    pub fn literals() -> bool {
+        LITERALS.with(|literals| literals.get())
+    }
+
+    pub fn set_literals(literals: bool) {
+        LITERALS.with(|cell| cell.set(literals))
+    }
+
+    /// Exact value of a literal like `1.50` or `2.5e-3`, `None` for other
+    /// text.
+    pub fn parse(text: &str) -> Option<Decimal> {
+        let mut parts = text.splitn(2, ['e', 'E']);
+        let mantissa = parts.next()?;
+        let exponent = match parts.next() {
+            Some(exponent) => exponent.parse::<i64>().ok()?,
+            None => 0,
+        };
+        let mut parts = mantissa.splitn(2, '.');
+        let whole = parts.next()?;
+        let fraction = parts.next().unwrap_or("");
+        let unscaled = format!("{}{}", whole, fraction).parse::<Long>().ok()?;
+
+        let scale = fraction.len() as i64 - exponent;
+        Some(if scale >= 0 {
+            Decimal::new(unscaled, scale as u32)
+        } else {
+            Decimal::new(unscaled * ten_to(scale.unsigned_abs() as u32), 0)
+        })
+    }
+
+    /// The closest decimal with `scale` digits after the point.
+    pub fn from_rational(rat: &Rational, scale: u32, rounding: Rounding) -> Decimal {
+        let numer = rat.numer.value() * ten_to(scale);
+        Decimal::new(rounding.divide(&numer, &rat.denom.value()), scale)
+    }
+
+    pub fn unscaled(&self) -> Long {
+        self.unscaled.clone()
+    }
+
+    /// Digits after the point of this number.
+    pub fn places(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn to_rational(&self) -> Rational {
+        Rational::new(Integer::new(self.unscaled()), Integer::new(ten_to(self.scale)))
+    }
+
+    /// Value for the cross-type conversions, exact.
+    pub fn value(&self) -> Rational {
+        self.to_rational()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.unscaled.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.unscaled.is_negative()
+    }
+
+    pub fn abs(&self) -> Decimal {
+        Decimal::new(self.unscaled.abs(), self.scale)
+    }
+
+    /// Largest integer not above `self`, with no digits after the point.
+    pub fn floor(&self) -> Decimal {
+        Decimal::new(self.to_rational().floor().value(), 0)
+    }
+
+    /// The same value with `scale` digits after the point, rounded in
+    /// the session mode when it has more.
+    pub fn rescale(&self, scale: u32) -> Decimal {
+        match scale.cmp(&self.scale) {
+            Ordering::Equal => self.clone(),
+            Ordering::Greater => Decimal::new(self.unscaled() * ten_to(scale - self.scale), scale),
+            Ordering::Less => {
+                let unscaled = Decimal::rounding().divide(&self.unscaled, &ten_to(self.scale - scale));
+                Decimal::new(unscaled, scale)
+            }
+        }
+    }
+
+    /// Rounded to the session scale when it has more digits.
+    fn limited(self) -> Decimal {
+        let scale = Decimal::scale();
+        if self.scale > scale {
+            self.rescale(scale)
+        } else {
+            self
+        }
+    }
+
+    /// `self^exp`, exact up to the session scale.
+    pub fn powi(&self, exp: u32) -> Decimal {
+        Decimal::new(self.unscaled.pow(exp), self.scale * exp).limited()
+    }
+
+    /// Both numbers with the larger of their scales.
+    fn aligned(&self, other: &Decimal) -> (Long, Long, u32) {
+        let scale = max(self.scale, other.scale);
+        (self.rescale(scale).unscaled, other.rescale(scale).unscaled, scale)
+    }
+}
+
+/// Digits after the point are all shown, `-0.50` keeps its zero.
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!("{:0>1$}", self.unscaled.abs(), scale + 1);
+        let point = digits.len() - scale;
+        if self.is_negative() {
+            "-".fmt(f)?;
+        }
+        digits[..point].fmt(f)?;
+        if scale > 0 {
+            write!(f, ".{}", &digits[point..])?;
+        }
+        Ok(())
+    }
+}
+
+/// Decimals are equal by value whatever their scales.
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let (a, b, _) = self.aligned(other);
+        a.cmp(&b)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Long> for Decimal {
+    fn from(value: Long) -> Decimal {
+        Decimal::new(value, 0)
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::new(-self.unscaled, self.scale)
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        let (a, b, scale) = self.aligned(&other);
+        Decimal::new(a + b, scale).limited()
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        let (a, b, scale) = self.aligned(&other);
+        Decimal::new(a - b, scale).limited()
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: Decimal) -> Decimal {
+        Decimal::new(self.unscaled * other.unscaled, self.scale + other.scale).limited()
+    }
+}
+
+/// An exact quotient keeps the scale of the dividend less the one of the
+/// divisor, or as many digits as it needs; any other is rounded to the
+/// session scale.
+impl Div for Decimal {
+    type Output = Decimal;
+
+    fn div(self, other: Decimal) -> Decimal {
+        let quot = self.to_rational() / other.to_rational();
+        let limit = Decimal::scale();
+        let preferred = self.scale.saturating_sub(other.scale).min(limit);
+        let mut denom = quot.denom.value();
+        let mut digits = 0;
+        for prime in &[2, 5] {
+            let mut count = 0;
+            loop {
+                let (rest, rem) = denom.div_rem(&Long::from(*prime));
+                if !rem.is_zero() {
+                    break;
+                }
+                denom = rest;
+                count += 1;
+            }
+            digits = max(digits, count);
+        }
+        let scale = if denom == Long::one() && digits <= limit { max(digits, preferred) } else { limit };
+        Decimal::from_rational(&quot, scale, Decimal::rounding())
+    }
+}
+
+impl Rem for Decimal {
+    type Output = Decimal;
+
+    fn rem(self, other: Decimal) -> Decimal {
+        let (a, b, scale) = self.aligned(&other);
+        Decimal::new(a % b, scale)
+    }
+}
+
+#[macro_export]
+macro_rules! decimal {
+    ($unscaled:expr, $scale:expr) => {
+        $crate::numbers::Decimal::new($unscaled, $scale)
+    };
+    ($a:expr) => {
+        $crate::numbers::Decimal::from($a)
+    };
+}
+
+impl Number {
+    /// Decimal of a literal like `1.50`, keeping its digits after the
+    /// point.
+    pub fn fixed_point(s: String) -> Option<Number> {
+        Decimal::parse(s.as_str()).map(Number::Decimal)
+    }
+
+    /// `self` rounded to `places` digits after the point in the session
+    /// mode, reals are taken as the decimal they are shown as.
+    pub fn quantize(self, places: Number) -> Result<Number, ArithmeticError> {
+        let places = match places {
+            Number::Natural(ref nat) if nat.value() <= Long::from(MAX_SCALE) => nat.value().low_u64() as u32,
+            _ => {
+                return Err(ArithmeticError::Domain(format!(
+                    "number of places must be a natural number up to {}",
+                    MAX_SCALE
+                )))
+            }
+        };
+        let rounding = Decimal::rounding();
+        let value = match self {
+            Number::Decimal(dec) => return Ok(Number::Decimal(dec.rescale(places))),
+            Number::Algebraic(ref alg) => {
+                let decimal = alg.refined(|value| Decimal::from_rational(value, places, rounding));
+                return Ok(Number::Decimal(decimal));
+            }
+            Number::Real(ref rea) => rea.to_decimal(),
+            ref number => number.to_rational(),
+        };
+        match value {
+            Some(rat) => Ok(Number::Decimal(Decimal::from_rational(&rat, places, rounding))),
+            None => Err(ArithmeticError::Domain(format!(
+                "{} can't be a Decimal number",
+                self
+            ))),
+        }
+    }
+
+    /// Decimal value of naturals, integers and decimals, which stay
+    /// decimal together.
+    pub(crate) fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Number::Natural(nat) => Some(Decimal::from(nat.value())),
+            Number::Integer(int) => Some(Decimal::from(int.value())),
+            Number::Decimal(dec) => Some(dec.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Power of a decimal base with a whole exponent, a negative one divides.
+pub(crate) fn decimal_pow(base: Decimal, exp: Long, max_bits: usize) -> Result<Number, ArithmeticError> {
+    let bits = base.unscaled.bits() + base.scale as usize * 4;
+    if exp.bits() > 31 || bits.saturating_mul(exp.abs().low_u64() as usize) > max_bits {
+        return Err(ArithmeticError::Overflow {
+            kind: "Decimal",
+            operation: Operation::Power,
+        });
+    }
+    let power = base.powi(exp.abs().low_u64() as u32);
+    Ok(Number::Decimal(if exp.is_negative() {
+        Decimal::from(Long::one()) / power
+    } else {
+        power
+    }))
+}
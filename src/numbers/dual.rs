@@ -0,0 +1,299 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Number, Operation, Real};
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Real number with its derivatives by the variables it depends on, for
+/// forward-mode automatic differentiation.
+///
+/// Every variable has a tangent of its own, so one evaluation gives all
+/// the partial derivatives of a gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dual {
+    value: f64,
+    tangents: BTreeMap<u64, f64>,
+}
+
+thread_local! {
+    static NEXT_VARIABLE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Derivatives of the elementary functions, for `f(x) + f'(x) dx`.
+macro_rules! impl_derivatives {
+    ($($name:ident: $domain:expr, $derivative:expr),*) => {
+        impl Dual {
+            $(
+                pub fn $name(&self) -> Result<Dual, ArithmeticError> {
+                    let domain: fn(f64) -> bool = $domain;
+                    let derivative: fn(f64) -> f64 = $derivative;
+                    if !domain(self.value) {
+                        return Err(ArithmeticError::Domain(format!(
+                            "{} of {} is not real",
+                            stringify!($name),
+                            self
+                        )));
+                    }
+                    Ok(self.chain(self.value.$name(), derivative(self.value)))
+                }
+            )*
+        }
+    };
+}
+
+impl_derivatives! {
+    exp: |_| true, f64::exp,
+    ln: |x| x > 0.0, |x| 1.0 / x,
+    sin: |_| true, f64::cos,
+    cos: |_| true, |x| -x.sin(),
+    tan: |_| true, |x| 1.0 + x.tan() * x.tan(),
+    asin: |x| x.abs() <= 1.0, |x| 1.0 / (1.0 - x * x).sqrt(),
+    acos: |x| x.abs() <= 1.0, |x| -1.0 / (1.0 - x * x).sqrt(),
+    atan: |_| true, |x| 1.0 / (1.0 + x * x),
+    sinh: |_| true, f64::cosh,
+    cosh: |_| true, f64::sinh,
+    tanh: |_| true, |x| 1.0 - x.tanh() * x.tanh(),
+    asinh: |_| true, |x| 1.0 / (x * x + 1.0).sqrt(),
+    acosh: |x| x >= 1.0, |x| 1.0 / (x * x - 1.0).sqrt(),
+    atanh: |x| x.abs() < 1.0, |x| 1.0 / (1.0 - x * x),
+    sqrt: |x| x >= 0.0, |x| 0.5 / x.sqrt(),
+    cbrt: |_| true, |x| 1.0 / (3.0 * x.cbrt() * x.cbrt())
+}
+
+impl Dual {
+    /// The variable `variable` at `value`, its own derivative is one.
+    pub fn variable(value: f64, variable: u64) -> Dual {
+        let mut tangents = BTreeMap::new();
+        tangents.insert(variable, 1.0);
+        Dual { value, tangents }
+    }
+
+    /// A variable distinct from all others.
+    pub fn next_variable() -> u64 {
+        NEXT_VARIABLE.with(|next| {
+            let variable = next.get();
+            next.set(variable + 1);
+            variable
+        })
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Partial derivative by `variable`, zero for the ones it does not
+    /// depend on.
+    pub fn derivative(&self, variable: u64) -> f64 {
+        self.tangents.get(&variable).cloned().unwrap_or(0.0)
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.tangents.is_empty()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.value.is_finite() && self.tangents.values().all(|tangent| tangent.is_finite())
+    }
+
+    /// Differentiable everywhere but at zero, where the slope is taken
+    /// from the right.
+    pub fn abs(&self) -> Dual {
+        self.chain(self.value.abs(), if self.value < 0.0 { -1.0 } else { 1.0 })
+    }
+
+    /// `self^exp`, `None` unless a negative base has a constant whole
+    /// exponent and a zero one a constant exponent.
+    pub fn powf(&self, exp: &Dual) -> Option<Dual> {
+        let (x, y) = (self.value, exp.value);
+        let whole = exp.is_constant() && y.fract() == 0.0;
+        if x < 0.0 && !whole || x == 0.0 && !exp.is_constant() {
+            return None;
+        }
+        let value = x.powf(y);
+        let base = if y == 0.0 { 0.0 } else { y * x.powf(y - 1.0) };
+        let power = if exp.is_constant() { 0.0 } else { x.ln() * value };
+        Some(self.linear(base, exp, power, value))
+    }
+
+    /// Angle of the point `(x, self)`.
+    pub fn atan2(&self, x: &Dual) -> Dual {
+        let (y, x_value) = (self.value, x.value);
+        let square = x_value * x_value + y * y;
+        self.linear(x_value / square, x, -y / square, y.atan2(x_value))
+    }
+
+    /// Remainder of the quotient truncated toward zero, which is constant
+    /// between the jumps.
+    pub fn rem(&self, other: &Dual) -> Dual {
+        let quot = (self.value / other.value).trunc();
+        self.linear(1.0, other, -quot, self.value % other.value)
+    }
+
+    /// `f(self)` for a function with the derivative `slope` at `self`.
+    fn chain(&self, value: f64, slope: f64) -> Dual {
+        self.linear(slope, &Dual::from(0.0), 0.0, value)
+    }
+
+    /// Tangents of `a * self + b * other` around `value`, the ones that
+    /// cancel are dropped.
+    fn linear(&self, a: f64, other: &Dual, b: f64, value: f64) -> Dual {
+        let mut tangents = BTreeMap::new();
+        for (variable, tangent) in &self.tangents {
+            tangents.insert(*variable, a * tangent);
+        }
+        for (variable, tangent) in &other.tangents {
+            *tangents.entry(*variable).or_insert(0.0) += b * tangent;
+        }
+        tangents.retain(|_, tangent| *tangent != 0.0);
+        Dual { value, tangents }
+    }
+}
+
+/// Only the value is shown, the tangents are read with `derivative`.
+impl Display for Dual {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Real::new(self.value).fmt(f)
+    }
+}
+
+impl From<f64> for Dual {
+    fn from(value: f64) -> Dual {
+        Dual {
+            value,
+            tangents: BTreeMap::new(),
+        }
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Dual {
+        self.chain(-self.value, -1.0)
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, other: Dual) -> Dual {
+        self.linear(1.0, &other, 1.0, self.value + other.value)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, other: Dual) -> Dual {
+        self.linear(1.0, &other, -1.0, self.value - other.value)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, other: Dual) -> Dual {
+        self.linear(other.value, &other, self.value, self.value * other.value)
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+
+    fn div(self, other: Dual) -> Dual {
+        let (x, y) = (self.value, other.value);
+        self.linear(1.0 / y, &other, -x / (y * y), x / y)
+    }
+}
+
+impl Number {
+    /// The real `value` as the variable `variable` of a derivative.
+    pub fn dual(value: Number, variable: u64) -> Result<Number, ArithmeticError> {
+        if value.is_dual() {
+            return Err(ArithmeticError::Domain("derivatives can't be nested".to_owned()));
+        }
+        match value.to_real() {
+            Some(rea) => Ok(Number::Dual(Dual::variable(rea.value(), variable))),
+            None => Err(undifferentiable(&value)),
+        }
+    }
+
+    /// Partial derivative by `variable` of a result computed from dual
+    /// numbers, a real number is a constant.
+    pub fn derivative(&self, variable: u64) -> Result<Number, ArithmeticError> {
+        match self {
+            Number::Dual(dual) => Ok(Number::Real(Real::new(dual.derivative(variable)))),
+            number if number.to_real().is_some() => Ok(Number::Natural(natural!(0))),
+            number => Err(undifferentiable(number)),
+        }
+    }
+
+    pub(crate) fn is_dual(&self) -> bool {
+        matches!(self, Number::Dual(_))
+    }
+
+    /// A real number as a constant, `None` for complex numbers, intervals,
+    /// measurements and residues.
+    pub(crate) fn to_dual(&self) -> Option<Dual> {
+        match self {
+            Number::Dual(dual) => Some(dual.clone()),
+            number => number.to_real().map(|rea| Dual::from(rea.value())),
+        }
+    }
+}
+
+fn undifferentiable(number: &Number) -> ArithmeticError {
+    ArithmeticError::Domain(format!("{} numbers can't be differentiated", number.kind()))
+}
+
+fn operands(a: &Number, b: &Number) -> Result<(Dual, Dual), ArithmeticError> {
+    match (a.to_dual(), b.to_dual()) {
+        (Some(x), Some(y)) => Ok((x, y)),
+        (None, _) => Err(undifferentiable(a)),
+        (_, None) => Err(undifferentiable(b)),
+    }
+}
+
+/// Operation with a dual operand, the other one is taken as a constant.
+pub(crate) fn dual_operation(operation: Operation, a: Number, b: Number) -> Result<Number, ArithmeticError> {
+    let (x, y) = operands(&a, &b)?;
+    let divisor = matches!(operation, Operation::Division | Operation::Remainder);
+    if divisor && y.value == 0.0 {
+        return Err(ArithmeticError::DivisionByZero);
+    }
+    Ok(Number::Dual(match operation {
+        Operation::Addition => x + y,
+        Operation::Subtraction => x - y,
+        Operation::Multiplication => x * y,
+        Operation::Division => x / y,
+        Operation::Remainder => x.rem(&y),
+        Operation::Power => x.powf(&y).ok_or_else(|| {
+            ArithmeticError::Domain(format!("{} raised to {} is not real", a, b))
+        })?,
+    }))
+}
+
+/// Dual numbers are ordered by their values, so that a function may
+/// branch on its arguments.
+pub(crate) fn dual_cmp(a: &Number, b: &Number) -> Result<Ordering, ArithmeticError> {
+    let (x, y) = operands(a, b)?;
+    Real::new(x.value).partial_cmp(&Real::new(y.value)).ok_or_else(|| {
+        ArithmeticError::Domain(format!("comparison of {} and {} is not defined", a, b))
+    })
+}
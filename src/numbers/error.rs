@@ -0,0 +1,83 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Addition,
+    Subtraction,
+    Multiplication,
+    Division,
+    Remainder,
+    Power,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Operation::Addition => "addition",
+            Operation::Subtraction => "subtraction",
+            Operation::Multiplication => "multiplication",
+            Operation::Division => "division",
+            Operation::Remainder => "remainder",
+            Operation::Power => "exponentiation",
+        }.fmt(f)
+    }
+}
+
+/// Failure of an arithmetic operation on `Number`s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithmeticError {
+    DivisionByZero,
+    /// The result is too large for its type, e.g. a real turned infinite.
+    Overflow {
+        kind: &'static str,
+        operation: Operation,
+    },
+    /// A real result of nonzero operands was rounded to zero.
+    Underflow {
+        kind: &'static str,
+        operation: Operation,
+    },
+    /// The number has no value of the type it was converted to, like
+    /// a negative natural.
+    Conversion {
+        value: String,
+        kind: &'static str,
+    },
+    /// The operation is not defined for its operands.
+    Domain(String),
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ArithmeticError::DivisionByZero => "division by zero".fmt(f),
+            ArithmeticError::Overflow { kind, operation } => {
+                write!(f, "overflow in {} {}", kind, operation)
+            }
+            ArithmeticError::Underflow { kind, operation } => {
+                write!(f, "underflow in {} {}", kind, operation)
+            }
+            ArithmeticError::Conversion { value, kind } => {
+                write!(f, "cannot convert {} to {}", value, kind)
+            }
+            ArithmeticError::Domain(message) => message.fmt(f),
+        }
+    }
+}
+
+impl Error for ArithmeticError {}
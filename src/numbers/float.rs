@@ -0,0 +1,825 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Long;
+use utils::ldexp;
+
+use std::cell::RefCell;
+use std::cmp::{max, min, Ordering};
+use std::f64::consts::LN_2;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::thread::LocalKey;
+
+/// Extra bits carried beyond the requested decimal digits, so that
+/// rounding errors of a few operations stay invisible when printing.
+const GUARD_BITS: usize = 16;
+
+/// Largest power of ten a literal is scaled by exactly.
+const MAX_DECIMAL_EXPONENT: u64 = 100_000;
+
+/// Extra decimal digits the elementary functions work with, so that the
+/// rounding of the steps of a series doesn't reach the result.
+const GUARD_DIGITS: usize = 10;
+
+thread_local! {
+    /// Most precise values of the constants computed so far.
+    static PI: RefCell<Option<Float>> = const { RefCell::new(None) };
+    static LN_TWO: RefCell<Option<Float>> = const { RefCell::new(None) };
+}
+
+/// Binary floating-point number `mantissa * 2^exponent`.
+///
+/// Every result is rounded half to even to the number of bits needed
+/// for `digits` significant decimal digits.
+#[derive(Debug, Clone)]
+pub struct Float {
+    mantissa: Long,
+    exponent: i64,
+    digits: usize,
+}
+
+fn precision_bits(digits: usize) -> usize {
+    (digits as f64 * 10f64.log2()).ceil() as usize + GUARD_BITS
+}
+
+/// Shifts right, setting the lowest bit when anything nonzero is lost,
+/// so that the following rounding still sees an inexact tail.
+fn sticky_shr(mantissa: Long, bits: usize) -> Long {
+    let shifted = mantissa.clone() >> bits;
+    if (shifted.clone() << bits) == mantissa || shifted.is_odd() {
+        shifted
+    } else if mantissa.is_negative() {
+        shifted - Long::one()
+    } else {
+        shifted + Long::one()
+    }
+}
+
+impl Float {
+    pub fn new(mantissa: Long, exponent: i64, digits: usize) -> Float {
+        let precision = precision_bits(digits);
+        let bits = mantissa.bits();
+        if bits <= precision {
+            return Float {
+                exponent: if mantissa.is_zero() { 0 } else { exponent },
+                mantissa,
+                digits,
+            };
+        }
+
+        let shift = bits - precision;
+        let negative = mantissa.is_negative();
+        let magnitude = mantissa.abs();
+        let mut quot = magnitude.clone() >> shift;
+        let rem = magnitude - (quot.clone() << shift);
+        let half = Long::one() << (shift - 1);
+        match rem.cmp(&half) {
+            Ordering::Greater => quot = quot + Long::one(),
+            Ordering::Equal if quot.is_odd() => quot = quot + Long::one(),
+            _ => {}
+        }
+
+        // rounding up may carry into a new bit, the next pass drops it exactly
+        Float::new(
+            if negative { -quot } else { quot },
+            exponent + shift as i64,
+            digits,
+        )
+    }
+
+    pub fn zero(digits: usize) -> Float {
+        Float::new(Long::zero(), 0, digits)
+    }
+
+    /// `numer / denom` rounded to `digits` decimal digits.
+    pub fn from_ratio(numer: Long, denom: Long, digits: usize) -> Float {
+        Float::new(numer, 0, digits) / Float::new(denom, 0, digits)
+    }
+
+    /// Exact value of a finite `f64`, rounded to `digits` decimal digits.
+    pub fn from_f64(value: f64, digits: usize) -> Float {
+        if value == 0.0 {
+            return Float::zero(digits);
+        }
+        let bits = value.to_bits();
+        let raw_exponent = ((bits >> 52) & 0x7FF) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if raw_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), raw_exponent - 1075)
+        };
+        let mantissa = Long::from(mantissa);
+
+        Float::new(
+            if value < 0.0 { -mantissa } else { mantissa },
+            exponent,
+            digits,
+        )
+    }
+
+    /// Parses a decimal literal like `12.375` or `6.02e23`, `None` when
+    /// the exponent is out of reach.
+    pub fn parse(text: &str, digits: usize) -> Option<Float> {
+        let mut parts = text.splitn(2, ['e', 'E']);
+        let mantissa = parts.next().unwrap_or("");
+        let exponent = match parts.next() {
+            Some(exponent) => exponent.parse::<i64>().ok()?,
+            None => 0,
+        };
+        let mut parts = mantissa.splitn(2, '.');
+        let whole = parts.next().unwrap_or("");
+        let fraction = parts.next().unwrap_or("");
+        let numer = format!("{}{}", whole, fraction).parse::<Long>().ok()?;
+
+        let scale = exponent - fraction.len() as i64;
+        if scale.unsigned_abs() > MAX_DECIMAL_EXPONENT {
+            return None;
+        }
+        let power = Long::from(10).pow(scale.unsigned_abs() as u32);
+        Some(if scale >= 0 {
+            Float::new(numer * power, 0, digits)
+        } else {
+            Float::from_ratio(numer, power, digits)
+        })
+    }
+
+    /// Tolerance of equality, `10^-(digits - 2)`, the same margin `f64`
+    /// reals get for their sixteen digits.
+    pub fn epsilon(digits: usize) -> Float {
+        let scale = Long::from(10).pow(digits.saturating_sub(2) as u32);
+        Float::from_ratio(Long::one(), scale, digits)
+    }
+
+    pub fn digits(&self) -> usize {
+        self.digits
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+
+    pub fn abs(&self) -> Float {
+        Float {
+            mantissa: self.mantissa.abs(),
+            exponent: self.exponent,
+            digits: self.digits,
+        }
+    }
+
+    /// Integer part, rounded toward zero.
+    pub fn trunc(&self) -> Float {
+        if self.exponent >= 0 {
+            return self.clone();
+        }
+        Float::new(
+            self.mantissa.clone() >> (-self.exponent) as usize,
+            0,
+            self.digits,
+        )
+    }
+
+    /// Unit in the last place of a number of this magnitude.
+    pub fn ulp(&self) -> Float {
+        let exponent = self.top() - precision_bits(self.digits) as i64;
+        Float::new(Long::one(), exponent, self.digits)
+    }
+
+    pub fn with_digits(&self, digits: usize) -> Float {
+        Float::new(self.mantissa.clone(), self.exponent, digits)
+    }
+
+    /// Small integer `value` carrying `digits` decimal digits.
+    pub fn integer(value: i64, digits: usize) -> Float {
+        Float::new(Long::from(value), 0, digits)
+    }
+
+    /// `pi` to `digits` decimal digits, by Machin's formula
+    /// `pi = 16 atan(1/5) - 4 atan(1/239)`.
+    pub fn pi(digits: usize) -> Float {
+        cached(&PI, digits, |work| {
+            Float::integer(16, work) * reciprocal_atan(5, false, work)
+                - Float::integer(4, work) * reciprocal_atan(239, false, work)
+        })
+    }
+
+    /// `ln 2` to `digits` decimal digits, as `2 atanh(1/3)`.
+    pub fn ln_2(digits: usize) -> Float {
+        cached(&LN_TWO, digits, |work| {
+            Float::integer(2, work) * reciprocal_atan(3, true, work)
+        })
+    }
+
+    /// `self * 2^shift`, exactly.
+    fn scaled(&self, shift: i64) -> Float {
+        Float {
+            mantissa: self.mantissa.clone(),
+            exponent: if self.is_zero() { 0 } else { self.exponent + shift },
+            digits: self.digits,
+        }
+    }
+
+    /// Whether adding `self` to `sum` no longer changes it.
+    fn negligible(&self, sum: &Float) -> bool {
+        self.is_zero() || self.top() < sum.top() - precision_bits(max(self.digits, sum.digits)) as i64 - 2
+    }
+
+    /// `atan(self)` by the Taylor series `x - x^3/3 + x^5/5 - ...`, for
+    /// `|self|` well below one.
+    fn atan_series(&self) -> Float {
+        let square = -(self.clone() * self.clone());
+        let mut power = self.clone();
+        let mut sum = self.clone();
+        let mut n = 3;
+        loop {
+            power = power * square.clone();
+            let term = power.clone() / Float::integer(n, self.digits);
+            if term.negligible(&sum) {
+                return sum;
+            }
+            sum = sum + term;
+            n += 2;
+        }
+    }
+
+    /// Number of times an argument is halved before a series, so that
+    /// the series needs fewer terms than the halvings cost to undo.
+    fn halvings(&self) -> usize {
+        (precision_bits(self.digits) as f64).sqrt() as usize / 2
+    }
+
+    /// `e^self`. The argument is reduced to `r = self - k ln 2` and
+    /// halved, the Taylor series of `e^r` squared back and scaled by `2^k`.
+    /// `|self|` must be in the range of `f64`.
+    pub fn exp(&self) -> Float {
+        let digits = self.digits;
+        if self.is_zero() {
+            return Float::integer(1, digits);
+        }
+        let k = (self.to_f64() / LN_2).round();
+        let halvings = self.halvings();
+        let work = digits + GUARD_DIGITS + decimal_length(k) + halvings / 3;
+
+        let ln_2 = Float::ln_2(work + decimal_length(k));
+        let reduced = self.with_digits(work) - ln_2 * Float::from_f64(k, work);
+        let reduced = reduced.scaled(-(halvings as i64));
+
+        let mut sum = Float::integer(1, work);
+        let mut term = Float::integer(1, work);
+        let mut n = 1;
+        loop {
+            term = term * reduced.clone() / Float::integer(n, work);
+            if term.negligible(&sum) {
+                break;
+            }
+            sum = sum + term.clone();
+            n += 1;
+        }
+        for _ in 0..halvings {
+            sum = sum.clone() * sum;
+        }
+        sum.scaled(k as i64).with_digits(digits)
+    }
+
+    /// Natural logarithm of a positive `self`. With `self = m 2^e` and
+    /// `m` in `[1, 2)`, `ln m` is the root of `e^y = m` found by Halley's
+    /// iteration `y += 2 (m - e^y) / (m + e^y)`, and `e ln 2` is added.
+    pub fn ln(&self) -> Float {
+        let digits = self.digits;
+        let shift = self.top() - 1;
+        let mantissa = self.scaled(-shift);
+        let one = Float::integer(1, digits);
+        let above_one = mantissa.clone() - one;
+        if above_one.is_zero() {
+            return (Float::ln_2(digits) * Float::new(Long::from(shift), 0, digits)).with_digits(digits);
+        }
+
+        // close to 1 the logarithm is small and needs as many more digits
+        let close = max(0, -above_one.top()) as usize * 3 / 10;
+        let work = digits + GUARD_DIGITS + close + decimal_length(shift as f64);
+        let mantissa = mantissa.with_digits(work);
+        let two = Float::integer(2, work);
+        let mut root = Float::from_f64(above_one.to_f64().ln_1p(), work);
+        let mut correct = 40;
+        while correct < precision_bits(work) {
+            let power = root.exp();
+            let step = two.clone() * (mantissa.clone() - power.clone()) / (mantissa.clone() + power);
+            root = root + step;
+            correct *= 3;
+        }
+        (root + Float::ln_2(work) * Float::new(Long::from(shift), 0, work)).with_digits(digits)
+    }
+
+    /// `(sin(self), cos(self))`. The argument is reduced to
+    /// `r = self - k pi/2`, `|r| <= pi/4`, where both Taylor series
+    /// converge fast. `|self|` must be in the range of `f64`.
+    pub fn sin_cos(&self) -> (Float, Float) {
+        let digits = self.digits;
+        if self.is_zero() {
+            return (Float::zero(digits), Float::integer(1, digits));
+        }
+        // the multiple k of pi/2 is found with as many digits as it has
+        let length = decimal_length(self.to_f64());
+        let estimate = length + GUARD_DIGITS;
+        let half = Float::new(Long::one(), -1, estimate);
+        let quotient = self.with_digits(estimate) / Float::pi(estimate).scaled(-1);
+        let k = if quotient.is_negative() { quotient - half } else { quotient + half }.trunc();
+
+        let mut work = digits + GUARD_DIGITS + length;
+        let reduced = loop {
+            let half_pi = Float::pi(work + length).scaled(-1);
+            let reduced = self.with_digits(work) - half_pi * k.with_digits(work);
+            // near a multiple of pi/2 the difference cancels, leaving
+            // fewer correct digits than asked for
+            let lost = max(0, -reduced.top()) as usize * 3 / 10;
+            if k.is_zero() || (!reduced.is_zero() && lost + digits + GUARD_DIGITS <= work) {
+                break reduced;
+            }
+            work = digits + GUARD_DIGITS + length + max(lost, work);
+        };
+
+        let square = -(reduced.clone() * reduced.clone());
+        let (mut sin, mut cos) = (reduced.clone(), Float::integer(1, work));
+        let (mut sin_term, mut cos_term) = (reduced, Float::integer(1, work));
+        let mut n = 1;
+        loop {
+            cos_term = cos_term * square.clone() / Float::integer(n * (n + 1), work);
+            sin_term = sin_term * square.clone() / Float::integer((n + 1) * (n + 2), work);
+            if cos_term.negligible(&cos) && sin_term.negligible(&sin) {
+                break;
+            }
+            sin = sin + sin_term.clone();
+            cos = cos + cos_term.clone();
+            n += 2;
+        }
+        let (sin, cos) = (sin.with_digits(digits), cos.with_digits(digits));
+        let four = Long::from(4);
+        match (((k.to_long() % four.clone()) + four.clone()) % four).low_u64() {
+            0 => (sin, cos),
+            1 => (cos, -sin),
+            2 => (-sin, -cos),
+            _ => (-cos, sin),
+        }
+    }
+
+    /// Arc tangent, in `(-pi/2, pi/2)`. Beyond one, `atan x` is
+    /// `pi/2 - atan(1/x)`; below it, the argument is halved with
+    /// `atan x = 2 atan(x / (1 + sqrt(1 + x^2)))` before the series.
+    pub fn atan(&self) -> Float {
+        let digits = self.digits;
+        if self.is_zero() {
+            return self.clone();
+        }
+        let halvings = self.halvings();
+        let work = digits + GUARD_DIGITS + halvings / 3;
+        let one = Float::integer(1, work);
+        let arg = self.with_digits(work);
+        let reflected = arg.abs() > one;
+        let mut arg = if reflected { one.clone() / arg } else { arg };
+        for _ in 0..halvings {
+            arg = arg.clone() / (one.clone() + (one.clone() + arg.clone() * arg).sqrt());
+        }
+        let angle = arg.atan_series().scaled(halvings as i64);
+        let angle = match (reflected, self.is_negative()) {
+            (false, _) => angle,
+            (true, false) => Float::pi(work).scaled(-1) - angle,
+            (true, true) => -Float::pi(work).scaled(-1) - angle,
+        };
+        angle.with_digits(digits)
+    }
+
+    /// Digits lost when a value this small is found as the difference
+    /// of values near one.
+    fn cancelled_digits(&self) -> usize {
+        max(0, -self.top()) as usize * 3 / 10
+    }
+
+    /// `(sinh(self), cosh(self))` from `e^self` and `e^-self`.
+    pub fn sinh_cosh(&self) -> (Float, Float) {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS + self.cancelled_digits();
+        let power = self.with_digits(work).exp();
+        let inverse = Float::integer(1, work) / power.clone();
+        let two = Float::integer(2, work);
+        (
+            ((power.clone() - inverse.clone()) / two.clone()).with_digits(digits),
+            ((power + inverse) / two).with_digits(digits),
+        )
+    }
+
+    /// `ln(x + sqrt(x^2 + 1))`
+    pub fn asinh(&self) -> Float {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS + self.cancelled_digits();
+        let arg = self.abs().with_digits(work);
+        let one = Float::integer(1, work);
+        let result = (arg.clone() + (arg.clone() * arg + one).sqrt()).ln().with_digits(digits);
+        if self.is_negative() {
+            -result
+        } else {
+            result
+        }
+    }
+
+    /// `ln(x + sqrt((x - 1)(x + 1)))` for `self >= 1`.
+    pub fn acosh(&self) -> Float {
+        let digits = self.digits;
+        let one = Float::integer(1, digits);
+        let work = digits + GUARD_DIGITS + (self.clone() - one).cancelled_digits();
+        let arg = self.with_digits(work);
+        let one = Float::integer(1, work);
+        let root = ((arg.clone() - one.clone()) * (arg.clone() + one)).sqrt();
+        (arg + root).ln().with_digits(digits)
+    }
+
+    /// `ln((1 + x) / (1 - x)) / 2` for `|self| < 1`.
+    pub fn atanh(&self) -> Float {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS + self.cancelled_digits();
+        let arg = self.with_digits(work);
+        let one = Float::integer(1, work);
+        let ratio = (one.clone() + arg.clone()) / (one - arg);
+        (ratio.ln() / Float::integer(2, work)).with_digits(digits)
+    }
+
+    /// `atan(x / sqrt((1 - x)(1 + x)))` for `|self| <= 1`.
+    pub fn asin(&self) -> Float {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS;
+        let arg = self.with_digits(work);
+        let one = Float::integer(1, work);
+        let cos = ((one.clone() - arg.clone()) * (one.clone() + arg.clone())).sqrt();
+        if cos.is_zero() {
+            let half_pi = Float::pi(digits).scaled(-1);
+            return if self.is_negative() { -half_pi } else { half_pi };
+        }
+        (arg / cos).atan().with_digits(digits)
+    }
+
+    /// `2 atan(sqrt((1 - x) / (1 + x)))` for `|self| <= 1`.
+    pub fn acos(&self) -> Float {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS;
+        let arg = self.with_digits(work);
+        let one = Float::integer(1, work);
+        let denom = one.clone() + arg.clone();
+        if denom.is_zero() {
+            return Float::pi(digits);
+        }
+        ((one - arg) / denom).sqrt().atan().scaled(1).with_digits(digits)
+    }
+
+    /// Angle of the point `(other, self)`, in `(-pi, pi]`.
+    pub fn atan2(&self, other: &Float) -> Float {
+        let digits = max(self.digits, other.digits);
+        let half_pi = Float::pi(digits).scaled(-1);
+        if other.is_zero() {
+            return match self.cmp(&Float::zero(digits)) {
+                Ordering::Greater => half_pi,
+                Ordering::Less => -half_pi,
+                Ordering::Equal => Float::zero(digits),
+            };
+        }
+        let angle = (self.clone() / other.clone()).atan();
+        match (other.is_negative(), self.is_negative()) {
+            (false, _) => angle,
+            (true, false) => angle + half_pi.scaled(1),
+            (true, true) => angle - half_pi.scaled(1),
+        }
+    }
+
+    /// `e^(exp ln self)` for a positive `self`; the product's integer
+    /// digits are carried on top, as they are spent by `exp`.
+    pub fn pow(&self, exp: &Float) -> Float {
+        let digits = max(self.digits, exp.digits);
+        let estimate = exp.to_f64() * self.to_f64().ln();
+        let work = digits + GUARD_DIGITS + decimal_length(estimate);
+        let power = exp.with_digits(work) * self.with_digits(work).ln();
+        power.exp().with_digits(digits)
+    }
+
+    /// Real cube root.
+    pub fn cbrt(&self) -> Float {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS;
+        let third = Float::from_ratio(Long::from(1), Long::from(3), work);
+        let root = self.abs().with_digits(work).pow(&third).with_digits(digits);
+        if self.is_negative() {
+            -root
+        } else {
+            root
+        }
+    }
+
+    /// Logarithm to the base 10 of a positive `self`.
+    pub fn log10(&self) -> Float {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS;
+        (self.with_digits(work).ln() / Float::integer(10, work).ln()).with_digits(digits)
+    }
+
+    /// Value of an integral `self`.
+    fn to_long(&self) -> Long {
+        if self.exponent >= 0 {
+            self.mantissa.clone() << self.exponent as usize
+        } else {
+            self.mantissa.clone() >> (-self.exponent) as usize
+        }
+    }
+
+    /// Position just above the leading bit, `|self| < 2^top`.
+    fn top(&self) -> i64 {
+        self.exponent + self.mantissa.bits() as i64
+    }
+
+    fn aligned(&self, exponent: i64) -> Long {
+        if self.exponent >= exponent {
+            self.mantissa.clone() << (self.exponent - exponent) as usize
+        } else {
+            sticky_shr(self.mantissa.clone(), (exponent - self.exponent) as usize)
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.mantissa.bits();
+        if bits <= 64 {
+            return ldexp(self.mantissa.to_f64(), self.exponent);
+        }
+        let shift = bits - 64;
+        ldexp(
+            sticky_shr(self.mantissa.clone(), shift).to_f64(),
+            self.exponent + shift as i64,
+        )
+    }
+
+    /// Square root of the absolute value.
+    pub fn sqrt(&self) -> Float {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let precision = precision_bits(self.digits) as i64;
+        let bits = self.mantissa.bits() as i64;
+        let mut shift = max(0, 2 * (precision + 3) - bits + 1);
+        if (self.exponent - shift) % 2 != 0 {
+            shift += 1;
+        }
+
+        let scaled = self.mantissa.abs() << shift as usize;
+        let root = scaled.isqrt();
+        let exact = &root * &root == scaled;
+        let root = if exact { root << 1 } else { (root << 1) + Long::one() };
+
+        Float::new(root, (self.exponent - shift) / 2 - 1, self.digits)
+    }
+
+    /// Decimal digits of `|self|` rounded to `self.digits` places and the
+    /// decimal exponent of the first one.
+    fn decimal(&self) -> (String, i64) {
+        let digits = self.digits as i64;
+        let mut exp10 = ((self.top() - 1) as f64 * 2f64.log10()).floor() as i64;
+        loop {
+            let scale = digits - 1 - exp10;
+            let mut numer = self.mantissa.abs();
+            let mut denom = Long::one();
+            if scale >= 0 {
+                numer = numer * Long::from(10).pow(scale as u32);
+            } else {
+                denom = Long::from(10).pow((-scale) as u32);
+            }
+            if self.exponent >= 0 {
+                numer = numer << self.exponent as usize;
+            } else {
+                denom = denom << (-self.exponent) as usize;
+            }
+
+            let (quot, rem) = numer.div_rem(&denom);
+            let quot = if rem << 1 >= denom {
+                quot + Long::one()
+            } else {
+                quot
+            };
+            let text = quot.to_string();
+            match (text.len() as i64).cmp(&digits) {
+                Ordering::Greater => exp10 += 1,
+                Ordering::Less => exp10 -= 1,
+                Ordering::Equal => return (text, exp10),
+            }
+        }
+    }
+}
+
+/// Decimal digits of the integer part of `value`.
+fn decimal_length(value: f64) -> usize {
+    (value.abs() + 1.0).log10().ceil() as usize
+}
+
+/// `atan(1/n)`, or `atanh(1/n)` when `hyperbolic`, by the Taylor series
+/// with each power found by a division by the integer `n^2`.
+fn reciprocal_atan(n: i64, hyperbolic: bool, digits: usize) -> Float {
+    let square = Float::integer(n * n, digits);
+    let mut power = Float::from_ratio(Long::one(), Long::from(n), digits);
+    let mut sum = power.clone();
+    let mut k = 3;
+    loop {
+        power = power / square.clone();
+        let term = power.clone() / Float::integer(k, digits);
+        if term.negligible(&sum) {
+            return sum;
+        }
+        sum = if hyperbolic || k % 4 == 1 { sum + term } else { sum - term };
+        k += 2;
+    }
+}
+
+/// A constant to `digits` decimal digits, computed with `GUARD_DIGITS`
+/// more the first time that many are asked for.
+fn cached(cache: &'static LocalKey<RefCell<Option<Float>>>, digits: usize, compute: fn(usize) -> Float) -> Float {
+    cache.with(|cache| {
+        if let Some(value) = &*cache.borrow() {
+            if value.digits >= digits {
+                return value.with_digits(digits);
+            }
+        }
+        let value = compute(digits + GUARD_DIGITS).with_digits(digits);
+        *cache.borrow_mut() = Some(value.clone());
+        value
+    })
+}
+
+impl Display for Float {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return "0".fmt(f);
+        }
+        let (text, exp10) = self.decimal();
+        let text = text.trim_end_matches('0');
+        let mut result = String::new();
+        if self.is_negative() {
+            result.push('-');
+        }
+
+        if exp10 < -7 || exp10 >= self.digits as i64 {
+            result.push_str(&text[..1]);
+            if text.len() > 1 {
+                result.push('.');
+                result.push_str(&text[1..]);
+            }
+            result.push_str(&format!("e{}", exp10));
+        } else if exp10 < 0 {
+            result.push_str("0.");
+            result.push_str(&"0".repeat((-exp10 - 1) as usize));
+            result.push_str(text);
+        } else {
+            let point = exp10 as usize + 1;
+            if text.len() <= point {
+                result.push_str(text);
+                result.push_str(&"0".repeat(point - text.len()));
+            } else {
+                result.push_str(&text[..point]);
+                result.push('.');
+                result.push_str(&text[point..]);
+            }
+        }
+        result.fmt(f)
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Float) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Float {}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Float) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Float) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Long::zero().cmp(&other.mantissa),
+            (false, true) => return self.mantissa.cmp(&Long::zero()),
+            _ => {}
+        }
+        if self.is_negative() != other.is_negative() {
+            return self.mantissa.cmp(&other.mantissa);
+        }
+        let by_magnitude = match self.top().cmp(&other.top()) {
+            Ordering::Equal => {
+                let exponent = min(self.exponent, other.exponent);
+                self.aligned(exponent).abs().cmp(&other.aligned(exponent).abs())
+            }
+            ordering => ordering,
+        };
+        if self.is_negative() {
+            by_magnitude.reverse()
+        } else {
+            by_magnitude
+        }
+    }
+}
+
+impl Neg for Float {
+    type Output = Float;
+
+    fn neg(self) -> Float {
+        Float {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+            digits: self.digits,
+        }
+    }
+}
+
+impl Add for Float {
+    type Output = Float;
+
+    fn add(self, other: Float) -> Float {
+        let digits = max(self.digits, other.digits);
+        if self.is_zero() {
+            return other.with_digits(digits);
+        }
+        if other.is_zero() {
+            return self.with_digits(digits);
+        }
+
+        // bits far below the rounding position only matter as a sticky bit
+        let floor = max(self.top(), other.top()) - precision_bits(digits) as i64 - 3;
+        let exponent = max(min(self.exponent, other.exponent), floor);
+
+        Float::new(
+            self.aligned(exponent) + other.aligned(exponent),
+            exponent,
+            digits,
+        )
+    }
+}
+
+impl Sub for Float {
+    type Output = Float;
+
+    fn sub(self, other: Float) -> Float {
+        self + -other
+    }
+}
+
+impl Mul for Float {
+    type Output = Float;
+
+    fn mul(self, other: Float) -> Float {
+        Float::new(
+            self.mantissa * other.mantissa,
+            self.exponent + other.exponent,
+            max(self.digits, other.digits),
+        )
+    }
+}
+
+impl Div for Float {
+    type Output = Float;
+
+    fn div(self, other: Float) -> Float {
+        let digits = max(self.digits, other.digits);
+        let precision = precision_bits(digits) as i64;
+        let shift = max(
+            0,
+            precision + 3 + other.mantissa.bits() as i64 - self.mantissa.bits() as i64,
+        );
+
+        let (quot, rem) = (self.mantissa << shift as usize).div_rem(&other.mantissa);
+        let quot = quot << 1;
+        let quot = if rem.is_zero() {
+            quot
+        } else if rem.is_negative() != other.mantissa.is_negative() {
+            quot - Long::one()
+        } else {
+            quot + Long::one()
+        };
+
+        Float::new(quot, self.exponent - other.exponent - shift - 1, digits)
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Long, Number, Rational};
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Longest repetend searched for before a decimal expansion is cut
+/// short with `...`.
+const MAX_DECIMALS: usize = 1000;
+
+/// Digits after the point shown of an algebraic number in decimal
+/// notation, which never repeat.
+const ALGEBRAIC_DECIMALS: usize = 50;
+
+/// Significant digits of scientific notation when none are asked for.
+const DEFAULT_DIGITS: usize = 15;
+
+/// How numbers are written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    /// Every type its own way, `2 / 3` for rationals.
+    Default,
+    /// `2/3`
+    Fraction,
+    /// `1 1/3`
+    Mixed,
+    /// `0.1(6)`, the repeating digits in parentheses.
+    Decimal,
+    /// `0.667`, this many digits after the point.
+    Fixed(usize),
+    /// `6.66667e-1`, this many significant digits or up to fifteen.
+    Scientific(Option<usize>),
+    /// `666.667e-3`, scientific with the exponent a multiple of three.
+    Engineering(Option<usize>),
+    /// `0xff`, integers only.
+    Hex,
+    /// `0o377`, integers only.
+    Octal,
+    /// `0b11111111`, integers only.
+    Binary,
+}
+
+thread_local! {
+    static NOTATION: Cell<Notation> = const { Cell::new(Notation::Default) };
+}
+
+impl Notation {
+    /// Notation of results set for the session.
+    pub fn session() -> Notation {
+        NOTATION.with(|notation| notation.get())
+    }
+
+    pub fn set_session(notation: Notation) {
+        NOTATION.with(|cell| cell.set(notation))
+    }
+
+    /// Reads the words of a `:format` command, like `fixed 4` or `sci`.
+    pub fn parse(words: &[&str]) -> Option<Notation> {
+        let digits = |text: &str| text.parse::<usize>().ok();
+        match words {
+            ["default"] => Some(Notation::Default),
+            ["fraction"] => Some(Notation::Fraction),
+            ["mixed"] => Some(Notation::Mixed),
+            ["decimal"] => Some(Notation::Decimal),
+            ["fixed", text] => digits(text).map(Notation::Fixed),
+            ["sci" | "scientific"] => Some(Notation::Scientific(None)),
+            ["sci" | "scientific", text] => {
+                digits(text).filter(|n| *n > 0).map(|n| Notation::Scientific(Some(n)))
+            }
+            ["eng" | "engineering"] => Some(Notation::Engineering(None)),
+            ["eng" | "engineering", text] => {
+                digits(text).filter(|n| *n > 0).map(|n| Notation::Engineering(Some(n)))
+            }
+            ["hex"] => Some(Notation::Hex),
+            ["oct"] => Some(Notation::Octal),
+            ["bin"] => Some(Notation::Binary),
+            _ => None,
+        }
+    }
+
+    fn is_radix(self) -> bool {
+        matches!(self, Notation::Hex | Notation::Octal | Notation::Binary)
+    }
+
+    /// Fails when `number` cannot be written in this notation; the
+    /// session notation quietly falls back to the default instead.
+    pub fn check(self, number: &Number) -> Result<Notation, ArithmeticError> {
+        if self.is_radix() && exact(number).is_none_or(|rat| rat.denom != integer!(1)) {
+            return Err(ArithmeticError::Domain(format!("{} notation needs an integer", self)));
+        }
+        if self != Notation::Default && matches!(number, Number::Interval(_) | Number::Uncertain(_)) {
+            return Err(ArithmeticError::Domain(format!(
+                "{} notation is not defined for {} numbers",
+                self,
+                number.kind()
+            )));
+        }
+        if self != Notation::Default && matches!(number, Number::Real(_)) && exact(number).is_none() {
+            return Err(ArithmeticError::Domain(format!("{} is not finite", number)));
+        }
+        Ok(self)
+    }
+}
+
+impl Display for Notation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Notation::Default => "default".fmt(f),
+            Notation::Fraction => "fraction".fmt(f),
+            Notation::Mixed => "mixed".fmt(f),
+            Notation::Decimal => "decimal".fmt(f),
+            Notation::Fixed(digits) => write!(f, "fixed {}", digits),
+            Notation::Scientific(None) => "sci".fmt(f),
+            Notation::Scientific(Some(digits)) => write!(f, "sci {}", digits),
+            Notation::Engineering(None) => "eng".fmt(f),
+            Notation::Engineering(Some(digits)) => write!(f, "eng {}", digits),
+            Notation::Hex => "hex".fmt(f),
+            Notation::Octal => "oct".fmt(f),
+            Notation::Binary => "bin".fmt(f),
+        }
+    }
+}
+
+impl Number {
+    /// The number written in `notation`, or in the default one when it
+    /// does not apply, like hex for a fraction.
+    pub fn display(&self, notation: Notation) -> String {
+        if let Number::Complex(com) = self {
+            if notation.is_radix() || com.is_infinite() {
+                return com.to_string();
+            }
+            let real = Number::Real(com.real.clone()).display(notation);
+            let imag = Number::Real(com.imag.abs()).display(notation);
+            let sign = if com.imag.is_negative() { "-" } else { "+" };
+            return format!("{} {} {}i", real, sign, imag);
+        }
+        if let Number::Gaussian(gau) = self {
+            if notation == Notation::Default || notation.is_radix() {
+                return gau.to_string();
+            }
+            let real = Number::Rational(gau.real.clone()).display(notation);
+            let imag = Number::Rational(gau.imag.clone()).abs().display(notation);
+            let imag = if imag.contains('/') { format!("({})", imag) } else { imag };
+            let sign = if gau.imag.numer.value().is_negative() { "-" } else { "+" };
+            return format!("{} {} {}i", real, sign, imag);
+        }
+        if let Number::Interval(ivl) = self {
+            return ivl.to_string();
+        }
+        if let Number::Uncertain(unc) = self {
+            return unc.to_string();
+        }
+        if let Number::Algebraic(alg) = self {
+            // digits of bounds on both sides, once they agree
+            let digits = |show: &dyn Fn(&Long, &Long) -> String| {
+                alg.refined(|bound| show(&bound.numer.value(), &bound.denom.value()))
+            };
+            return match notation {
+                Notation::Decimal => digits(&|numer, denom| truncated(numer, denom, ALGEBRAIC_DECIMALS)) + "...",
+                Notation::Fixed(places) => digits(&|numer, denom| fixed(numer, denom, places)),
+                Notation::Scientific(count) => digits(&|numer, denom| scientific(numer, denom, count, 1)),
+                Notation::Engineering(count) => digits(&|numer, denom| scientific(numer, denom, count, 3)),
+                _ => alg.to_string(),
+            };
+        }
+        let rat = match exact(self) {
+            Some(rat) if notation != Notation::Default => rat,
+            _ => return self.default_display(),
+        };
+        let (numer, denom) = (rat.numer.value(), rat.denom.value());
+        match notation {
+            Notation::Default => unreachable!(),
+            Notation::Fraction => fraction(&numer, &denom),
+            Notation::Mixed => mixed(&numer, &denom),
+            Notation::Decimal => decimal(&numer, &denom),
+            Notation::Fixed(places) => fixed(&numer, &denom, places),
+            Notation::Scientific(digits) => scientific(&numer, &denom, digits, 1),
+            Notation::Engineering(digits) => scientific(&numer, &denom, digits, 3),
+            _ if denom != Long::one() => self.default_display(),
+            Notation::Hex => format!("{:#x}", numer),
+            Notation::Octal => format!("{:#o}", numer),
+            Notation::Binary => format!("{:#b}", numer),
+        }
+    }
+
+    fn default_display(&self) -> String {
+        match self {
+            Number::Natural(val) => val.to_string(),
+            Number::Integer(val) => val.to_string(),
+            Number::Decimal(val) => val.to_string(),
+            Number::Rational(val) => val.to_string(),
+            Number::Algebraic(val) => val.to_string(),
+            Number::Real(val) => val.to_string(),
+            Number::Interval(val) => val.to_string(),
+            Number::Uncertain(val) => val.to_string(),
+            Number::Dual(val) => val.to_string(),
+            Number::Complex(val) => val.to_string(),
+            Number::Gaussian(val) => val.to_string(),
+            Number::Modular(val) => val.to_string(),
+        }
+    }
+}
+
+/// Exact value of a number, reals are taken as the decimal they print as.
+fn exact(number: &Number) -> Option<Rational> {
+    match number {
+        Number::Real(rea) => rea.to_decimal(),
+        Number::Uncertain(_) | Number::Complex(_) | Number::Gaussian(_) => None,
+        number => number.to_rational(),
+    }
+}
+
+fn sign(numer: &Long) -> &'static str {
+    if numer.is_negative() {
+        "-"
+    } else {
+        ""
+    }
+}
+
+/// `numer / denom` rounded half to even, for a positive `denom`.
+fn round(numer: &Long, denom: &Long) -> Long {
+    let (quot, rem) = numer.abs().div_rem(denom);
+    let quot = match (rem << 1).cmp(denom) {
+        Ordering::Greater => quot + Long::one(),
+        Ordering::Equal if quot.is_odd() => quot + Long::one(),
+        _ => quot,
+    };
+    if numer.is_negative() {
+        -quot
+    } else {
+        quot
+    }
+}
+
+fn fraction(numer: &Long, denom: &Long) -> String {
+    if *denom == Long::one() {
+        numer.to_string()
+    } else {
+        format!("{}/{}", numer, denom)
+    }
+}
+
+fn mixed(numer: &Long, denom: &Long) -> String {
+    let (whole, rem) = numer.abs().div_rem(denom);
+    if whole.is_zero() || rem.is_zero() {
+        fraction(numer, denom)
+    } else {
+        format!("{}{} {}/{}", sign(numer), whole, rem, denom)
+    }
+}
+
+fn decimal(numer: &Long, denom: &Long) -> String {
+    let (whole, mut rem) = numer.abs().div_rem(denom);
+    let mut text = format!("{}{}", sign(numer), whole);
+    if rem.is_zero() {
+        return text;
+    }
+
+    text.push('.');
+    let mut seen = HashMap::new();
+    let mut digits = String::new();
+    while !rem.is_zero() {
+        if let Some(start) = seen.insert(rem.clone(), digits.len()) {
+            digits.insert(start, '(');
+            digits.push(')');
+            break;
+        }
+        if digits.len() == MAX_DECIMALS {
+            digits.push_str("...");
+            break;
+        }
+        let (digit, next) = (rem * Long::from(10)).div_rem(denom);
+        digits.push_str(&digit.to_string());
+        rem = next;
+    }
+    text + &digits
+}
+
+fn fixed(numer: &Long, denom: &Long, places: usize) -> String {
+    let scaled = round(&(numer * &Long::from(10).pow(places as u32)), denom);
+    let digits = format!("{:0>1$}", scaled.abs(), places + 1);
+    let point = digits.len() - places;
+    let mut text = format!("{}{}", sign(&scaled), &digits[..point]);
+    if places > 0 {
+        text.push('.');
+        text.push_str(&digits[point..]);
+    }
+    text
+}
+
+/// `numer / denom` cut after `places` digits after the point.
+fn truncated(numer: &Long, denom: &Long, places: usize) -> String {
+    let scaled = (numer.abs() * Long::from(10).pow(places as u32)) / denom.clone();
+    let digits = format!("{:0>1$}", scaled, places + 1);
+    let point = digits.len() - places;
+    format!("{}{}.{}", sign(numer), &digits[..point], &digits[point..])
+}
+
+/// Significant digits of `|numer / denom|` rounded to `count` places and
+/// the decimal exponent of the first one.
+fn significant(numer: &Long, denom: &Long, count: usize) -> (String, i64) {
+    let mut exponent = numer.abs().to_string().len() as i64 - denom.to_string().len() as i64;
+    loop {
+        let scale = count as i64 - 1 - exponent;
+        let power = Long::from(10).pow(scale.unsigned_abs() as u32);
+        let digits = if scale >= 0 {
+            round(&(numer.abs() * power), denom)
+        } else {
+            round(&numer.abs(), &(denom * &power))
+        }
+        .to_string();
+        match digits.len().cmp(&count) {
+            Ordering::Greater => exponent += 1,
+            Ordering::Less => exponent -= 1,
+            Ordering::Equal => return (digits, exponent),
+        }
+    }
+}
+
+/// Scientific notation with an exponent that is a multiple of `step`.
+fn scientific(numer: &Long, denom: &Long, count: Option<usize>, step: i64) -> String {
+    let (mut digits, exponent) = match numer.is_zero() {
+        true => ("0".repeat(count.unwrap_or(1)), 0),
+        false => significant(numer, denom, count.unwrap_or(DEFAULT_DIGITS)),
+    };
+    let shift = exponent.rem_euclid(step) as usize;
+    let point = shift + 1;
+    if digits.len() < point {
+        digits.push_str(&"0".repeat(point - digits.len()));
+    }
+
+    let mut fraction = &digits[point..];
+    if count.is_none() {
+        fraction = fraction.trim_end_matches('0');
+    }
+    let mut text = format!("{}{}", sign(numer), &digits[..point]);
+    if !fraction.is_empty() {
+        text.push('.');
+        text.push_str(fraction);
+    }
+    format!("{}e{}", text, exponent - shift as i64)
+}
@@ -0,0 +1,333 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Complex, Gaussian, Integer, Number, Rational, Real, Sqrt};
+
+use std::cmp::Ordering;
+
+/// Elementary function computed in the precision of reals on its real
+/// domain and as a principal complex value outside of it.
+macro_rules! impl_elementary {
+    ($($name:ident: $domain:expr),*) => {
+        impl Number {
+            $(
+                pub fn $name(self) -> Result<Number, ArithmeticError> {
+                    if let Number::Interval(ivl) = self {
+                        return ivl.$name().map(Number::Interval);
+                    }
+                    if let Number::Uncertain(unc) = self {
+                        let result = Number::Uncertain(unc.$name()?);
+                        return finite(stringify!($name), Number::Uncertain(unc), result);
+                    }
+                    if let Number::Dual(dual) = self {
+                        let result = Number::Dual(dual.$name()?);
+                        return finite(stringify!($name), Number::Dual(dual), result);
+                    }
+                    let domain: fn(f64) -> bool = $domain;
+                    let result = match self.to_real() {
+                        Some(x) if domain(x.value()) => Number::Real(x.$name()),
+                        _ => Number::Complex(self.to_complex().$name()),
+                    };
+                    finite(stringify!($name), self, result)
+                }
+            )*
+        }
+    };
+}
+
+impl_elementary! {
+    exp: |_| true,
+    ln: |x| x >= 0.0,
+    sin: |_| true,
+    cos: |_| true,
+    tan: |_| true,
+    asin: |x| x.abs() <= 1.0,
+    acos: |x| x.abs() <= 1.0,
+    atan: |_| true,
+    sinh: |_| true,
+    cosh: |_| true,
+    tanh: |_| true,
+    asinh: |_| true,
+    acosh: |x| x >= 1.0,
+    atanh: |x| x.abs() <= 1.0
+}
+
+/// Significant digits of an algebraic number taken for its exact value.
+const APPROXIMATE_DIGITS: u32 = 40;
+
+/// A finite argument must give a finite result in strict sessions,
+/// otherwise the infinities and NaN of IEEE arithmetic are results.
+fn finite(name: &str, arg: Number, result: Number) -> Result<Number, ArithmeticError> {
+    if Real::strict() && arg.is_finite() && !result.is_finite() {
+        return Err(ArithmeticError::Domain(format!("{} of {} is not finite", name, arg)));
+    }
+    Ok(result)
+}
+
+/// Square root of a rational that is a perfect square.
+fn exact_sqrt(rat: &Rational) -> Option<Rational> {
+    let numer = rat.numer.value().abs();
+    let denom = rat.denom.value();
+    let (root_numer, root_denom) = (numer.isqrt(), denom.isqrt());
+    if &root_numer * &root_numer == numer && &root_denom * &root_denom == denom {
+        Some(Rational::new(Integer::new(root_numer), Integer::new(root_denom)))
+    } else {
+        None
+    }
+}
+
+impl Number {
+    /// Principal square root, exact for exact numbers that are not
+    /// negative and for negative perfect squares.
+    pub fn sqrt(self) -> Result<Number, ArithmeticError> {
+        if let Number::Interval(ivl) = self {
+            return ivl.sqrt().map(Number::Interval);
+        }
+        if let Number::Uncertain(unc) = self {
+            let result = Number::Uncertain(unc.sqrt()?);
+            return finite("sqrt", Number::Uncertain(unc), result);
+        }
+        if let Number::Dual(dual) = self {
+            let result = Number::Dual(dual.sqrt()?);
+            return finite("sqrt", Number::Dual(dual), result);
+        }
+        let negative = self.is_negative();
+        if let Some(root) = self.to_rational().as_ref().and_then(exact_sqrt) {
+            return Ok(if negative {
+                Number::Gaussian(Gaussian::new(rational!(0), root))
+            } else {
+                Number::Rational(root).normalize()
+            });
+        }
+        if let Some(root) = self.exact_root(2) {
+            return Ok(root);
+        }
+        let result = match self.to_real() {
+            Some(x) if !negative => Number::Real(x.sqrt_abs()),
+            Some(x) => Number::Complex(Sqrt::<Complex>::sqrt(&x)),
+            None => Number::Complex(self.to_complex().sqrt()),
+        };
+        finite("sqrt", self, result)
+    }
+
+    /// Real cube root, exact for exact numbers.
+    pub fn cbrt(self) -> Result<Number, ArithmeticError> {
+        if let Some(root) = self.exact_root(3) {
+            return Ok(root);
+        }
+        if let Number::Interval(ivl) = self {
+            return ivl.cbrt().map(Number::Interval);
+        }
+        if let Number::Uncertain(unc) = self {
+            let result = Number::Uncertain(unc.cbrt()?);
+            return finite("cbrt", Number::Uncertain(unc), result);
+        }
+        if let Number::Dual(dual) = self {
+            let result = Number::Dual(dual.cbrt()?);
+            return finite("cbrt", Number::Dual(dual), result);
+        }
+        let result = match self.to_real() {
+            Some(x) => Number::Real(x.cbrt()),
+            None => Number::Complex(self.to_complex().cbrt()),
+        };
+        finite("cbrt", self, result)
+    }
+
+    /// Logarithm to the base 10.
+    pub fn log(self) -> Result<Number, ArithmeticError> {
+        match self.to_real() {
+            Some(x) if x.value() > 0.0 => Ok(Number::Real(x.log10())),
+            _ => self.log_base(Number::Natural(natural!(10))),
+        }
+    }
+
+    pub fn log_base(self, base: Number) -> Result<Number, ArithmeticError> {
+        let arg = self.clone();
+        let result = self.ln()?.checked_div(base.ln()?)?;
+        finite("log", arg, result)
+    }
+
+    /// Angle of the point `(x, y)`, in `(-pi, pi]`.
+    pub fn atan2(self, x: Number) -> Result<Number, ArithmeticError> {
+        if self.is_dual() || x.is_dual() {
+            if let (Some(y), Some(x)) = (self.to_dual(), x.to_dual()) {
+                return Ok(Number::Dual(y.atan2(&x)));
+            }
+        }
+        match (self.to_real(), x.to_real()) {
+            (Some(y), Some(x)) => Ok(Number::Real(y.atan2(&x))),
+            _ => Err(ArithmeticError::Domain(format!(
+                "atan2 is not defined for {} numbers",
+                if self.to_real().is_none() { self.kind() } else { x.kind() }
+            ))),
+        }
+    }
+
+    /// Whether `|self - other| <= tolerance`, regardless of the session
+    /// tolerance.
+    pub fn approx(self, other: Number, tolerance: Number) -> Result<bool, ArithmeticError> {
+        if tolerance.is_negative() {
+            return Err(ArithmeticError::Domain("tolerance must not be negative".to_owned()));
+        }
+        let diff = self.checked_sub(other)?.abs();
+        let ordering = match (diff.to_rational(), tolerance.to_rational()) {
+            (Some(diff), Some(tolerance)) => diff.cmp(&tolerance),
+            _ => match (diff.to_real(), tolerance.to_real()) {
+                (Some(diff), Some(tolerance)) => {
+                    diff.cmp_exact(&tolerance).unwrap_or(Ordering::Greater)
+                }
+                _ => return Err(ArithmeticError::Domain("tolerance must be real".to_owned())),
+            },
+        };
+        Ok(ordering != Ordering::Greater)
+    }
+
+    /// Exact value a number stands for, reals are taken as the decimal
+    /// they are shown as and algebraic numbers to `APPROXIMATE_DIGITS`
+    /// significant digits.
+    fn exact_value(&self, name: &str) -> Result<Rational, ArithmeticError> {
+        match self {
+            Number::Real(rea) => rea
+                .to_decimal()
+                .ok_or_else(|| ArithmeticError::Domain(format!("{} of {} is not defined", name, self))),
+            Number::Algebraic(alg) => Ok(alg.approximate(APPROXIMATE_DIGITS)),
+            Number::Interval(_) | Number::Uncertain(_) | Number::Dual(_) | Number::Complex(_) | Number::Gaussian(_) => {
+                Err(ArithmeticError::Domain(format!(
+                "{} is not defined for {} numbers",
+                    name,
+                    self.kind()
+                )))
+            }
+            number => Ok(number.to_rational().unwrap()),
+        }
+    }
+
+    /// Best rational approximation: the closest one with a denominator
+    /// up to a natural `bound`, or the simplest one within a rational or
+    /// real `bound` of `self`.
+    pub fn rationalize(self, bound: Number) -> Result<Number, ArithmeticError> {
+        let closest = |value: &Rational| match bound {
+            Number::Natural(ref max_denom) if !max_denom.value().is_zero() => {
+                Ok(value.limit_denominator(&max_denom.value()))
+            }
+            ref tolerance => {
+                let tolerance = tolerance.exact_value("rationalize")?;
+                if tolerance < Rational::default() {
+                    return Err(ArithmeticError::Domain("tolerance must not be negative".to_owned()));
+                }
+                Ok(Rational::simplest_between(&(value.clone() - tolerance.clone()), &(value.clone() + tolerance)))
+            }
+        };
+        // the answer for bounds on both sides of an algebraic number is
+        // the answer for the number
+        let result = match self {
+            Number::Algebraic(ref alg) => alg.refined(closest)?,
+            _ => closest(&self.exact_value("rationalize")?)?,
+        };
+        Ok(Number::Rational(result))
+    }
+
+    /// First `count` terms of the continued fraction.
+    pub fn cfrac(self, count: usize) -> Result<Vec<Number>, ArithmeticError> {
+        let terms = match self {
+            Number::Algebraic(ref alg) => alg.continued_fraction(count),
+            _ => self.exact_value("cfrac")?.continued_fraction(count),
+        };
+        Ok(terms.into_iter().map(Number::Integer).collect())
+    }
+
+    /// Convergents of the first `count` terms of the continued fraction.
+    pub fn convergents(self, count: usize) -> Result<Vec<Number>, ArithmeticError> {
+        let convergents = match self {
+            Number::Algebraic(ref alg) => Rational::from_terms(&alg.continued_fraction(count)),
+            _ => self.exact_value("convergents")?.convergents(count),
+        };
+        Ok(convergents.into_iter().map(Number::Rational).collect())
+    }
+
+    /// Absolute value, the modulus for complex numbers.
+    pub fn abs(self) -> Number {
+        match self {
+            Number::Natural(nat) => Number::Natural(nat),
+            Number::Integer(int) => Number::Natural(int.abs()),
+            Number::Decimal(dec) => Number::Decimal(dec.abs()),
+            Number::Rational(rat) => {
+                let numer = Integer::new(rat.numer.value().abs());
+                Number::Rational(Rational::new(numer, rat.denom))
+            }
+            Number::Algebraic(alg) => Number::Algebraic(alg.abs()),
+            Number::Real(rea) => Number::Real(rea.abs()),
+            Number::Interval(ivl) => Number::Interval(ivl.abs()),
+            Number::Uncertain(unc) => Number::Uncertain(unc.abs()),
+            Number::Dual(dual) => Number::Dual(dual.abs()),
+            Number::Complex(com) => Number::Real(com.abs()),
+            Number::Gaussian(gau) => match exact_sqrt(&gau.norm_sqr()) {
+                Some(modulus) => Number::Rational(modulus).normalize(),
+                None => Number::Real(Real::from(gau.norm_sqr()).sqrt_abs()),
+            },
+            Number::Modular(m) => Number::Modular(m),
+        }
+    }
+
+    /// Principal argument, `pi` for negative reals.
+    pub fn arg(self) -> Number {
+        match self {
+            Number::Interval(ivl) => Number::Interval(ivl.arg()),
+            Number::Uncertain(unc) => Number::Real(Real::new(unc.value())).arg(),
+            Number::Dual(dual) => Number::Real(Real::new(dual.value())).arg(),
+            number => Number::Real(number.to_complex().arg()),
+        }
+    }
+
+    /// Complex number with the given modulus and argument.
+    pub fn from_polar(modulus: Number, angle: Number) -> Result<Number, ArithmeticError> {
+        match (modulus.to_real(), angle.to_real()) {
+            (Some(modulus), Some(angle)) => Ok(Number::Complex(Complex::from_polar(modulus, angle))),
+            _ => Err(ArithmeticError::Domain(
+                "polar coordinates must be real".to_owned(),
+            )),
+        }
+    }
+
+    /// `cos(angle) + i sin(angle)`
+    pub fn cis(angle: Number) -> Result<Number, ArithmeticError> {
+        Number::from_polar(Number::Natural(natural!(1)), angle)
+    }
+
+    pub fn re(self) -> Number {
+        match self {
+            Number::Complex(com) if com.is_infinite() => Number::Real(Real::new(f64::NAN)),
+            Number::Complex(com) => Number::Real(com.real),
+            Number::Gaussian(gau) => Number::Rational(gau.real).normalize(),
+            number => number,
+        }
+    }
+
+    pub fn im(self) -> Number {
+        match self {
+            Number::Complex(com) if com.is_infinite() => Number::Real(Real::new(f64::NAN)),
+            Number::Complex(com) => Number::Real(com.imag),
+            Number::Gaussian(gau) => Number::Rational(gau.imag).normalize(),
+            _ => Number::Natural(natural!(0)),
+        }
+    }
+
+    pub fn conj(self) -> Number {
+        match self {
+            Number::Complex(com) => Number::Complex(com.conj()),
+            Number::Gaussian(gau) => Number::Gaussian(gau.conj()),
+            number => number,
+        }
+    }
+}
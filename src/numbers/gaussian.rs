@@ -0,0 +1,137 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Rational};
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Exact complex number `a + bi` with rational parts, a Gaussian integer
+/// when both of them are integral.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Gaussian {
+    pub(crate) real: Rational,
+    pub(crate) imag: Rational,
+}
+
+impl Gaussian {
+    pub fn new<U, V>(real: U, imag: V) -> Gaussian
+    where
+        U: Into<Rational>,
+        V: Into<Rational>,
+    {
+        Gaussian {
+            real: real.into(),
+            imag: imag.into(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.real == Rational::default() && self.imag == Rational::default()
+    }
+
+    pub fn conj(&self) -> Gaussian {
+        Gaussian::new(self.real.clone(), -self.imag.clone())
+    }
+
+    /// Squared modulus `re^2 + im^2`.
+    pub fn norm_sqr(&self) -> Rational {
+        self.real.clone() * self.real.clone() + self.imag.clone() * self.imag.clone()
+    }
+
+    pub fn inv(&self) -> Gaussian {
+        Gaussian::new(rational!(1), rational!(0)) / self.clone()
+    }
+
+    /// Complex numbers are not ordered, only equal ones and ones on the
+    /// real line can be compared.
+    pub fn checked_cmp(&self, other: &Gaussian) -> Result<Ordering, ArithmeticError> {
+        if self == other {
+            return Ok(Ordering::Equal);
+        }
+        if self.imag == Rational::default() && other.imag == Rational::default() {
+            return Ok(self.real.cmp(&other.real));
+        }
+        Err(ArithmeticError::Domain("cannot order complex numbers".to_owned()))
+    }
+}
+
+/// Fractional parts are written without spaces and an imaginary one in
+/// parentheses, `1/2 + (3/2)i`.
+impl Display for Gaussian {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let part = |rat: &Rational| match rat.denom == integer!(1) {
+            true => rat.numer.to_string(),
+            false => format!("{} / {}", rat.numer, rat.denom),
+        };
+        part(&self.real).fmt(f)?;
+        let imag = if self.imag < Rational::default() {
+            " - ".fmt(f)?;
+            -self.imag.clone()
+        } else {
+            " + ".fmt(f)?;
+            self.imag.clone()
+        };
+        match imag.denom == integer!(1) {
+            true => write!(f, "{}i", part(&imag)),
+            false => write!(f, "({})i", part(&imag)),
+        }
+    }
+}
+
+impl Neg for Gaussian {
+    type Output = Gaussian;
+
+    fn neg(self) -> Gaussian {
+        Gaussian::new(-self.real, -self.imag)
+    }
+}
+
+impl Add for Gaussian {
+    type Output = Gaussian;
+
+    fn add(self, other: Gaussian) -> Gaussian {
+        Gaussian::new(self.real + other.real, self.imag + other.imag)
+    }
+}
+
+impl Sub for Gaussian {
+    type Output = Gaussian;
+
+    fn sub(self, other: Gaussian) -> Gaussian {
+        Gaussian::new(self.real - other.real, self.imag - other.imag)
+    }
+}
+
+impl Mul for Gaussian {
+    type Output = Gaussian;
+
+    fn mul(self, other: Gaussian) -> Gaussian {
+        Gaussian::new(
+            self.real.clone() * other.real.clone() - self.imag.clone() * other.imag.clone(),
+            self.imag * other.real + other.imag * self.real,
+        )
+    }
+}
+
+impl Div for Gaussian {
+    type Output = Gaussian;
+
+    fn div(self, other: Gaussian) -> Gaussian {
+        let numer = self * other.conj();
+        let denom = other.norm_sqr();
+        Gaussian::new(numer.real / denom.clone(), numer.imag / denom)
+    }
+}
@@ -12,30 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::Natural;
+use super::{Long, Natural};
 
 use std::convert::From;
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
-pub struct Integer(i128); // TODO: --//--
+#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone)]
+pub struct Integer(Long);
 
 impl Integer {
-    pub fn new<T: Into<i128>>(int: T) -> Integer {
+    pub fn new<T: Into<Long>>(int: T) -> Integer {
         Integer(int.into())
     }
 
     pub fn abs(&self) -> Natural {
-        if self.0 >= 0 {
-            Natural::new(self.0)
-        } else {
-            Natural::new(-self.0)
-        }
+        Natural::new(self.0.abs())
     }
 
-    pub fn value(&self) -> i128 {
-        self.0
+    pub fn value(&self) -> Long {
+        self.0.clone()
     }
 }
 
@@ -45,7 +41,7 @@ impl Display for Integer {
     }
 }
 
-impl<T: Into<i128>> From<T> for Integer {
+impl<T: Into<Long>> From<T> for Integer {
     fn from(some: T) -> Integer {
         Integer::new(some.into())
     }
@@ -62,10 +58,10 @@ impl Neg for Integer {
 #[macro_export]
 macro_rules! integer {
     ($int:tt) => {
-        $crate::numbers::Integer::new($int as i128)
+        $crate::numbers::Integer::new($int)
     };
     (- $int:tt) => {
-        $crate::numbers::Integer::new(-$int as i128)
+        $crate::numbers::Integer::new(-$int)
     };
 }
 
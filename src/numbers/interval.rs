@@ -0,0 +1,632 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Integer, Long, Number, Operation, Rational, Real};
+
+use std::cmp::Ordering;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Closed interval `[lo, hi]` of `f64` bounds that encloses the exact
+/// value: every operation rounds its lower bound down and its upper bound
+/// up, so the true result can't escape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+/// Outcome of comparing intervals, the relation may hold for some of
+/// their values and not for others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Certainty {
+    Never,
+    Possibly,
+    Certainly,
+}
+
+impl From<bool> for Certainty {
+    fn from(holds: bool) -> Certainty {
+        if holds {
+            Certainty::Certainly
+        } else {
+            Certainty::Never
+        }
+    }
+}
+
+impl Certainty {
+    /// Kleene negation, `Possibly` stays.
+    pub fn not(self) -> Certainty {
+        match self {
+            Certainty::Never => Certainty::Certainly,
+            Certainty::Possibly => Certainty::Possibly,
+            Certainty::Certainly => Certainty::Never,
+        }
+    }
+}
+
+/// Rounded result and the sign of its rounding error, `NaN` when the sign
+/// is unknown.
+///
+/// Sums are `TwoSum`, products and quotients take the error from `fma`,
+/// which is not exact below the normal range.
+fn add_err(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_part = sum - a;
+    (sum, (a - (sum - b_part)) + (b - b_part))
+}
+
+fn mul_err(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    if product.abs() < f64::MIN_POSITIVE && a != 0.0 && b != 0.0 {
+        return (product, underflow(product, (a < 0.0) != (b < 0.0)));
+    }
+    (product, a.mul_add(b, -product))
+}
+
+fn div_err(a: f64, b: f64) -> (f64, f64) {
+    let quot = a / b;
+    if quot.abs() < f64::MIN_POSITIVE && a != 0.0 {
+        return (quot, underflow(quot, (a < 0.0) != (b < 0.0)));
+    }
+    // `a - quot * b` is exact, its sign over the one of `b` tells the side
+    let rem = -quot.mul_add(b, -a);
+    (quot, if b < 0.0 { -rem } else { rem })
+}
+
+/// Error of a result below the normal range: a zero is off toward the
+/// sign of the exact result, other values either way.
+fn underflow(value: f64, negative: bool) -> f64 {
+    match (value == 0.0, negative) {
+        (true, true) => -1.0,
+        (true, false) => 1.0,
+        _ => f64::NAN,
+    }
+}
+
+fn down((value, err): (f64, f64)) -> f64 {
+    if err < 0.0 || err.is_nan() {
+        value.next_down()
+    } else {
+        value
+    }
+}
+
+fn up((value, err): (f64, f64)) -> f64 {
+    if err > 0.0 || err.is_nan() {
+        value.next_up()
+    } else {
+        value
+    }
+}
+
+/// Exact value of a finite `f64`.
+fn exact(x: f64) -> Rational {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mantissa, exponent) = match exponent {
+        0 => (fraction, -1074),
+        _ => (fraction | (1 << 52), exponent - 1075),
+    };
+    let mantissa = if x < 0.0 { -Long::from(mantissa) } else { Long::from(mantissa) };
+    if exponent >= 0 {
+        Rational::new(Integer::new(mantissa << exponent as usize), integer!(1))
+    } else {
+        Rational::new(Integer::new(mantissa), Integer::new(Long::one() << (-exponent) as usize))
+    }
+}
+
+/// Whether `offset + k * period` lies in `[lo, hi]` for some `k`, erring
+/// on the side of yes near the bounds.
+fn hits(lo: f64, hi: f64, offset: f64, period: f64) -> bool {
+    let slack = 1e-12 * lo.abs().max(hi.abs()).max(1.0);
+    let k = ((lo - offset) / period).floor();
+    [k, k + 1.0]
+        .iter()
+        .map(|k| offset + k * period)
+        .any(|point| point >= lo - slack && point <= hi + slack)
+}
+
+/// `f(x)` rounded outward: `libm` is within an ulp, and exact at the point
+/// `x = exact`.
+fn outward(f: fn(f64) -> f64, x: f64, exact: f64) -> (f64, f64) {
+    let y = f(x);
+    if x == exact {
+        (y, y)
+    } else {
+        (y.next_down(), y.next_up())
+    }
+}
+
+/// Functions increasing on their domain, with the point where `libm`
+/// gives them exactly.
+macro_rules! impl_increasing {
+    ($($name:ident: $domain:expr, $exact:expr),*) => {
+        impl Interval {
+            $(
+                pub fn $name(&self) -> Result<Interval, ArithmeticError> {
+                    let domain: fn(f64) -> bool = $domain;
+                    if !domain(self.lo) || !domain(self.hi) {
+                        return Err(outside(stringify!($name), self));
+                    }
+                    let (lo, _) = outward(f64::$name, self.lo, $exact);
+                    let (_, hi) = outward(f64::$name, self.hi, $exact);
+                    Ok(Interval::new(lo, hi))
+                }
+            )*
+        }
+    };
+}
+
+impl_increasing! {
+    exp: |_| true, 0.0,
+    ln: |x| x > 0.0, 1.0,
+    asin: |x| x.abs() <= 1.0, 0.0,
+    atan: |_| true, 0.0,
+    sinh: |_| true, 0.0,
+    tanh: |_| true, 0.0,
+    asinh: |_| true, 0.0,
+    acosh: |x| x >= 1.0, 1.0,
+    atanh: |x| x.abs() < 1.0, 0.0,
+    cbrt: |_| true, 0.0
+}
+
+fn outside(name: &str, interval: &Interval) -> ArithmeticError {
+    ArithmeticError::Domain(format!("{} is not defined on all of {}", name, interval))
+}
+
+fn divisor_with_zero(interval: &Interval) -> ArithmeticError {
+    ArithmeticError::Domain(format!("divisor {} contains zero", interval))
+}
+
+impl Interval {
+    /// `[lo, hi]`, a reversed or `NaN` bound is a bug of the caller.
+    pub fn new(lo: f64, hi: f64) -> Interval {
+        if lo.is_nan() || hi.is_nan() || lo > hi {
+            panic!("bad bounds [{}, {}] of Interval", lo, hi)
+        }
+        Interval { lo, hi }
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn contains_zero(&self) -> bool {
+        self.lo <= 0.0 && self.hi >= 0.0
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.lo.is_finite() && self.hi.is_finite()
+    }
+
+    /// Upper bound of `hi - lo`.
+    pub fn width(&self) -> f64 {
+        up(add_err(self.hi, -self.lo))
+    }
+
+    pub fn mid(&self) -> f64 {
+        self.lo / 2.0 + self.hi / 2.0
+    }
+
+    /// Relation of every pair of values: `Certainly` when `holds` is true
+    /// for all of them, `Never` when for none.
+    pub fn relation(&self, other: &Interval, holds: fn(Ordering) -> bool) -> Certainty {
+        let possible = [
+            (Ordering::Less, self.lo < other.hi),
+            (Ordering::Equal, self.lo <= other.hi && other.lo <= self.hi),
+            (Ordering::Greater, self.hi > other.lo),
+        ];
+        let outcomes: Vec<bool> = possible
+            .iter()
+            .filter(|(_, possible)| *possible)
+            .map(|(ordering, _)| holds(*ordering))
+            .collect();
+        match (outcomes.iter().all(|holds| *holds), outcomes.iter().any(|holds| *holds)) {
+            (true, _) => Certainty::Certainly,
+            (false, true) => Certainty::Possibly,
+            (false, false) => Certainty::Never,
+        }
+    }
+
+    pub fn abs(&self) -> Interval {
+        match (self.lo >= 0.0, self.hi <= 0.0) {
+            (true, _) => *self,
+            (_, true) => -*self,
+            _ => Interval::new(0.0, self.hi.max(-self.lo)),
+        }
+    }
+
+    /// Floors of the bounds.
+    pub fn floor(&self) -> Interval {
+        Interval::new(self.lo.floor(), self.hi.floor())
+    }
+
+    /// Integer parts of the bounds, rounding toward zero keeps the order.
+    pub fn trunc(&self) -> Interval {
+        Interval::new(self.lo.trunc(), self.hi.trunc())
+    }
+
+    /// Nearest integers to the bounds, halves to the even one.
+    pub fn round(&self) -> Interval {
+        Interval::new(self.lo.round_ties_even(), self.hi.round_ties_even())
+    }
+
+    /// Square root, exact where `f64` hits it.
+    pub fn sqrt(&self) -> Result<Interval, ArithmeticError> {
+        if self.lo < 0.0 {
+            return Err(outside("sqrt", self));
+        }
+        // `x - root^2` is exact and has the sign of the error
+        let root = |x: f64| {
+            let root = x.sqrt();
+            match x != 0.0 && x < f64::MIN_POSITIVE {
+                true => (root, f64::NAN),
+                false => (root, -root.mul_add(root, -x)),
+            }
+        };
+        Ok(Interval::new(down(root(self.lo)), up(root(self.hi))))
+    }
+
+    /// `self^exp` for a whole `exp`, tighter than repeated products since
+    /// even powers are never negative.
+    pub fn powi(&self, exp: &Long) -> Result<Interval, ArithmeticError> {
+        if exp.is_negative() {
+            if self.contains_zero() {
+                return Err(divisor_with_zero(self));
+            }
+            return Ok(Interval::new(1.0, 1.0) / self.powi(&-exp)?);
+        }
+        let (lo, hi) = match (exp.is_odd(), self.lo >= 0.0, self.hi <= 0.0) {
+            (true, _, _) | (false, true, _) => (self.lo, self.hi),
+            (false, _, true) => (-self.hi, -self.lo),
+            (false, false, false) => (0.0, self.hi.max(-self.lo)),
+        };
+        let magnitude = exp.low_u64();
+        // rounding every product the same way bounds a power of `x >= 0`
+        let power = |x: f64, round: fn((f64, f64)) -> f64| {
+            let (mut result, mut base, mut exp) = (1.0, x, magnitude);
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    result = round(mul_err(result, base));
+                }
+                exp >>= 1;
+                if exp > 0 {
+                    base = round(mul_err(base, base));
+                }
+            }
+            result
+        };
+        // odd powers of negative bounds round the other way
+        let lower = |x: f64| if x < 0.0 { -power(-x, up) } else { power(x, down) };
+        let upper = |x: f64| if x < 0.0 { -power(-x, down) } else { power(x, up) };
+        Ok(Interval::new(lower(lo), upper(hi)))
+    }
+
+    /// `self^exp` as `exp(exp * ln(self))`, for positive bases.
+    pub fn powf(&self, exp: &Interval) -> Result<Interval, ArithmeticError> {
+        if self.lo <= 0.0 {
+            return Err(outside("exponentiation", self));
+        }
+        (*exp * self.ln()?).exp()
+    }
+
+    pub fn acos(&self) -> Result<Interval, ArithmeticError> {
+        if self.lo < -1.0 || self.hi > 1.0 {
+            return Err(outside("acos", self));
+        }
+        let (lo, _) = outward(f64::acos, self.hi, 1.0);
+        let (_, hi) = outward(f64::acos, self.lo, 1.0);
+        Ok(Interval::new(lo.max(0.0), hi))
+    }
+
+    pub fn cosh(&self) -> Result<Interval, ArithmeticError> {
+        let abs = self.abs();
+        let (lo, _) = outward(f64::cosh, abs.lo, 0.0);
+        let (_, hi) = outward(f64::cosh, abs.hi, 0.0);
+        Ok(Interval::new(lo.max(1.0), hi))
+    }
+
+    pub fn sin(&self) -> Result<Interval, ArithmeticError> {
+        Ok(self.periodic(f64::sin, FRAC_PI_2, -FRAC_PI_2))
+    }
+
+    pub fn cos(&self) -> Result<Interval, ArithmeticError> {
+        Ok(self.periodic(f64::cos, 0.0, PI))
+    }
+
+    /// Between two poles tan increases, an interval with a pole has no
+    /// bound.
+    pub fn tan(&self) -> Result<Interval, ArithmeticError> {
+        if !self.is_finite() || self.hi - self.lo >= PI || hits(self.lo, self.hi, FRAC_PI_2, PI) {
+            return Err(ArithmeticError::Domain(format!("tan is unbounded on {}", self)));
+        }
+        let (lo, _) = outward(f64::tan, self.lo, 0.0);
+        let (_, hi) = outward(f64::tan, self.hi, 0.0);
+        Ok(Interval::new(lo, hi))
+    }
+
+    /// Principal argument, `0` on the positive and `pi` on the negative
+    /// axis.
+    pub fn arg(&self) -> Interval {
+        let pi = Interval::new(PI, PI.next_up());
+        match (self.lo >= 0.0, self.hi < 0.0) {
+            (true, _) => Interval::new(0.0, 0.0),
+            (_, true) => pi,
+            _ => Interval::new(0.0, pi.hi),
+        }
+    }
+
+    /// Range of a function with period `2 pi` over the interval, its
+    /// maxima lie at `max_at` and minima at `min_at` modulo the period.
+    fn periodic(&self, f: fn(f64) -> f64, max_at: f64, min_at: f64) -> Interval {
+        if !self.is_finite() || self.hi - self.lo >= TAU {
+            return Interval::new(-1.0, 1.0);
+        }
+        let (a, b) = (outward(f, self.lo, 0.0), outward(f, self.hi, 0.0));
+        let mut lo = a.0.min(b.0).max(-1.0);
+        let mut hi = a.1.max(b.1).min(1.0);
+        if hits(self.lo, self.hi, max_at, TAU) {
+            hi = 1.0;
+        }
+        if hits(self.lo, self.hi, min_at, TAU) {
+            lo = -1.0;
+        }
+        Interval::new(lo, hi)
+    }
+}
+
+/// Bounds are written in the shortest form that reads back the same,
+/// `[0, 5e-324]` rather than with hundreds of zeros.
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let bound = |x: f64| {
+            let text = format!("{:?}", if x == 0.0 { 0.0 } else { x });
+            text.strip_suffix(".0").map(str::to_owned).unwrap_or(text)
+        };
+        write!(f, "[{}, {}]", bound(self.lo), bound(self.hi))
+    }
+}
+
+impl From<f64> for Interval {
+    fn from(x: f64) -> Interval {
+        Interval::new(x, x)
+    }
+}
+
+impl From<Long> for Interval {
+    fn from(value: Long) -> Interval {
+        Interval::from(Rational::new(Integer::new(value), integer!(1)))
+    }
+}
+
+/// The tightest enclosure of an exact number.
+impl From<Rational> for Interval {
+    fn from(rat: Rational) -> Interval {
+        let x = rat.value();
+        if x.is_infinite() {
+            return match x > 0.0 {
+                true => Interval::new(f64::MAX, x),
+                false => Interval::new(x, f64::MIN),
+            };
+        }
+        let (mut lo, mut hi) = (x, x);
+        while lo.is_finite() && exact(lo) > rat {
+            lo = lo.next_down();
+        }
+        while hi.is_finite() && exact(hi) < rat {
+            hi = hi.next_up();
+        }
+        Interval::new(lo, hi)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Interval {
+        Interval::new(-self.hi, -self.lo)
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, other: Interval) -> Interval {
+        Interval::new(down(add_err(self.lo, other.lo)), up(add_err(self.hi, other.hi)))
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, other: Interval) -> Interval {
+        self + -other
+    }
+}
+
+/// Bounds of `f` over the corners of two intervals.
+fn corners(a: &Interval, b: &Interval, f: fn(f64, f64) -> (f64, f64)) -> Interval {
+    let products = [f(a.lo, b.lo), f(a.lo, b.hi), f(a.hi, b.lo), f(a.hi, b.hi)];
+    let lo = products.iter().map(|product| down(*product)).fold(f64::INFINITY, f64::min);
+    let hi = products.iter().map(|product| up(*product)).fold(f64::NEG_INFINITY, f64::max);
+    Interval::new(lo, hi)
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, other: Interval) -> Interval {
+        corners(&self, &other, mul_err)
+    }
+}
+
+/// A divisor holding zero gives the whole line, `Number` refuses it first.
+impl Div for Interval {
+    type Output = Interval;
+
+    fn div(self, other: Interval) -> Interval {
+        if other.contains_zero() {
+            return Interval::new(f64::NEG_INFINITY, f64::INFINITY);
+        }
+        corners(&self, &other, div_err)
+    }
+}
+
+#[macro_export]
+macro_rules! interval {
+    ($a:expr, $b:expr) => {
+        $crate::numbers::Interval::new($a as f64, $b as f64)
+    };
+    ($a:expr) => {
+        $crate::numbers::Interval::from($a)
+    };
+}
+
+impl Number {
+    /// Interval from the lower bound of `lo` to the upper one of `hi`.
+    pub fn interval(lo: Number, hi: Number) -> Result<Number, ArithmeticError> {
+        if lo.is_nan() || hi.is_nan() {
+            return Err(ArithmeticError::Domain("bounds of an interval must not be nan".to_owned()));
+        }
+        match (lo.to_interval(), hi.to_interval()) {
+            (Some(a), Some(b)) if a.lo <= b.hi => Ok(Number::Interval(Interval::new(a.lo, b.hi))),
+            (Some(_), Some(_)) => Err(ArithmeticError::Domain(format!(
+                "lower bound {} is above upper bound {}",
+                lo, hi
+            ))),
+            _ => Err(ArithmeticError::Domain("bounds of an interval must be real".to_owned())),
+        }
+    }
+
+    pub(crate) fn is_interval(&self) -> bool {
+        matches!(self, Number::Interval(_))
+    }
+
+    /// Enclosure of a real number, `None` for NaN, complex numbers and
+    /// residues.
+    pub(crate) fn to_interval(&self) -> Option<Interval> {
+        match self.clone() {
+            Number::Natural(nat) => Some(nat.into()),
+            Number::Integer(int) => Some(int.into()),
+            Number::Decimal(dec) => Some(dec.into()),
+            Number::Rational(rat) => Some(rat.into()),
+            Number::Algebraic(alg) => Some(alg.into()),
+            Number::Real(rea) if !rea.is_nan() => Some(rea.into()),
+            Number::Interval(ivl) => Some(ivl),
+            _ => None,
+        }
+    }
+
+    /// Whether `holds` is true of the ordering of `self` and `other`,
+    /// which is uncertain for overlapping intervals.
+    pub fn relation(&self, other: &Number, holds: fn(Ordering) -> bool) -> Result<Certainty, ArithmeticError> {
+        if !self.is_interval() && !other.is_interval() {
+            return Ok(Certainty::from(holds(self.checked_cmp(other)?)));
+        }
+        match (self.to_interval(), other.to_interval()) {
+            (Some(a), Some(b)) => Ok(a.relation(&b, holds)),
+            _ => Err(mixed(self, other)),
+        }
+    }
+
+    /// Lower bound, a real number is its own bound.
+    pub fn lo(self) -> Result<Number, ArithmeticError> {
+        self.bound("lo", |ivl| Number::Real(Real::new(ivl.lo)))
+    }
+
+    pub fn hi(self) -> Result<Number, ArithmeticError> {
+        self.bound("hi", |ivl| Number::Real(Real::new(ivl.hi)))
+    }
+
+    pub fn mid(self) -> Result<Number, ArithmeticError> {
+        self.bound("mid", |ivl| Number::Real(Real::new(ivl.mid())))
+    }
+
+    pub fn width(self) -> Result<Number, ArithmeticError> {
+        match self {
+            Number::Interval(ivl) => Ok(Number::Real(Real::new(ivl.width()))),
+            number => number.bound("width", |_| Number::Natural(natural!(0))),
+        }
+    }
+
+    fn bound(self, name: &str, f: fn(&Interval) -> Number) -> Result<Number, ArithmeticError> {
+        match self {
+            Number::Interval(ivl) => Ok(f(&ivl)),
+            number if number.to_real().is_some() => Ok(number),
+            number => Err(ArithmeticError::Domain(format!(
+                "{} is not defined for {} numbers",
+                name,
+                number.kind()
+            ))),
+        }
+    }
+}
+
+fn mixed(a: &Number, b: &Number) -> ArithmeticError {
+    let other = if a.is_interval() { b } else { a };
+    ArithmeticError::Domain(format!("{} numbers do not combine with Interval numbers", other.kind()))
+}
+
+/// Checks operands of an operation with an interval that the `Number`
+/// operators can't turn into one.
+pub(crate) fn check_interval(operation: Operation, a: &Number, b: &Number) -> Result<(), ArithmeticError> {
+    let divisor = match (a.to_interval(), b.to_interval()) {
+        (Some(_), Some(divisor)) => divisor,
+        _ => return Err(mixed(a, b)),
+    };
+    match operation {
+        Operation::Division if divisor.contains_zero() && !b.is_zero() => Err(divisor_with_zero(&divisor)),
+        Operation::Remainder => Err(ArithmeticError::Domain(
+            "remainder is not defined for Interval numbers".to_owned(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Ordering of an interval and a real number, certain only when they do
+/// not overlap or both are the same point.
+pub(crate) fn interval_cmp(a: &Number, b: &Number) -> Result<Ordering, ArithmeticError> {
+    let (x, y) = match (a.to_interval(), b.to_interval()) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return Err(mixed(a, b)),
+    };
+    if x.hi < y.lo {
+        Ok(Ordering::Less)
+    } else if x.lo > y.hi {
+        Ok(Ordering::Greater)
+    } else if x.lo == x.hi && x == y {
+        Ok(Ordering::Equal)
+    } else {
+        Err(ArithmeticError::Domain(format!("comparison of {} and {} is uncertain", a, b)))
+    }
+}
+
+/// Power with an interval base or exponent.
+pub(crate) fn interval_pow(base: Number, exp: Number) -> Result<Number, ArithmeticError> {
+    let x = base.to_interval().ok_or_else(|| mixed(&base, &exp))?;
+    let result = match exp {
+        Number::Natural(ref nat) => x.powi(&nat.value())?,
+        Number::Integer(ref int) => x.powi(&int.value())?,
+        ref exp => x.powf(&exp.to_interval().ok_or_else(|| mixed(&base, exp))?)?,
+    };
+    Ok(Number::Interval(result))
+}
@@ -0,0 +1,582 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use utils::ldexp;
+
+use std::cmp::{max, Ordering};
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Rem, Shl, Shr, Sub};
+use std::str::FromStr;
+
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+/// Signed integer of arbitrary length.
+///
+/// The magnitude is stored as little-endian base 2^32 digits without
+/// leading zeros, so zero is an empty vector and is never negative.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct Long {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseLongError {
+    Empty,
+    InvalidDigit,
+}
+
+impl Display for ParseLongError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseLongError::Empty => "cannot parse integer from empty string".fmt(f),
+            ParseLongError::InvalidDigit => "invalid digit found in string".fmt(f),
+        }
+    }
+}
+
+impl Long {
+    fn from_parts(negative: bool, mut digits: Vec<u32>) -> Long {
+        trim(&mut digits);
+        Long {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn zero() -> Long {
+        Long::from_parts(false, Vec::new())
+    }
+
+    pub fn one() -> Long {
+        Long::from_parts(false, vec![1])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Long {
+        Long::from_parts(false, self.digits.clone())
+    }
+
+    /// Number of significant bits of the magnitude.
+    pub fn bits(&self) -> usize {
+        match self.digits.last() {
+            Some(top) => self.digits.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.digits.first().is_some_and(|d| d & 1 == 1)
+    }
+
+    pub fn pow(&self, exp: u32) -> Long {
+        let mut result = Long::one();
+        let mut base = self.clone();
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Integer square root of the magnitude, rounded down.
+    pub fn isqrt(&self) -> Long {
+        if self.is_zero() {
+            return Long::zero();
+        }
+        // Newton's iteration from a power of two that is surely above the root
+        let mut x = Long::one() << self.bits().div_ceil(2);
+        let n = self.abs();
+        loop {
+            let y = (&x + &(&n / &x)) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Quotient and remainder of the division truncated toward zero,
+    /// the same way as for primitive integers.
+    pub fn div_rem(&self, other: &Long) -> (Long, Long) {
+        if other.is_zero() {
+            panic!("attempt to divide by zero")
+        }
+        let (q, r) = divmod_mag(&self.digits, &other.digits);
+        (
+            Long::from_parts(self.negative != other.negative, q),
+            Long::from_parts(self.negative, r),
+        )
+    }
+
+    /// Nearest `f64`, rounded to nearest even; infinite when out of range.
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.bits();
+        let value = if bits <= 64 {
+            self.low_u64() as f64
+        } else {
+            let shift = bits - 64;
+            let mut top = Long::from_parts(false, shr_mag(&self.digits, shift)).low_u64();
+            if self.digits.iter().take(shift / 32).any(|d| *d != 0)
+                || self.digits[shift / 32] & ((1 << (shift % 32)) - 1) != 0
+            {
+                // sticky bit keeps rounding of the truncated tail correct
+                top |= 1;
+            }
+            ldexp(top as f64, shift as i64)
+        };
+
+        if self.negative {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Lowest 64 bits of the magnitude.
+    pub fn low_u64(&self) -> u64 {
+        self.digits
+            .iter()
+            .take(2)
+            .rev()
+            .fold(0, |acc, d| (acc << 32) | u64::from(*d))
+    }
+
+    /// Parses unsigned digits in base `radix`, up to 36.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Long, ParseLongError> {
+        if s.is_empty() {
+            return Err(ParseLongError::Empty);
+        }
+        let mut digits = Vec::new();
+        for c in s.chars() {
+            let digit = c.to_digit(radix).ok_or(ParseLongError::InvalidDigit)?;
+            digits = mul_mag(&digits, &[radix]);
+            digits = add_mag(&digits, &[digit]);
+        }
+        Ok(Long::from_parts(false, digits))
+    }
+}
+
+fn trim(digits: &mut Vec<u32>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, d) in long.iter().enumerate() {
+        let sum = u64::from(*d) + u64::from(*short.get(i).unwrap_or(&0)) + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// `a - b`, requires `a >= b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, d) in a.iter().enumerate() {
+        let diff = i64::from(*d) - i64::from(*b.get(i).unwrap_or(&0)) - borrow;
+        if diff < 0 {
+            result.push((diff + (1 << 32)) as u32);
+            borrow = 1;
+        } else {
+            result.push(diff as u32);
+            borrow = 0;
+        }
+    }
+    trim(&mut result);
+    result
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let cur = u64::from(*x) * u64::from(*y) + u64::from(result[i + j]) + carry;
+            result[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    trim(&mut result);
+    result
+}
+
+fn divmod_small(a: &[u32], b: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut rem = 0u64;
+    for i in (0..a.len()).rev() {
+        let cur = (rem << 32) | u64::from(a[i]);
+        quotient[i] = (cur / u64::from(b)) as u32;
+        rem = cur % u64::from(b);
+    }
+    trim(&mut quotient);
+    (quotient, rem as u32)
+}
+
+fn shl_mag(a: &[u32], shift: usize) -> Vec<u32> {
+    let (limbs, bits) = (shift / 32, (shift % 32) as u32);
+    let mut result = vec![0u32; limbs];
+    if bits == 0 {
+        result.extend_from_slice(a);
+    } else {
+        let mut carry = 0u32;
+        for d in a {
+            result.push((d << bits) | carry);
+            carry = d >> (32 - bits);
+        }
+        result.push(carry);
+    }
+    trim(&mut result);
+    result
+}
+
+fn shr_mag(a: &[u32], shift: usize) -> Vec<u32> {
+    let (limbs, bits) = (shift / 32, (shift % 32) as u32);
+    if limbs >= a.len() {
+        return Vec::new();
+    }
+    let a = &a[limbs..];
+    let mut result = Vec::with_capacity(a.len());
+    for i in 0..a.len() {
+        let high = if bits == 0 {
+            0
+        } else {
+            a.get(i + 1).map_or(0, |d| d << (32 - bits))
+        };
+        result.push((a[i] >> bits) | high);
+    }
+    trim(&mut result);
+    result
+}
+
+/// Schoolbook long division (Knuth, TAOCP vol. 2, algorithm D).
+fn divmod_mag(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(u, v) == Ordering::Less {
+        return (Vec::new(), u.to_vec());
+    }
+    if v.len() == 1 {
+        let (q, r) = divmod_small(u, v[0]);
+        return (q, if r == 0 { Vec::new() } else { vec![r] });
+    }
+
+    let shift = v[v.len() - 1].leading_zeros() as usize;
+    let vn = shl_mag(v, shift);
+    let mut un = shl_mag(u, shift);
+    un.resize(u.len() + 1, 0);
+
+    let (n, m) = (v.len(), u.len());
+    let base = 1u64 << 32;
+    let mut q = vec![0u32; m - n + 1];
+
+    for j in (0..=m - n).rev() {
+        let num = (u64::from(un[j + n]) << 32) | u64::from(un[j + n - 1]);
+        let mut qhat = num / u64::from(vn[n - 1]);
+        let mut rhat = num % u64::from(vn[n - 1]);
+        while qhat >= base
+            || qhat * u64::from(vn[n - 2]) > ((rhat << 32) | u64::from(un[j + n - 2]))
+        {
+            qhat -= 1;
+            rhat += u64::from(vn[n - 1]);
+            if rhat >= base {
+                break;
+            }
+        }
+
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * u64::from(vn[i]) + carry;
+            carry = p >> 32;
+            let t = i64::from(un[i + j]) - borrow - (p & 0xFFFF_FFFF) as i64;
+            un[i + j] = t as u32;
+            borrow = if t < 0 { 1 } else { 0 };
+        }
+        let t = i64::from(un[j + n]) - borrow - carry as i64;
+        un[j + n] = t as u32;
+
+        if t < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u64::from(un[i + j]) + u64::from(vn[i]) + carry;
+                un[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+        q[j] = qhat as u32;
+    }
+
+    trim(&mut q);
+    un.truncate(n);
+    (q, shr_mag(&un, shift))
+}
+
+impl Ord for Long {
+    fn cmp(&self, other: &Long) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.digits, &other.digits),
+            (true, true) => cmp_mag(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for Long {
+    fn partial_cmp(&self, other: &Long) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Long {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut chunks = Vec::new();
+        let mut rest = self.digits.clone();
+        while !rest.is_empty() {
+            let (q, r) = divmod_small(&rest, DECIMAL_BASE);
+            chunks.push(r);
+            rest = q;
+        }
+
+        let mut text = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            text.push_str(&format!("{:01$}", chunk, DECIMAL_DIGITS));
+        }
+        f.pad_integral(!self.negative, "", &text)
+    }
+}
+
+/// `width` bits of the magnitude starting at bit `pos`.
+fn bits_at(digits: &[u32], pos: usize, width: usize) -> u32 {
+    let (limb, shift) = (pos / 32, pos % 32);
+    let low = u64::from(digits.get(limb).cloned().unwrap_or(0));
+    let high = u64::from(digits.get(limb + 1).cloned().unwrap_or(0));
+    (((high << 32 | low) >> shift) & ((1 << width) - 1)) as u32
+}
+
+macro_rules! impl_long_radix {
+    ($($trait_:ident, $prefix:expr, $width:expr;)*) => {
+        $(
+            impl fmt::$trait_ for Long {
+                fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                    let count = max(1, self.bits().div_ceil($width));
+                    let text: String = (0..count)
+                        .rev()
+                        .map(|i| std::char::from_digit(bits_at(&self.digits, i * $width, $width), 1 << $width).unwrap())
+                        .collect();
+                    f.pad_integral(!self.negative, $prefix, &text)
+                }
+            }
+        )*
+    };
+}
+
+impl_long_radix! {
+    LowerHex, "0x", 4;
+    Octal, "0o", 3;
+    Binary, "0b", 1;
+}
+
+impl FromStr for Long {
+    type Err = ParseLongError;
+
+    fn from_str(s: &str) -> Result<Long, ParseLongError> {
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if s.is_empty() {
+            return Err(ParseLongError::Empty);
+        }
+        if !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseLongError::InvalidDigit);
+        }
+
+        let mut digits = Vec::new();
+        let head = s.len() % DECIMAL_DIGITS;
+        let mut start = 0;
+        let mut end = if head == 0 { DECIMAL_DIGITS } else { head };
+        while start < s.len() {
+            let chunk = u32::from_str(&s[start..end]).unwrap();
+            let width = (end - start) as u32;
+            digits = mul_mag(&digits, &[10u32.pow(width)]);
+            digits = add_mag(&digits, &[chunk]);
+            start = end;
+            end += DECIMAL_DIGITS;
+        }
+        Ok(Long::from_parts(negative, digits))
+    }
+}
+
+macro_rules! impl_long_from {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for Long {
+                #[allow(unused_comparisons)]
+                fn from(value: $t) -> Long {
+                    let negative = value < 0;
+                    let mut magnitude = if negative {
+                        (value as i128).unsigned_abs()
+                    } else {
+                        value as u128
+                    };
+                    let mut digits = Vec::new();
+                    while magnitude > 0 {
+                        digits.push(magnitude as u32);
+                        magnitude >>= 32;
+                    }
+                    Long::from_parts(negative, digits)
+                }
+            }
+        )*
+    };
+}
+
+impl_long_from! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+impl Neg for Long {
+    type Output = Long;
+
+    fn neg(self) -> Long {
+        Long::from_parts(!self.negative, self.digits)
+    }
+}
+
+impl Neg for &Long {
+    type Output = Long;
+
+    fn neg(self) -> Long {
+        Long::from_parts(!self.negative, self.digits.clone())
+    }
+}
+
+impl<'a> Add for &'a Long {
+    type Output = Long;
+
+    fn add(self, other: &'a Long) -> Long {
+        if self.negative == other.negative {
+            return Long::from_parts(self.negative, add_mag(&self.digits, &other.digits));
+        }
+        match cmp_mag(&self.digits, &other.digits) {
+            Ordering::Less => Long::from_parts(other.negative, sub_mag(&other.digits, &self.digits)),
+            _ => Long::from_parts(self.negative, sub_mag(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl<'a> Sub for &'a Long {
+    type Output = Long;
+
+    fn sub(self, other: &'a Long) -> Long {
+        self + &(-other)
+    }
+}
+
+impl<'a> Mul for &'a Long {
+    type Output = Long;
+
+    fn mul(self, other: &'a Long) -> Long {
+        Long::from_parts(
+            self.negative != other.negative,
+            mul_mag(&self.digits, &other.digits),
+        )
+    }
+}
+
+impl<'a> Div for &'a Long {
+    type Output = Long;
+
+    fn div(self, other: &'a Long) -> Long {
+        self.div_rem(other).0
+    }
+}
+
+impl<'a> Rem for &'a Long {
+    type Output = Long;
+
+    fn rem(self, other: &'a Long) -> Long {
+        self.div_rem(other).1
+    }
+}
+
+macro_rules! impl_long_ops_by_value {
+    ($($trait_:ident($method:ident)),*) => {
+        $(
+            impl $trait_ for Long {
+                type Output = Long;
+
+                fn $method(self, other: Long) -> Long {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+impl Shl<usize> for Long {
+    type Output = Long;
+
+    fn shl(self, bits: usize) -> Long {
+        Long::from_parts(self.negative, shl_mag(&self.digits, bits))
+    }
+}
+
+/// Shifts the magnitude, so for negative values `>>` rounds toward zero
+/// like the division by a power of two.
+impl Shr<usize> for Long {
+    type Output = Long;
+
+    fn shr(self, bits: usize) -> Long {
+        Long::from_parts(self.negative, shr_mag(&self.digits, bits))
+    }
+}
+
+impl_long_ops_by_value! { Add(add), Sub(sub), Mul(mul), Div(div), Rem(rem) }
+
+impl_default! { Long, Long::zero() }
@@ -27,7 +27,7 @@ macro_rules! impl_std_ops_for_tuple_struct {
             type Output = $typename;
 
             fn $method(self, other: $typename) -> $typename {
-                $typename::new(self.value() $op other.value())
+                $typename::new(self.0 $op other.0)
             }
         }
     }
@@ -14,6 +14,8 @@
 
 #[macro_use]
 mod macros;
+mod long;
+mod float;
 #[macro_use]
 mod complex;
 #[macro_use]
@@ -24,7 +26,21 @@ mod rational;
 mod real;
 #[macro_use]
 mod natural;
+#[macro_use]
+mod decimal;
+mod algebraic;
+mod gaussian;
+#[macro_use]
+mod interval;
+#[macro_use]
+mod uncertain;
+mod dual;
+mod error;
 mod number;
+mod functions;
+mod format;
+mod theory;
+mod modular;
 
 mod traits;
 
@@ -33,10 +49,21 @@ mod cross_types;
 #[cfg(test)]
 mod tests;
 
+pub use self::algebraic::Algebraic;
 pub use self::complex::Complex;
+pub use self::dual::Dual;
+pub use self::decimal::{Decimal, Rounding, DEFAULT_SCALE, MAX_SCALE};
+pub use self::error::{ArithmeticError, Operation};
 pub use self::integer::Integer;
+pub use self::interval::{Certainty, Interval};
+pub use self::float::Float;
+pub use self::gaussian::Gaussian;
+pub use self::format::Notation;
+pub use self::long::Long;
+pub use self::modular::Modular;
 pub use self::natural::Natural;
 pub use self::number::Number;
 pub use self::rational::Rational;
-pub use self::real::Real;
+pub use self::real::{Real, Tolerance, MAX_PRECISION};
 pub use self::traits::{AsNum, Sqrt};
+pub use self::uncertain::Uncertain;
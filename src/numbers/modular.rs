@@ -0,0 +1,194 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Long, Natural, Number, Operation};
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::Neg;
+
+/// Residue `value mod modulus`, an element of the ring Z/nZ.
+///
+/// It is not part of the tower: it only combines with other residues of
+/// the same modulus and with exact numbers, which are taken into the ring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modular {
+    value: Natural,
+    modulus: Natural,
+}
+
+impl Modular {
+    /// `value mod modulus`, the modulus must be positive.
+    pub fn new<T: Into<Long>>(value: T, modulus: Natural) -> Modular {
+        let modulus_value = modulus.value();
+        if modulus_value.is_zero() {
+            panic!("zero modulus in Modular")
+        }
+        let rem = &value.into() % &modulus_value;
+        let value = if rem.is_negative() { rem + modulus_value } else { rem };
+        Modular {
+            value: Natural::new(value),
+            modulus,
+        }
+    }
+
+    /// Representative in `[0, modulus)`.
+    pub fn value(&self) -> Natural {
+        self.value.clone()
+    }
+
+    pub fn modulus(&self) -> Natural {
+        self.modulus.clone()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.value().is_zero()
+    }
+
+    /// Multiplicative inverse, `None` when the value and the modulus have
+    /// a common factor.
+    pub fn inv(&self) -> Option<Modular> {
+        self.value.modinv(&self.modulus).map(|value| Modular {
+            value,
+            modulus: self.modulus.clone(),
+        })
+    }
+
+    /// `self^exp`, a negative exponent raises the inverse.
+    pub fn checked_pow(&self, exp: &Long) -> Result<Modular, ArithmeticError> {
+        let base = if exp.is_negative() { self.checked_inv()? } else { self.clone() };
+        let value = base.value.modpow(&Natural::new(exp.abs()), &self.modulus);
+        Ok(Modular {
+            value,
+            modulus: self.modulus.clone(),
+        })
+    }
+
+    fn checked_inv(&self) -> Result<Modular, ArithmeticError> {
+        if self.is_zero() {
+            return Err(ArithmeticError::DivisionByZero);
+        }
+        self.inv().ok_or_else(|| {
+            ArithmeticError::Domain(format!("{} has no inverse modulo {}", self.value, self.modulus))
+        })
+    }
+
+    /// Ring operation with a residue of the same modulus.
+    pub fn checked_op(&self, operation: Operation, other: &Modular) -> Result<Modular, ArithmeticError> {
+        if self.modulus != other.modulus {
+            return Err(ArithmeticError::Domain(format!(
+                "moduli {} and {} do not match",
+                self.modulus, other.modulus
+            )));
+        }
+        let (a, b) = (self.value.value(), other.value.value());
+        let value = match operation {
+            Operation::Addition => a + b,
+            Operation::Subtraction => a - b,
+            Operation::Multiplication => a * b,
+            Operation::Division => a * other.checked_inv()?.value.value(),
+            _ => return Err(not_in_ring(operation)),
+        };
+        Ok(Modular::new(value, self.modulus.clone()))
+    }
+}
+
+impl Display for Modular {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value(), self.modulus())
+    }
+}
+
+impl Neg for Modular {
+    type Output = Modular;
+
+    fn neg(self) -> Modular {
+        Modular::new(-self.value.value(), self.modulus)
+    }
+}
+
+fn not_in_ring(operation: Operation) -> ArithmeticError {
+    ArithmeticError::Domain(format!("{} is not defined for Modular numbers", operation))
+}
+
+/// Takes an exact number into the ring of `modulus`, a rational through
+/// the inverse of its denominator.
+fn lift(number: &Number, modulus: &Natural) -> Result<Modular, ArithmeticError> {
+    match number {
+        Number::Modular(m) => Ok(m.clone()),
+        Number::Natural(nat) => Ok(Modular::new(nat.value(), modulus.clone())),
+        Number::Integer(int) => Ok(Modular::new(int.value(), modulus.clone())),
+        Number::Decimal(dec) => lift(&Number::Rational(dec.to_rational()), modulus),
+        Number::Rational(rat) => {
+            let numer = Modular::new(rat.numer.value(), modulus.clone());
+            let denom = Modular::new(rat.denom.value(), modulus.clone());
+            numer.checked_op(Operation::Division, &denom)
+        }
+        _ => Err(ArithmeticError::Domain(format!(
+            "{} numbers do not combine with Modular numbers",
+            number.kind()
+        ))),
+    }
+}
+
+impl Number {
+    /// `value mod modulus` as an element of Z/nZ.
+    pub fn modular(value: Number, modulus: Number) -> Result<Number, ArithmeticError> {
+        let modulus = match modulus {
+            Number::Natural(ref modulus) if !modulus.value().is_zero() => modulus.clone(),
+            _ => return Err(ArithmeticError::Domain("modulus must be positive".to_owned())),
+        };
+        match value {
+            Number::Modular(_) => Err(ArithmeticError::Domain(
+                "value of a Modular number must be exact".to_owned(),
+            )),
+            value => lift(&value, &modulus).map(Number::Modular),
+        }
+    }
+
+    pub(crate) fn is_modular(&self) -> bool {
+        matches!(self, Number::Modular(_))
+    }
+}
+
+/// Operation where at least one operand is `Modular`, the result stays in
+/// its ring.
+pub(crate) fn checked_modular(operation: Operation, a: Number, b: Number) -> Result<Number, ArithmeticError> {
+    let result = match (operation, &a, &b) {
+        (Operation::Power, Number::Modular(base), exp) => match exp {
+            Number::Natural(nat) => base.checked_pow(&nat.value()),
+            Number::Integer(int) => base.checked_pow(&int.value()),
+            _ => Err(ArithmeticError::Domain(
+                "exponent of a Modular number must be an integer".to_owned(),
+            )),
+        },
+        (Operation::Power, _, _) | (Operation::Remainder, _, _) => Err(not_in_ring(operation)),
+        (_, Number::Modular(m), _) | (_, _, Number::Modular(m)) => {
+            let (x, y) = (lift(&a, &m.modulus)?, lift(&b, &m.modulus)?);
+            x.checked_op(operation, &y)
+        }
+        _ => unreachable!(),
+    };
+    result.map(Number::Modular)
+}
+
+/// Residues are only equal or not, they have no order.
+pub(crate) fn modular_equal(a: &Number, b: &Number) -> Result<bool, ArithmeticError> {
+    match (a, b) {
+        (Number::Modular(m), _) | (_, Number::Modular(m)) => {
+            let (x, y) = (lift(a, &m.modulus)?, lift(b, &m.modulus)?);
+            Ok(x == y)
+        }
+        _ => unreachable!(),
+    }
+}
@@ -12,29 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use numbers::Integer;
+use numbers::{Integer, Long};
 
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
-pub struct Natural(i128); // TODO: change to long math
+#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone)]
+pub struct Natural(Long);
 
 impl Natural {
-    pub fn new<T: Into<i128>>(nat: T) -> Natural {
+    pub fn new<T: Into<Long>>(nat: T) -> Natural {
         let nat = nat.into();
-        if nat < 0 {
-            panic!{}
+        if nat.is_negative() {
+            panic!("negative Natural {}", nat)
         }
         Natural(nat)
     }
 
     pub fn zero() -> Natural {
-        Natural(0)
+        Natural(Long::zero())
     }
 
-    pub fn value(&self) -> i128 {
-        self.0
+    pub fn value(&self) -> Long {
+        self.0.clone()
     }
 }
 
@@ -50,7 +50,7 @@ pub trait AsNat {
 
 impl AsNat for Natural {
     fn as_nat(&self) -> Natural {
-        *self
+        self.clone()
     }
 }
 
@@ -12,54 +12,815 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Complex, Integer, Natural, Rational, Real};
+use super::algebraic::{algebraic_pow, MAX_DEGREE};
+use super::decimal::decimal_pow;
+use super::dual::{dual_cmp, dual_operation};
+use super::interval::{check_interval, interval_cmp, interval_pow};
+use super::modular::{checked_modular, modular_equal};
+use super::uncertain::{check_uncertain, uncertain_cmp, uncertain_pow};
+use super::{
+    Algebraic, ArithmeticError, AsNum, Complex, Decimal, Dual, Gaussian, Integer, Interval, Long, Modular, Natural, Notation, Operation,
+    Rational, Real, Uncertain,
+};
 
-use std::cmp::{Ordering, PartialEq, PartialOrd};
+use std::cmp::{max, Ordering, PartialEq, PartialOrd};
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Sub, Rem};
 use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum Number {
     Natural(Natural),
     Integer(Integer),
+    Decimal(Decimal),
     Rational(Rational),
+    Algebraic(Algebraic),
     Real(Real),
+    Interval(Interval),
+    Uncertain(Uncertain),
+    Dual(Dual),
     Complex(Complex),
+    Gaussian(Gaussian),
+    Modular(Modular),
 }
 
 impl Number {
-    pub fn natural(s: String) -> Result<Number, <i128 as FromStr>::Err> {
-        let nat = <i128>::from_str(s.as_str())?;
+    pub fn natural(s: String) -> Result<Number, <Long as FromStr>::Err> {
+        let nat = <Long>::from_str(s.as_str())?;
         Ok(Number::Natural(natural!(nat)))
     }
 
-    pub fn rational(n: String, d: String) -> Result<Number, <i128 as FromStr>::Err> {
-        let num = <i128>::from_str(n.as_str())?;
-        let den = <i128>::from_str(d.as_str())?;
+    pub fn rational(n: String, d: String) -> Result<Number, <Long as FromStr>::Err> {
+        let num = <Long>::from_str(n.as_str())?;
+        let den = <Long>::from_str(d.as_str())?;
         Ok(Number::Rational(rational!(num / den)))
     }
 
-    pub fn complex(s: String) -> Result<Number, <f64 as FromStr>::Err> {
-        let com = <f64>::from_str(s.as_str())?;
-        Ok(Number::Complex(complex!(com.i)))
+    pub fn complex(s: String) -> Result<Number, <Real as FromStr>::Err> {
+        let com = <Real>::from_str(s.as_str())?;
+        Ok(Number::Complex(Complex::new(0, com)))
     }
 
-    pub fn real(s: String) -> Result<Number, <f64 as FromStr>::Err> {
-        let real = <f64>::from_str(s.as_str())?;
-        Ok(Number::Real(real!(real)))
+    /// Exact imaginary number, like `2i`.
+    pub fn imaginary(s: String) -> Result<Number, <Long as FromStr>::Err> {
+        let imag = <Long>::from_str(s.as_str())?;
+        Ok(Number::Gaussian(Gaussian::new(rational!(0), rational!(imag))))
+    }
+
+    /// Exact value of a decimal literal like `0.1` or `1e-9`.
+    pub fn decimal(s: String) -> Option<Number> {
+        Rational::from_decimal(s.as_str()).map(Number::Rational)
+    }
+
+    pub fn real(s: String) -> Result<Number, <Real as FromStr>::Err> {
+        let real = <Real>::from_str(s.as_str())?;
+        Ok(Number::Real(real))
+    }
+
+    /// The simplest type holding the same value: integral rationals become
+    /// integers, non-negative integers naturals and complex numbers without
+    /// an imaginary part reals or, when exact, rationals, as are rational
+    /// algebraic numbers. Decimals keep their digits after the point.
+    /// Complex numbers with an infinite part are complex infinity, and
+    /// ones with a NaN part are NaN.
+    pub fn normalize(self) -> Number {
+        match self {
+            Number::Integer(int) => match int.try_as_num() {
+                Some(nat) => Number::Natural(nat),
+                None => Number::Integer(int),
+            },
+            Number::Rational(rat) => match AsNum::<Integer>::try_as_num(&rat) {
+                Some(int) => Number::Integer(int).normalize(),
+                None => Number::Rational(rat),
+            },
+            Number::Complex(com) => match com.try_as_num() {
+                Some(rea) => Number::Real(rea),
+                None if com.is_infinite() => Number::Complex(Complex::infinity()),
+                None if com.real.is_nan() || com.imag.is_nan() => Number::Real(Real::new(f64::NAN)),
+                None => Number::Complex(com),
+            },
+            Number::Gaussian(gau) if gau.imag == Rational::default() => Number::Rational(gau.real).normalize(),
+            Number::Algebraic(alg) => match alg.to_rational() {
+                Some(rat) => Number::Rational(rat).normalize(),
+                None => Number::Algebraic(alg),
+            },
+            number => number,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Number::Natural(_) => "Natural",
+            Number::Integer(_) => "Integer",
+            Number::Decimal(_) => "Decimal",
+            Number::Rational(_) => "Rational",
+            Number::Algebraic(_) => "Algebraic",
+            Number::Real(_) => "Real",
+            Number::Interval(_) => "Interval",
+            Number::Uncertain(_) => "Uncertain",
+            Number::Dual(_) => "Dual",
+            Number::Complex(_) | Number::Gaussian(_) => "Complex",
+            Number::Modular(_) => "Modular",
+        }
+    }
+
+    /// Position in the tower, `Natural` is the narrowest.
+    fn rank(&self) -> u8 {
+        match self {
+            Number::Natural(_) => 0,
+            Number::Integer(_) => 1,
+            Number::Decimal(_) => 2,
+            Number::Rational(_) => 3,
+            Number::Algebraic(_) => 4,
+            Number::Real(_) => 5,
+            // intervals enclose reals, they do not combine with complex
+            // numbers
+            Number::Interval(_) => 6,
+            // measurements are reals with an error, just as far from
+            // complex numbers
+            Number::Uncertain(_) => 7,
+            // exact complex numbers do not hold reals, but combine with
+            // them into `Complex`
+            Number::Gaussian(_) => 8,
+            Number::Complex(_) => 9,
+            // residues and dual numbers are outside of the tower, they
+            // never get here
+            Number::Modular(_) | Number::Dual(_) => 10,
+        }
+    }
+
+    /// Exactly zero, without the tolerance of `==`.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Natural(nat) => nat.value().is_zero(),
+            Number::Integer(int) => int.value().is_zero(),
+            Number::Decimal(dec) => dec.is_zero(),
+            Number::Rational(rat) => rat.numer.value().is_zero(),
+            Number::Algebraic(alg) => alg.is_zero(),
+            Number::Real(rea) => rea.is_zero(),
+            Number::Interval(ivl) => ivl.lo() == 0.0 && ivl.hi() == 0.0,
+            Number::Uncertain(unc) => unc.value() == 0.0 && unc.is_exact(),
+            Number::Dual(dual) => dual.value() == 0.0 && dual.is_constant(),
+            Number::Complex(com) => com.real.is_zero() && com.imag.is_zero(),
+            Number::Gaussian(gau) => gau.is_zero(),
+            Number::Modular(m) => m.is_zero(),
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Number::Real(rea) => rea.is_finite(),
+            Number::Interval(ivl) => ivl.is_finite(),
+            Number::Uncertain(unc) => unc.is_finite(),
+            Number::Dual(dual) => dual.is_finite(),
+            Number::Complex(com) => com.real.is_finite() && com.imag.is_finite(),
+            _ => true,
+        }
+    }
+
+    /// Not a number, complex infinity is not NaN even with a NaN part.
+    pub fn is_nan(&self) -> bool {
+        match self {
+            Number::Real(rea) => rea.is_nan(),
+            Number::Interval(ivl) => ivl.lo().is_nan() || ivl.hi().is_nan(),
+            Number::Uncertain(unc) => unc.value().is_nan() || unc.error().is_nan(),
+            Number::Dual(dual) => dual.value().is_nan(),
+            Number::Complex(com) => !com.is_infinite() && (com.real.is_nan() || com.imag.is_nan()),
+            _ => false,
+        }
+    }
+
+    /// An error for infinities and NaN when the session is strict about
+    /// them.
+    pub fn check_finite(&self) -> Result<(), ArithmeticError> {
+        if Real::strict() && !self.is_finite() {
+            return Err(ArithmeticError::Domain(format!("{} is not finite", self)));
+        }
+        Ok(())
+    }
+
+    fn is_complex_infinity(&self) -> bool {
+        matches!(self, Number::Complex(com) if com.is_infinite())
+    }
+
+    /// Size of an exact number, `None` for reals and complex numbers.
+    fn bits(&self) -> Option<usize> {
+        match self {
+            Number::Natural(nat) => Some(nat.value().bits()),
+            Number::Integer(int) => Some(int.value().bits()),
+            Number::Decimal(dec) => Some(dec.unscaled().bits() + dec.places() as usize * 4),
+            Number::Rational(rat) => Some(rat.numer.value().bits() + rat.denom.value().bits()),
+            Number::Algebraic(alg) => Some(alg.bits()),
+            Number::Gaussian(gau) => {
+                let (real, imag) = (Number::Rational(gau.real.clone()), Number::Rational(gau.imag.clone()));
+                Some(real.bits()? + imag.bits()?)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_rational(&self) -> Option<Rational> {
+        match self.clone() {
+            Number::Natural(nat) => Some(nat.into()),
+            Number::Integer(int) => Some(int.into()),
+            Number::Decimal(dec) => Some(dec.to_rational()),
+            Number::Rational(rat) => Some(rat),
+            Number::Algebraic(alg) => alg.to_rational(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_real(&self) -> Option<Real> {
+        match self.clone() {
+            Number::Natural(nat) => Some(nat.into()),
+            Number::Integer(int) => Some(int.into()),
+            Number::Decimal(dec) => Some(dec.into()),
+            Number::Rational(rat) => Some(rat.into()),
+            Number::Algebraic(alg) => Some(alg.into()),
+            Number::Real(rea) => Some(rea),
+            Number::Interval(_) | Number::Uncertain(_) | Number::Dual(_) => None,
+            Number::Complex(_) | Number::Gaussian(_) | Number::Modular(_) => None,
+        }
+    }
+
+    /// Exact complex value, `None` for reals and complex numbers.
+    pub(crate) fn to_gaussian(&self) -> Option<Gaussian> {
+        match self {
+            Number::Gaussian(gau) => Some(gau.clone()),
+            number => number.to_rational().map(Gaussian::from),
+        }
+    }
+
+    pub(crate) fn to_complex(&self) -> Complex {
+        match self.clone() {
+            Number::Complex(com) => com,
+            Number::Gaussian(gau) => gau.into(),
+            number => Complex::new(number.to_real().expect("only real numbers are complex"), 0),
+        }
+    }
+
+    /// The value as a type `T` of the tower, for `nat(x)` and the like.
+    /// Decimals convert as rationals and exact complex numbers as complex
+    /// ones, intervals, measurements and residues not at all.
+    fn convert<T>(&self, kind: &'static str) -> Result<T, ArithmeticError>
+    where
+        Natural: AsNum<T>,
+        Integer: AsNum<T>,
+        Rational: AsNum<T>,
+        Algebraic: AsNum<T>,
+        Real: AsNum<T>,
+        Complex: AsNum<T>,
+    {
+        let converted = match self {
+            Number::Natural(nat) => nat.try_as_num(),
+            Number::Integer(int) => int.try_as_num(),
+            Number::Decimal(dec) => dec.to_rational().try_as_num(),
+            Number::Rational(rat) => rat.try_as_num(),
+            Number::Algebraic(alg) => alg.try_as_num(),
+            Number::Real(rea) => rea.try_as_num(),
+            Number::Complex(com) => com.try_as_num(),
+            Number::Gaussian(gau) => Complex::from(gau.clone()).try_as_num(),
+            _ => None,
+        };
+        converted.ok_or_else(|| ArithmeticError::Conversion {
+            value: self.to_string(),
+            kind,
+        })
+    }
+
+    pub fn into_natural(self) -> Result<Number, ArithmeticError> {
+        self.convert("Natural").map(Number::Natural)
+    }
+
+    pub fn into_integer(self) -> Result<Number, ArithmeticError> {
+        self.convert("Integer").map(Number::Integer)
+    }
+
+    /// Exact value, a real is taken as the decimal it is shown as.
+    pub fn into_rational(self) -> Result<Number, ArithmeticError> {
+        self.convert("Rational").map(Number::Rational)
+    }
+
+    pub fn into_real(self) -> Result<Number, ArithmeticError> {
+        self.convert("Real").map(Number::Real)
+    }
+
+    pub fn into_complex(self) -> Result<Number, ArithmeticError> {
+        self.convert("Complex").map(Number::Complex)
+    }
+
+    /// Exponent as a whole number, `None` when it has a fractional or
+    /// imaginary part.
+    fn integral(&self) -> Option<Long> {
+        match self {
+            Number::Natural(nat) => Some(nat.value()),
+            Number::Integer(int) => Some(int.value()),
+            Number::Decimal(dec) if dec.to_rational().denom == integer!(1) => Some(dec.to_rational().numer.value()),
+            Number::Rational(rat) if rat.denom == integer!(1) => Some(rat.numer.value()),
+            Number::Real(rea) if rea.is_finite() && rea.trunc() == *rea && rea.value().abs() < 1e9 => {
+                Some(Long::from(rea.value() as i64))
+            }
+            _ => None,
+        }
+    }
+
+    /// `self` raised to the power `other`.
+    ///
+    /// Exact bases with whole exponents give exact results, and so do
+    /// positive exact bases with fractional exponents up to the degree
+    /// of algebraic numbers. Other real powers are computed in floating
+    /// point and negative bases with a fractional exponent give the
+    /// principal complex value.
+    pub fn checked_pow(self, other: Number) -> Result<Number, ArithmeticError> {
+        let operation = Operation::Power;
+        if self.is_modular() || other.is_modular() {
+            return checked_modular(operation, self, other);
+        }
+        if self.is_interval() || other.is_interval() {
+            let finite = self.is_finite() && other.is_finite();
+            return check_result(operation, finite, false, interval_pow(self, other)?);
+        }
+        if self.is_uncertain() || other.is_uncertain() {
+            let finite = self.is_finite() && other.is_finite();
+            return check_result(operation, finite, false, uncertain_pow(self, other)?);
+        }
+        if self.is_dual() || other.is_dual() {
+            let finite = self.is_finite() && other.is_finite();
+            return check_result(operation, finite, false, dual_operation(operation, self, other)?);
+        }
+        if self.is_complex_infinity() || other.is_complex_infinity() {
+            return Ok(infinite_pow(&self, &other));
+        }
+        let exp = other.integral();
+
+        if self.is_zero() {
+            return match &exp {
+                Some(exp) if exp.is_zero() => Ok(Number::Natural(natural!(1))),
+                _ if matches!(other, Number::Gaussian(_) | Number::Complex(_)) => Err(ArithmeticError::Domain(
+                    "zero raised to a complex power".to_owned(),
+                )),
+                _ if other.is_negative() && ieee(&self, &other) => {
+                    let (base, exp) = (self.to_real().unwrap(), other.to_real().unwrap());
+                    Ok(Number::Real(Real::new(base.value().powf(exp.value()))))
+                }
+                _ if other.is_negative() => Err(ArithmeticError::DivisionByZero),
+                _ => Ok(self),
+            };
+        }
+
+        let finite = self.is_finite() && other.is_finite();
+        if let (None, Some(base), Some(exp)) = (&exp, self.to_algebraic(), other.to_rational()) {
+            let index = exp.denom.value();
+            if !base.is_negative() && index <= Long::from(MAX_DEGREE) {
+                if let Some(root) = base.root(index.low_u64() as u32) {
+                    let result = algebraic_pow(root, exp.numer.value(), MAX_BITS)?;
+                    return check_result(Operation::Power, finite, true, result);
+                }
+            }
+            if base.is_negative() {
+                // principal value, the root of the magnitude turned by
+                // `(-1)^(p/q)`
+                let magnitude = Number::Algebraic(-base).checked_pow(other)?.normalize();
+                return magnitude.checked_mul(unit_power(&exp));
+            }
+        }
+        let result = match exp {
+            Some(exp) if matches!(self, Number::Decimal(_)) => match self {
+                Number::Decimal(dec) => decimal_pow(dec, exp, MAX_BITS)?,
+                _ => unreachable!(),
+            },
+            Some(exp) if matches!(self, Number::Algebraic(_)) => match self {
+                Number::Algebraic(alg) => algebraic_pow(alg, exp, MAX_BITS)?,
+                _ => unreachable!(),
+            },
+            Some(exp) if self.rank() <= 3 => exact_pow(self, exp)?,
+            Some(exp) if matches!(self, Number::Gaussian(_)) => gaussian_pow(self, exp)?,
+            Some(exp) if exp.bits() <= 31 => {
+                let (magnitude, negative) = (exp.abs().low_u64(), exp.is_negative());
+                match self {
+                    Number::Complex(com) => {
+                        let power = power(com, magnitude, Complex::new(1, 0));
+                        Number::Complex(if negative { power.inv() } else { power })
+                    }
+                    base => {
+                        let power = power(base.to_real().unwrap(), magnitude, Real::new(1));
+                        Number::Real(if negative { Real::new(1) / power } else { power })
+                    }
+                }
+            }
+            _ => match (self.to_real(), other.to_real()) {
+                (Some(base), Some(exp)) if !base.is_negative() => Number::Real(base.powf(&exp)),
+                _ => Number::Complex(self.to_complex().pow(&other.to_complex())),
+            },
+        };
+        check_result(operation, finite, true, result)
+    }
+
+    /// Negative for numbers below zero, complex numbers are never negative.
+    pub(crate) fn is_negative(&self) -> bool {
+        match self {
+            Number::Integer(int) => int.value().is_negative(),
+            Number::Decimal(dec) => dec.is_negative(),
+            Number::Rational(rat) => rat.numer.value().is_negative(),
+            Number::Algebraic(alg) => alg.is_negative(),
+            Number::Real(rea) => rea.is_negative(),
+            Number::Interval(ivl) => ivl.hi() < 0.0,
+            Number::Uncertain(unc) => unc.value() < 0.0,
+            Number::Dual(dual) => dual.value() < 0.0,
+            _ => false,
+        }
+    }
+
+    pub fn checked_rem(self, other: Number) -> Result<Number, ArithmeticError> {
+        if self.is_modular() || other.is_modular() {
+            return checked_modular(Operation::Remainder, self, other);
+        }
+        if self.is_dual() || other.is_dual() {
+            let (operation, finite) = (Operation::Remainder, self.is_finite() && other.is_finite());
+            return check_result(operation, finite, false, dual_operation(operation, self, other)?);
+        }
+        check_operands(Operation::Remainder, &self, &other)?;
+
+        Ok(match (self, other) {
+            (Number::Natural(a), Number::Natural(b)) => Number::Natural(a % b),
+            (Number::Natural(a), Number::Integer(b)) => Number::Integer(a % b),
+            (Number::Integer(a), Number::Natural(b)) => Number::Integer(a % b),
+            (Number::Integer(a), Number::Integer(b)) => Number::Integer(a % b),
+            (a, b) if a.to_decimal().is_some() && b.to_decimal().is_some() => {
+                Number::Decimal(a.to_decimal().unwrap() % b.to_decimal().unwrap())
+            }
+            (a, b) => match (a.to_rational(), b.to_rational()) {
+                (Some(x), Some(y)) => Number::Rational(x % y),
+                _ => match (a.to_algebraic(), b.to_algebraic()) {
+                    (Some(x), Some(y)) => {
+                        let quot: Rational = (x.clone() / y.clone()).trunc().into();
+                        Number::Algebraic(x - y * Algebraic::from(quot))
+                    }
+                    _ => Number::Real(a.to_real().unwrap() % b.to_real().unwrap()),
+                },
+            },
+        })
+    }
+
+    /// Ordering of the values, an error for complex numbers that are not
+    /// real, for different residues and for overlapping intervals.
+    /// Measurements and dual numbers are ordered by their values.
+    pub fn checked_cmp(&self, other: &Number) -> Result<Ordering, ArithmeticError> {
+        if self.is_modular() || other.is_modular() {
+            return match modular_equal(self, other)? {
+                true => Ok(Ordering::Equal),
+                false => Err(ArithmeticError::Domain("cannot order Modular numbers".to_owned())),
+            };
+        }
+        if self.is_interval() || other.is_interval() {
+            return interval_cmp(self, other);
+        }
+        if self.is_uncertain() || other.is_uncertain() {
+            return uncertain_cmp(self, other);
+        }
+        if self.is_dual() || other.is_dual() {
+            return dual_cmp(self, other);
+        }
+        if self.is_algebraic() || other.is_algebraic() {
+            if let (Some(a), Some(b)) = (self.to_algebraic(), other.to_algebraic()) {
+                return Ok((a - b).signum());
+            }
+        }
+        if self.is_nan() || other.is_nan() {
+            return Err(ArithmeticError::Domain("cannot order nan".to_owned()));
+        }
+        match (self.to_gaussian(), other.to_gaussian()) {
+            (Some(a), Some(b)) => a.checked_cmp(&b),
+            _ => self.to_complex().checked_cmp(&other.to_complex()),
+        }
+    }
+
+    /// Largest integer not above `self`.
+    pub fn floor(self) -> Result<Number, ArithmeticError> {
+        rounded_operand("floor", &self)?;
+        Ok(match self {
+            Number::Decimal(dec) => Number::Decimal(dec.floor()),
+            Number::Rational(rat) => Number::Integer(rat.floor()),
+            Number::Algebraic(alg) => Number::Integer(alg.floor()),
+            Number::Real(rea) => Number::Real(rea.floor()),
+            Number::Interval(ivl) => Number::Interval(ivl.floor()),
+            // constant between the jumps
+            Number::Dual(dual) => Number::Real(Real::new(dual.value().floor())),
+            number => number,
+        })
+    }
+
+    /// Smallest integer not below `self`.
+    pub fn ceil(self) -> Result<Number, ArithmeticError> {
+        rounded_operand("ceil", &self)?;
+        Ok(-(-self).floor()?)
+    }
+
+    /// Integer part, rounded toward zero.
+    pub fn trunc(self) -> Result<Number, ArithmeticError> {
+        rounded_operand("trunc", &self)?;
+        match self {
+            Number::Interval(ivl) => Ok(Number::Interval(ivl.trunc())),
+            number if number.is_negative() => number.ceil(),
+            number => number.floor(),
+        }
+    }
+
+    /// Nearest integer, halves are rounded to the even one.
+    pub fn round(self) -> Result<Number, ArithmeticError> {
+        rounded_operand("round", &self)?;
+        Ok(match self {
+            Number::Decimal(dec) => Number::Decimal(Decimal::new(dec.to_rational().round().value(), 0)),
+            Number::Rational(rat) => Number::Integer(rat.round()),
+            Number::Algebraic(alg) => Number::Integer(alg.refined(Rational::round)),
+            Number::Real(rea) => Number::Real(rea.round()),
+            Number::Interval(ivl) => Number::Interval(ivl.round()),
+            Number::Dual(dual) => Number::Real(Real::new(dual.value().round_ties_even())),
+            number => number,
+        })
+    }
+
+    /// Fractional part `self - trunc(self)`, with the sign of `self`.
+    pub fn frac(self) -> Result<Number, ArithmeticError> {
+        if self.is_interval() {
+            return Err(undefined("frac", self.kind()));
+        }
+        let int = self.clone().trunc()?;
+        self.checked_sub(int)
+    }
+
+    /// Quotient rounded toward negative infinity.
+    pub fn checked_floor_div(self, other: Number) -> Result<Number, ArithmeticError> {
+        ordered_operands("floor division", &self, &other)?;
+        self.checked_div(other)?.floor()
+    }
+
+    /// Euclidean quotient `q`, such that `self = q * other + r` with
+    /// `0 <= r < |other|`.
+    pub fn checked_euclid_div(self, other: Number) -> Result<Number, ArithmeticError> {
+        ordered_operands("integer division", &self, &other)?;
+        let negative = other.is_negative();
+        let quot = self.checked_floor_div(other.abs())?;
+        Ok(if negative { -quot } else { quot })
+    }
+
+    /// Euclidean remainder, never negative.
+    pub fn checked_euclid_rem(self, other: Number) -> Result<Number, ArithmeticError> {
+        ordered_operands("modulo", &self, &other)?;
+        let divisor = other.abs();
+        let quot = self.clone().checked_floor_div(divisor.clone())?;
+        self.checked_sub(divisor.checked_mul(quot)?)
+    }
+
+    /// Euclidean quotient and remainder.
+    pub fn checked_divmod(self, other: Number) -> Result<(Number, Number), ArithmeticError> {
+        let quot = self.clone().checked_euclid_div(other.clone())?;
+        let rem = self.checked_sub(other.checked_mul(quot.clone())?)?;
+        Ok((quot, rem))
+    }
+}
+
+/// Operations that need an order, which complex numbers and residues lack.
+fn ordered_operands(operation: &str, a: &Number, b: &Number) -> Result<(), ArithmeticError> {
+    match (a, b) {
+        (Number::Complex(_), _) | (_, Number::Complex(_)) => Err(unordered(operation)),
+        (Number::Gaussian(_), _) | (_, Number::Gaussian(_)) => Err(unordered(operation)),
+        (Number::Modular(_), _) | (_, Number::Modular(_)) => Err(undefined(operation, "Modular")),
+        (Number::Uncertain(_), _) | (_, Number::Uncertain(_)) => Err(undefined(operation, "Uncertain")),
+        _ => Ok(()),
+    }
+}
+
+/// Rounding needs a real value, measurements are not rounded.
+fn rounded_operand(operation: &str, number: &Number) -> Result<(), ArithmeticError> {
+    match number {
+        Number::Uncertain(_) | Number::Complex(_) | Number::Gaussian(_) | Number::Modular(_) => {
+            Err(undefined(operation, number.kind()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn unordered(operation: &str) -> ArithmeticError {
+    undefined(operation, "Complex")
+}
+
+fn undefined(operation: &str, kind: &str) -> ArithmeticError {
+    ArithmeticError::Domain(format!("{} is not defined for {} numbers", operation, kind))
+}
+
+/// Exact results are refused above this size instead of exhausting memory.
+const MAX_BITS: usize = 1 << 20;
+
+fn check_operands(operation: Operation, a: &Number, b: &Number) -> Result<(), ArithmeticError> {
+    let kind = if a.rank() >= b.rank() { a.kind() } else { b.kind() };
+
+    match operation {
+        Operation::Division | Operation::Remainder if b.is_zero() && !ieee(a, b) => {
+            return Err(ArithmeticError::DivisionByZero)
+        }
+        Operation::Remainder if kind == "Complex" => return Err(unordered("remainder")),
+        _ => {}
+    }
+    if a.is_interval() || b.is_interval() {
+        check_interval(operation, a, b)?;
+    }
+    if a.is_uncertain() || b.is_uncertain() {
+        check_uncertain(operation, a, b)?;
+    }
+
+    if let (Some(x), Some(y)) = (a.bits(), b.bits()) {
+        let bits = match operation {
+            Operation::Addition | Operation::Subtraction => max(x, y) + 1,
+            _ => x + y,
+        };
+        if bits > MAX_BITS {
+            return Err(ArithmeticError::Overflow { kind, operation });
+        }
+    }
+    Ok(())
+}
+
+/// Power of an exact nonzero base with a whole exponent.
+fn exact_pow(base: Number, exp: Long) -> Result<Number, ArithmeticError> {
+    let operation = Operation::Power;
+    let kind = base.kind();
+    let rational = base.to_rational().unwrap();
+    let unit = rational.denom == integer!(1) && rational.numer.value().abs() == Long::one();
+    if unit {
+        // any exponent of `1` and `-1` works, only its parity matters
+        return Ok(if rational.numer.value().is_negative() && exp.is_odd() {
+            base
+        } else {
+            Number::Natural(natural!(1))
+        });
+    }
+
+    let bits = base.bits().unwrap_or(0);
+    if exp.bits() > 31 || bits.saturating_mul(exp.abs().low_u64() as usize) > MAX_BITS {
+        return Err(ArithmeticError::Overflow { kind, operation });
+    }
+    let magnitude = exp.abs().low_u64() as u32;
+    if exp.is_negative() {
+        let inv = rational.inv();
+        return Ok(Number::Rational(Rational::new(
+            Integer::new(inv.numer.value().pow(magnitude)),
+            Integer::new(inv.denom.value().pow(magnitude)),
+        )));
+    }
+    Ok(match base {
+        Number::Natural(nat) => Number::Natural(Natural::new(nat.value().pow(magnitude))),
+        Number::Integer(int) => Number::Integer(Integer::new(int.value().pow(magnitude))),
+        _ => Number::Rational(Rational::new(
+            Integer::new(rational.numer.value().pow(magnitude)),
+            Integer::new(rational.denom.value().pow(magnitude)),
+        )),
+    })
+}
+
+/// Whole power of an exact nonzero complex base.
+fn gaussian_pow(base: Number, exp: Long) -> Result<Number, ArithmeticError> {
+    let operation = Operation::Power;
+    let kind = base.kind();
+    let bits = base.bits().unwrap_or(0);
+    if exp.bits() > 31 || bits.saturating_mul(exp.abs().low_u64() as usize) > MAX_BITS {
+        return Err(ArithmeticError::Overflow { kind, operation });
+    }
+    let base = base.to_gaussian().unwrap();
+    let one = Gaussian::new(rational!(1), rational!(0));
+    let power = power(base, exp.abs().low_u64(), one);
+    Ok(Number::Gaussian(if exp.is_negative() { power.inv() } else { power }))
+}
+
+/// Principal value of `(-1)^(p/q)`, `cis(pi * p/q)`, exactly `i` or `-i`
+/// for square roots.
+fn unit_power(exp: &Rational) -> Number {
+    let (numer, denom) = (exp.numer.value(), exp.denom.value());
+    let turn = &denom * &Long::from(2);
+    let mut angle = &(&(&numer % &turn) + &turn) % &turn;
+    if angle > denom {
+        angle = angle - turn;
+    }
+    if denom == Long::from(2) {
+        return Number::Gaussian(Gaussian::new(rational!(0), Rational::new(Integer::new(angle), integer!(1))));
+    }
+    let angle = Real::pi() * Real::from(angle) / Real::from(denom);
+    Number::Complex(Complex::from_polar(1, angle))
+}
+
+/// `base^exp` by repeated squaring.
+fn power<T: Clone + Mul<Output = T>>(base: T, exp: u64, one: T) -> T {
+    let mut result = one;
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base.clone();
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.clone() * base;
+        }
+    }
+    result
+}
+
+/// Widens the left operand when the result leaves the type of both
+/// operands: a negative difference of naturals is an `Integer`, an inexact
+/// quotient of integers is a `Rational`.
+fn promote(operation: Operation, a: Number, b: &Number) -> Number {
+    match (operation, a, b) {
+        (Operation::Subtraction, Number::Natural(x), Number::Natural(y)) if &x < y => {
+            Number::Integer(x.into())
+        }
+        (Operation::Division, a, b) if a.rank() <= 1 && b.rank() <= 1 => {
+            let exact = match a.clone().checked_rem(b.clone()) {
+                Ok(rem) => rem.is_zero(),
+                Err(_) => true,
+            };
+            if exact {
+                a
+            } else {
+                Number::Rational(a.to_rational().unwrap())
+            }
+        }
+        (_, a, _) => a,
+    }
+}
+
+/// Whether an operation on `a` and `b` gives a real that follows IEEE
+/// arithmetic, with infinities and NaN in place of errors. Strict
+/// sessions report them.
+fn ieee(a: &Number, b: &Number) -> bool {
+    let real = Number::Real(Real::zero()).rank();
+    !Real::strict() && max(a.rank(), b.rank()) == real
+}
+
+/// Reals have a limited range: in strict sessions finite operands must
+/// not give an infinite result, and nonzero finite factors must not
+/// vanish. Decimals may round to zero. Algebraic numbers of a higher
+/// degree than `MAX_DEGREE` are given as reals.
+fn check_result(
+    operation: Operation,
+    finite: bool,
+    nonzero: bool,
+    result: Number,
+) -> Result<Number, ArithmeticError> {
+    let result = match result {
+        Number::Algebraic(alg) if alg.degree() > MAX_DEGREE => Number::Real(alg.into()),
+        result => result,
+    };
+    let kind = result.kind();
+    if matches!(result, Number::Real(_)) && !Real::strict() {
+        return Ok(result);
+    }
+    if finite && !result.is_finite() {
+        return Err(ArithmeticError::Overflow { kind, operation });
+    }
+    let scaling = matches!(
+        operation,
+        Operation::Multiplication | Operation::Division | Operation::Power
+    );
+    if finite && scaling && nonzero && result.is_zero() && !matches!(result, Number::Decimal(_)) {
+        return Err(ArithmeticError::Underflow { kind, operation });
+    }
+    Ok(result)
+}
+
+/// Arithmetic on the Riemann sphere, where complex infinity is one point:
+/// it absorbs finite terms and nonzero factors, while `inf - inf`,
+/// `0 * inf` and `inf / inf` are NaN.
+fn infinite_operation(operation: Operation, a: &Number, b: &Number) -> Number {
+    let infinite = |number: &Number| !number.is_finite() && !number.is_nan();
+    let nan = Number::Real(Real::new(f64::NAN));
+    if a.is_nan() || b.is_nan() {
+        return nan;
+    }
+    match operation {
+        Operation::Addition | Operation::Subtraction if infinite(a) && infinite(b) => nan,
+        Operation::Multiplication if a.is_zero() || b.is_zero() => nan,
+        Operation::Division if infinite(a) && infinite(b) => nan,
+        Operation::Division if infinite(b) => Number::Real(Real::zero()),
+        _ => Number::Complex(Complex::infinity()),
+    }
+}
+
+/// Powers with complex infinity: its real powers are infinite or zero by
+/// their sign, other powers are NaN.
+fn infinite_pow(base: &Number, exp: &Number) -> Number {
+    if exp.is_zero() {
+        return Number::Natural(natural!(1));
+    }
+    match exp.to_real() {
+        Some(exp) if base.is_complex_infinity() && exp.is_negative() => Number::Real(Real::zero()),
+        Some(exp) if base.is_complex_infinity() && !exp.is_nan() => Number::Complex(Complex::infinity()),
+        _ => Number::Real(Real::new(f64::NAN)),
     }
 }
 
 impl Display for Number {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match *self {
-            Number::Natural(val) => val.fmt(f),
-            Number::Integer(val) => val.fmt(f),
-            Number::Rational(val) => val.fmt(f),
-            Number::Real(val) => val.fmt(f),
-            Number::Complex(val) => val.fmt(f),
-        }
+        self.display(Notation::session()).fmt(f)
     }
 }
 
@@ -67,13 +828,7 @@ impl Rem for Number {
     type Output = Number;
 
     fn rem(self, other: Number) -> Number {
-        match (self, other) {
-            (Number::Natural(a), Number::Natural(b)) => Number::Natural(a % b),
-            (Number::Natural(a), Number::Integer(b)) => Number::Integer(a % b),
-            (Number::Integer(a), Number::Natural(b)) => Number::Integer(a % b),
-            (Number::Integer(a), Number::Integer(b)) => Number::Integer(a % b),
-            _ => unimplemented!()
-        }
+        self.checked_rem(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
@@ -84,58 +839,186 @@ impl Neg for Number {
         match self {
             Number::Natural(nat) => Number::Integer(-nat),
             Number::Integer(int) => Number::Integer(-int),
+            Number::Decimal(dec) => Number::Decimal(-dec),
             Number::Rational(rat) => Number::Rational(-rat),
+            Number::Algebraic(alg) => Number::Algebraic(-alg),
             Number::Real(rea) => Number::Real(-rea),
+            Number::Interval(ivl) => Number::Interval(-ivl),
+            Number::Uncertain(unc) => Number::Uncertain(-unc),
+            Number::Dual(dual) => Number::Dual(-dual),
             Number::Complex(com) => Number::Complex(-com),
+            Number::Gaussian(gau) => Number::Gaussian(-gau),
+            Number::Modular(m) => Number::Modular(-m),
         }
     }
 }
 
 macro_rules! impl_ops_for_number {
-    () => {impl_ops_for_number! { (Add; add; +) (Sub; sub; -) (Mul; mul; *) (Div; div; /) } };
-    ($(($tr:ty; $name:ident; $op:tt))*) => { $(impl_ops_for_number! { @impl $tr; $name; $op } )* };
-    (@impl $tr:ty; $name:ident; $op:tt) => {
+    () => {
+        impl_ops_for_number! {
+            (Add; add; checked_add; Addition; +) (Sub; sub; checked_sub; Subtraction; -)
+            (Mul; mul; checked_mul; Multiplication; *) (Div; div; checked_div; Division; /)
+        }
+    };
+    ($(($tr:ty; $name:ident; $checked:ident; $operation:ident; $op:tt))*) => {
+        $(impl_ops_for_number! { @impl $tr; $name; $checked; $operation; $op } )*
+    };
+    (@impl $tr:ty; $name:ident; $checked:ident; $operation:ident; $op:tt) => {
         impl $tr for Number {
             type Output = Number;
 
             fn $name(self, other: Number) -> Number {
-                match self {
+                self.$checked(other).unwrap_or_else(|err| panic!("{}", err))
+            }
+        }
+
+        impl Number {
+            pub fn $checked(self, other: Number) -> Result<Number, ArithmeticError> {
+                let operation = Operation::$operation;
+                if self.is_modular() || other.is_modular() {
+                    return checked_modular(operation, self, other);
+                }
+                if self.is_dual() || other.is_dual() {
+                    let finite = self.is_finite() && other.is_finite();
+                    return check_result(operation, finite, false, dual_operation(operation, self, other)?);
+                }
+                check_operands(operation, &self, &other)?;
+                if self.is_complex_infinity() || other.is_complex_infinity() {
+                    return Ok(infinite_operation(operation, &self, &other));
+                }
+                let finite = self.is_finite() && other.is_finite();
+                let nonzero = !self.is_zero() && !other.is_zero();
+
+                let result = match promote(operation, self, &other) {
                     Number::Natural(nat) => match other {
                         Number::Natural(oth) => Number::Natural(nat $op oth),
                         Number::Integer(int) => Number::Integer(nat $op int),
+                        Number::Decimal(dec) => Number::Decimal(nat $op dec),
                         Number::Rational(rat) => Number::Rational(nat $op rat),
+                        Number::Algebraic(alg) => Number::Algebraic(nat $op alg),
                         Number::Real(rea) => Number::Real(nat $op rea),
+                        Number::Interval(ivl) => Number::Interval(nat $op ivl),
+                        Number::Uncertain(unc) => Number::Uncertain(nat $op unc),
                         Number::Complex(com) => Number::Complex(nat $op com),
+                        Number::Gaussian(gau) => Number::Gaussian(nat $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
                     },
                     Number::Integer(int) => match other {
                         Number::Natural(nat) => Number::Integer(int $op nat),
                         Number::Integer(oth) => Number::Integer(int $op oth),
+                        Number::Decimal(dec) => Number::Decimal(int $op dec),
                         Number::Rational(rat) => Number::Rational(int $op rat),
+                        Number::Algebraic(alg) => Number::Algebraic(int $op alg),
                         Number::Real(rea) => Number::Real(int $op rea),
+                        Number::Interval(ivl) => Number::Interval(int $op ivl),
+                        Number::Uncertain(unc) => Number::Uncertain(int $op unc),
                         Number::Complex(com) => Number::Complex(int $op com),
+                        Number::Gaussian(gau) => Number::Gaussian(int $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
+                    },
+                    Number::Decimal(dec) => match other {
+                        Number::Natural(nat) => Number::Decimal(dec $op nat),
+                        Number::Integer(int) => Number::Decimal(dec $op int),
+                        Number::Decimal(oth) => Number::Decimal(dec $op oth),
+                        Number::Rational(rat) => Number::Rational(dec $op rat),
+                        Number::Algebraic(alg) => Number::Algebraic(dec $op alg),
+                        Number::Real(rea) => Number::Real(dec $op rea),
+                        Number::Interval(ivl) => Number::Interval(dec $op ivl),
+                        Number::Uncertain(unc) => Number::Uncertain(dec $op unc),
+                        Number::Complex(com) => Number::Complex(dec $op com),
+                        Number::Gaussian(gau) => Number::Gaussian(dec $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
                     },
                     Number::Rational(rat) => match other {
                         Number::Natural(nat) => Number::Rational(rat $op nat),
                         Number::Integer(int) => Number::Rational(rat $op int),
+                        Number::Decimal(dec) => Number::Rational(rat $op dec),
                         Number::Rational(oth) => Number::Rational(rat $op oth),
+                        Number::Algebraic(alg) => Number::Algebraic(rat $op alg),
                         Number::Real(rea) => Number::Real(rat $op rea),
+                        Number::Interval(ivl) => Number::Interval(rat $op ivl),
+                        Number::Uncertain(unc) => Number::Uncertain(rat $op unc),
                         Number::Complex(com) => Number::Complex(rat $op com),
+                        Number::Gaussian(gau) => Number::Gaussian(rat $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
+                    },
+                    Number::Algebraic(alg) => match other {
+                        Number::Natural(nat) => Number::Algebraic(alg $op nat),
+                        Number::Integer(int) => Number::Algebraic(alg $op int),
+                        Number::Decimal(dec) => Number::Algebraic(alg $op dec),
+                        Number::Rational(rat) => Number::Algebraic(alg $op rat),
+                        Number::Algebraic(oth) => Number::Algebraic(alg $op oth),
+                        Number::Real(rea) => Number::Real(alg $op rea),
+                        Number::Interval(ivl) => Number::Interval(alg $op ivl),
+                        Number::Uncertain(unc) => Number::Uncertain(alg $op unc),
+                        Number::Complex(com) => Number::Complex(alg $op com),
+                        Number::Gaussian(gau) => Number::Complex(alg $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
                     },
                     Number::Real(rea) => match other {
                         Number::Natural(nat) => Number::Real(rea $op nat),
                         Number::Integer(int) => Number::Real(rea $op int),
+                        Number::Decimal(dec) => Number::Real(rea $op dec),
                         Number::Rational(rat) => Number::Real(rea $op rat),
+                        Number::Algebraic(alg) => Number::Real(rea $op alg),
                         Number::Real(oth) => Number::Real(rea $op oth),
+                        Number::Interval(ivl) => Number::Interval(rea $op ivl),
+                        Number::Uncertain(unc) => Number::Uncertain(rea $op unc),
                         Number::Complex(com) => Number::Complex(rea $op com),
+                        Number::Gaussian(gau) => Number::Complex(rea $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
                     },
                     Number::Complex(com) => match other {
                         Number::Natural(nat) => Number::Complex(com $op nat),
                         Number::Integer(int) => Number::Complex(com $op int),
+                        Number::Decimal(dec) => Number::Complex(com $op dec),
                         Number::Rational(rat) => Number::Complex(com $op rat),
+                        Number::Algebraic(alg) => Number::Complex(com $op alg),
                         Number::Real(rea) => Number::Complex(com $op rea),
+                        Number::Interval(_) | Number::Uncertain(_) => unreachable!(),
                         Number::Complex(oth) => Number::Complex(com $op oth),
+                        Number::Gaussian(gau) => Number::Complex(com $op gau),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
                     },
-                }
+                    Number::Interval(ivl) => match other {
+                        Number::Natural(nat) => Number::Interval(ivl $op nat),
+                        Number::Integer(int) => Number::Interval(ivl $op int),
+                        Number::Decimal(dec) => Number::Interval(ivl $op dec),
+                        Number::Rational(rat) => Number::Interval(ivl $op rat),
+                        Number::Algebraic(alg) => Number::Interval(ivl $op alg),
+                        Number::Real(rea) => Number::Interval(ivl $op rea),
+                        Number::Interval(oth) => Number::Interval(ivl $op oth),
+                        Number::Uncertain(_) | Number::Complex(_) | Number::Gaussian(_) | Number::Modular(_) | Number::Dual(_) => {
+                            unreachable!()
+                        }
+                    },
+                    Number::Uncertain(unc) => match other {
+                        Number::Natural(nat) => Number::Uncertain(unc $op nat),
+                        Number::Integer(int) => Number::Uncertain(unc $op int),
+                        Number::Decimal(dec) => Number::Uncertain(unc $op dec),
+                        Number::Rational(rat) => Number::Uncertain(unc $op rat),
+                        Number::Algebraic(alg) => Number::Uncertain(unc $op alg),
+                        Number::Real(rea) => Number::Uncertain(unc $op rea),
+                        Number::Uncertain(oth) => Number::Uncertain(unc $op oth),
+                        Number::Interval(_) | Number::Complex(_) | Number::Gaussian(_) | Number::Modular(_) | Number::Dual(_) => {
+                            unreachable!()
+                        }
+                    },
+                    Number::Gaussian(gau) => match other {
+                        Number::Natural(nat) => Number::Gaussian(gau $op nat),
+                        Number::Integer(int) => Number::Gaussian(gau $op int),
+                        Number::Decimal(dec) => Number::Gaussian(gau $op dec),
+                        Number::Rational(rat) => Number::Gaussian(gau $op rat),
+                        Number::Algebraic(alg) => Number::Complex(gau $op alg),
+                        Number::Real(rea) => Number::Complex(gau $op rea),
+                        Number::Interval(_) | Number::Uncertain(_) => unreachable!(),
+                        Number::Complex(com) => Number::Complex(gau $op com),
+                        Number::Gaussian(oth) => Number::Gaussian(gau $op oth),
+                        Number::Modular(_) | Number::Dual(_) => unreachable!(),
+                    },
+                    Number::Modular(_) | Number::Dual(_) => unreachable!(),
+                };
+                check_result(operation, finite, nonzero, result)
             }
         }
     }
@@ -143,45 +1026,44 @@ macro_rules! impl_ops_for_number {
 
 impl_ops_for_number!{}
 
+/// Exact numbers compare exactly, reals with the tolerance of `Real`.
 impl PartialOrd for Number {
     fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
-        let self_ = match *self {
-            Number::Natural(nat) => nat.into(),
-            Number::Integer(int) => int.into(),
-            Number::Rational(rat) => rat.into(),
-            Number::Real(rea) => rea.into(),
-            Number::Complex(com) => com,
-        };
-        let other_ = match *other {
-            Number::Natural(nat) => nat.into(),
-            Number::Integer(int) => int.into(),
-            Number::Rational(rat) => rat.into(),
-            Number::Real(rea) => rea.into(),
-            Number::Complex(com) => com,
-        };
-
-        self_.partial_cmp(&other_)
+        self.checked_cmp(other).ok()
     }
 }
 
 impl PartialEq for Number {
     fn eq(&self, other: &Number) -> bool {
-        let self_ = match *self {
-            Number::Natural(nat) => nat.into(),
-            Number::Integer(int) => int.into(),
-            Number::Rational(rat) => rat.into(),
-            Number::Real(rea) => rea.into(),
-            Number::Complex(com) => com,
-        };
-        let other_ = match *other {
-            Number::Natural(nat) => nat.into(),
-            Number::Integer(int) => int.into(),
-            Number::Rational(rat) => rat.into(),
-            Number::Real(rea) => rea.into(),
-            Number::Complex(com) => com,
-        };
-
-        self_.eq(&other_)
+        if self.is_modular() || other.is_modular() {
+            return modular_equal(self, other).unwrap_or(false);
+        }
+        if self.is_interval() || other.is_interval() {
+            return self.to_interval() == other.to_interval();
+        }
+        if self.is_uncertain() || other.is_uncertain() {
+            return match (self.to_uncertain(), other.to_uncertain()) {
+                (Some(a), Some(b)) => {
+                    Real::new(a.value()) == Real::new(b.value()) && Real::new(a.error()) == Real::new(b.error())
+                }
+                _ => false,
+            };
+        }
+        if self.is_dual() || other.is_dual() {
+            return dual_cmp(self, other) == Ok(Ordering::Equal);
+        }
+        if self.is_algebraic() || other.is_algebraic() {
+            if let (Some(a), Some(b)) = (self.to_algebraic(), other.to_algebraic()) {
+                return a == b;
+            }
+        }
+        match (self.to_gaussian(), other.to_gaussian()) {
+            (Some(a), Some(b)) => a == b,
+            _ => match (self.to_real(), other.to_real()) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.to_complex() == other.to_complex(),
+            },
+        }
     }
 }
 
@@ -12,14 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::Integer;
-use utils::gcd;
+use super::{Integer, Long};
+use utils::{gcd, ldexp};
 
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::fmt::{self, Display, Formatter};
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
-#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone)]
 pub struct Rational {
     pub(crate) numer: Integer,
     pub(crate) denom: Integer,
@@ -40,29 +40,223 @@ impl Rational {
     }
 
     pub fn norm(&mut self) {
-        let gcd = gcd(self.numer, self.denom);
+        if self.denom == integer!(0) {
+            panic!("zero denominator in Rational")
+        }
+        let gcd = gcd_int(&self.numer, &self.denom);
 
-        self.numer = self.numer / gcd;
-        self.denom = self.denom / gcd;
+        self.numer = self.numer.clone() / gcd.clone();
+        self.denom = self.denom.clone() / gcd;
 
         if self.denom < integer!(0) {
-            self.numer = self.numer * integer!(-1);
-            self.denom = self.denom * integer!(-1);
+            self.numer = -self.numer.clone();
+            self.denom = -self.denom.clone();
+        }
+    }
+
+    /// Exact value of a decimal like `-12.5` or `1.25e-7`, `None` for
+    /// other text such as `inf`.
+    pub fn from_decimal(text: &str) -> Option<Rational> {
+        let mut parts = text.splitn(2, ['e', 'E']);
+        let mantissa = parts.next()?;
+        let exponent = match parts.next() {
+            Some(exponent) => exponent.parse::<i64>().ok()?,
+            None => 0,
+        };
+        let mut parts = mantissa.splitn(2, '.');
+        let whole = parts.next()?;
+        let fraction = parts.next().unwrap_or("");
+        let numer = format!("{}{}", whole, fraction).parse::<Long>().ok()?;
+
+        let scale = exponent - fraction.len() as i64;
+        let power = Long::from(10).pow(scale.unsigned_abs() as u32);
+        Some(if scale >= 0 {
+            Rational::new(Integer::new(numer * power), integer!(1))
+        } else {
+            Rational::new(Integer::new(numer), Integer::new(power))
+        })
+    }
+
+    /// Integer part, rounded toward zero.
+    pub fn trunc(&self) -> Integer {
+        self.numer.clone() / self.denom.clone()
+    }
+
+    /// Largest integer not above `self`.
+    pub fn floor(&self) -> Integer {
+        Integer::new(floor_quot(&self.numer.value(), &self.denom.value()))
+    }
+
+    /// Nearest integer, halves are rounded to the even one.
+    pub fn round(&self) -> Integer {
+        let (numer, denom) = (self.numer.value(), self.denom.value());
+        let two = Long::from(2);
+        let round = floor_quot(&(&numer * &two + denom.clone()), &(&denom * &two));
+        // only a denominator of two makes a half
+        if denom == two && round.is_odd() {
+            Integer::new(round - Long::one())
+        } else {
+            Integer::new(round)
+        }
+    }
+
+    /// First `count` terms `[a0; a1, a2, ...]` of the continued fraction,
+    /// fewer when it ends sooner.
+    pub fn continued_fraction(&self, count: usize) -> Vec<Integer> {
+        let (mut numer, mut denom) = (self.numer.value(), self.denom.value());
+        let mut terms = Vec::new();
+        while !denom.is_zero() && terms.len() < count {
+            let term = floor_quot(&numer, &denom);
+            let rem = &numer - &(&term * &denom);
+            terms.push(Integer::new(term));
+            numer = denom;
+            denom = rem;
         }
+        terms
+    }
+
+    /// Convergents of the first `count` terms of the continued fraction,
+    /// each the best approximation up to its own denominator.
+    pub fn convergents(&self, count: usize) -> Vec<Rational> {
+        Rational::from_terms(&self.continued_fraction(count))
+    }
+
+    /// Convergents of the continued fraction with the given terms.
+    pub fn from_terms(terms: &[Integer]) -> Vec<Rational> {
+        let (mut numer, mut numer_prev) = (Long::one(), Long::zero());
+        let (mut denom, mut denom_prev) = (Long::zero(), Long::one());
+        let mut result = Vec::new();
+        for term in terms {
+            let term = term.value();
+            let next_numer = &(&term * &numer) + &numer_prev;
+            let next_denom = &(&term * &denom) + &denom_prev;
+            numer_prev = numer;
+            denom_prev = denom;
+            numer = next_numer;
+            denom = next_denom;
+            result.push(Rational::new(Integer::new(numer.clone()), Integer::new(denom.clone())));
+        }
+        result
+    }
+
+    /// Closest rational with a denominator up to the positive `max_denom`,
+    /// the last convergent or semiconvergent that fits.
+    pub fn limit_denominator(&self, max_denom: &Long) -> Rational {
+        if self.denom.value() <= *max_denom {
+            return self.clone();
+        }
+        let (mut numer_prev, mut denom_prev) = (Long::zero(), Long::one());
+        let (mut numer, mut denom) = (Long::one(), Long::zero());
+        let (mut n, mut d) = (self.numer.value(), self.denom.value());
+        loop {
+            let term = floor_quot(&n, &d);
+            let next_denom = &denom_prev + &(&term * &denom);
+            if next_denom > *max_denom {
+                break;
+            }
+            let next_numer = &numer_prev + &(&term * &numer);
+            numer_prev = numer;
+            denom_prev = denom;
+            numer = next_numer;
+            denom = next_denom;
+            let rem = &n - &(&term * &d);
+            n = d;
+            d = rem;
+        }
+
+        let steps = floor_quot(&(max_denom - &denom_prev), &denom);
+        let semiconvergent = Rational::new(
+            Integer::new(&numer_prev + &(&steps * &numer)),
+            Integer::new(&denom_prev + &(&steps * &denom)),
+        );
+        let convergent = Rational::new(Integer::new(numer), Integer::new(denom));
+        let distance = |rat: &Rational| {
+            let diff = rat.clone() - self.clone();
+            if diff < Rational::default() { -diff } else { diff }
+        };
+        if distance(&convergent) <= distance(&semiconvergent) {
+            convergent
+        } else {
+            semiconvergent
+        }
+    }
+
+    /// Rational with the smallest denominator in `[lo, hi]`, the one
+    /// nearest to zero among those, found by walking the Stern–Brocot tree
+    /// along the continued fractions of the bounds.
+    pub fn simplest_between(lo: &Rational, hi: &Rational) -> Rational {
+        let zero = Rational::default();
+        if *lo <= zero && zero <= *hi {
+            return zero;
+        }
+        if *hi < zero {
+            return -Rational::simplest_between(&-hi.clone(), &-lo.clone());
+        }
+        let floor: Rational = lo.floor().into();
+        if floor == *lo {
+            return floor;
+        }
+        let next = floor.clone() + Rational::new(integer!(1), integer!(1));
+        if next <= *hi {
+            return next;
+        }
+        let lo_rest = (lo.clone() - floor.clone()).inv();
+        let hi_rest = (hi.clone() - floor.clone()).inv();
+        floor + Rational::simplest_between(&hi_rest, &lo_rest).inv()
     }
 
     pub fn inv(&self) -> Rational {
-        Rational::new(self.denom, self.numer)
+        Rational::new(self.denom.clone(), self.numer.clone())
     }
 
+    /// Nearest `f64`, computed from a 64-bit quotient so that huge
+    /// numerators and denominators do not overflow to `inf / inf`.
     pub fn value(&self) -> f64 {
-        (self.numer.value() as f64) / (self.denom.value() as f64)
+        let numer = self.numer.value();
+        let denom = self.denom.value();
+        let shift = 64 + denom.bits() as i64 - numer.bits() as i64;
+
+        let (quot, rem) = if shift >= 0 {
+            (numer << shift as usize).div_rem(&denom)
+        } else {
+            numer.div_rem(&(denom << (-shift) as usize))
+        };
+        // one extra sticky bit keeps the rounding of the dropped tail
+        let mut quot = quot << 1;
+        if !rem.is_zero() {
+            quot = if rem.is_negative() {
+                quot - Long::one()
+            } else {
+                quot + Long::one()
+            };
+        }
+
+        ldexp(quot.to_f64(), -shift - 1)
+    }
+}
+
+/// `a / b` rounded toward negative infinity, for a positive `b`.
+fn floor_quot(a: &Long, b: &Long) -> Long {
+    let (quot, rem) = a.div_rem(b);
+    if rem.is_negative() {
+        quot - Long::one()
+    } else {
+        quot
     }
 }
 
+/// Greatest common divisor as an `Integer`, ready to divide by.
+fn gcd_int(a: &Integer, b: &Integer) -> Integer {
+    gcd(a.clone(), b.clone()).into()
+}
+
+/// `7 / 2`, a whole rational without its denominator.
 impl Display for Rational {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         self.numer.fmt(f)?;
+        if self.denom == integer!(1) {
+            return Ok(());
+        }
         " / ".fmt(f)?;
         self.denom.fmt(f)
     }
@@ -76,18 +270,39 @@ impl PartialOrd for Rational {
 
 impl Ord for Rational {
     fn cmp(&self, other: &Rational) -> Ordering {
-        (self.numer * other.denom).cmp(&(self.denom * other.numer))
+        // denominators are positive after normalization
+        let lhs = self.numer.clone() * other.denom.clone();
+        let rhs = other.numer.clone() * self.denom.clone();
+        lhs.cmp(&rhs)
     }
 }
 
 impl Add for Rational {
     type Output = Rational;
 
+    /// Knuth's addition: only the gcd of the denominators and of a
+    /// much smaller numerator are computed, and intermediate products
+    /// stay as small as the result allows.
     fn add(self, other: Rational) -> Rational {
-        Rational::new(
-            self.numer * other.denom + self.denom * other.numer,
-            self.denom * other.denom,
-        )
+        let d1 = gcd_int(&self.denom, &other.denom);
+        if d1 == integer!(1) {
+            return Rational {
+                numer: self.numer * other.denom.clone() + self.denom.clone() * other.numer,
+                denom: self.denom * other.denom,
+            };
+        }
+
+        let t = self.numer * (other.denom.clone() / d1.clone())
+            + other.numer * (self.denom.clone() / d1.clone());
+        if t == integer!(0) {
+            return Rational::default();
+        }
+        let d2 = gcd_int(&t, &d1);
+
+        Rational {
+            numer: t / d2.clone(),
+            denom: (self.denom / d1) * (other.denom / d2),
+        }
     }
 }
 
@@ -95,18 +310,26 @@ impl Sub for Rational {
     type Output = Rational;
 
     fn sub(self, other: Rational) -> Rational {
-        Rational::new(
-            self.numer * other.denom - self.denom * other.numer,
-            self.denom * other.denom,
-        )
+        self + -other
     }
 }
 
 impl Mul for Rational {
     type Output = Rational;
 
+    /// Cross-cancels before multiplying, so the product is already
+    /// in lowest terms.
     fn mul(self, other: Rational) -> Rational {
-        Rational::new(self.numer * other.numer, self.denom * other.denom)
+        if self.numer == integer!(0) || other.numer == integer!(0) {
+            return Rational::default();
+        }
+        let g1 = gcd_int(&self.numer, &other.denom);
+        let g2 = gcd_int(&other.numer, &self.denom);
+
+        Rational {
+            numer: (self.numer / g1.clone()) * (other.numer / g2.clone()),
+            denom: (self.denom / g2) * (other.denom / g1),
+        }
     }
 }
 
@@ -118,11 +341,24 @@ impl Div for Rational {
     }
 }
 
+/// Remainder of the division truncated toward zero, with the sign of `self`.
+impl Rem for Rational {
+    type Output = Rational;
+
+    fn rem(self, other: Rational) -> Rational {
+        let quot = (self.clone() / other.clone()).trunc();
+        self - other * Rational::new(quot, 1)
+    }
+}
+
 impl Neg for Rational {
     type Output = Rational;
 
     fn neg(self) -> Rational {
-        Rational::new(-self.numer, self.denom)
+        Rational {
+            numer: -self.numer,
+            denom: self.denom,
+        }
     }
 }
 
@@ -12,18 +12,143 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::{Float, Long, Rational};
+
+use std::cell::Cell;
+use std::cmp::{max, Ordering};
 use std::convert::From;
+use std::f64::consts::PI;
 use std::fmt::{self, Display, Formatter};
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::num::ParseFloatError;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+/// Real number, either a plain `f64` or, when the session asks for more
+/// digits, a multiprecision `Float`.
+#[derive(Debug, Clone)]
+pub struct Real(Value);
 
-#[derive(PartialOrd, Debug, Copy, Clone)]
-pub struct Real(f64); // TODO: --//--
+#[derive(Debug, Clone)]
+enum Value {
+    Double(f64),
+    Float(Float),
+}
 
-const EPS: Real = Real(1e-14);
+const EPS: f64 = 1e-14;
+
+/// How `==` of reals allows for rounding errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// `|a - b| <= eps`
+    Absolute(f64),
+    /// `|a - b| <= eps * max(|a|, |b|)`
+    Relative(f64),
+    /// At most this many representable numbers apart.
+    Ulps(u64),
+    /// Only identical values are equal.
+    Strict,
+}
+
+impl Tolerance {
+    fn doubles_equal(self, a: f64, b: f64) -> bool {
+        if a == b {
+            return true;
+        }
+        match self {
+            Tolerance::Absolute(eps) => (a - b).abs() <= eps,
+            Tolerance::Relative(eps) => (a - b).abs() <= eps * a.abs().max(b.abs()),
+            Tolerance::Ulps(ulps) => {
+                if a.is_nan() || b.is_nan() || a.is_sign_negative() != b.is_sign_negative() {
+                    return false;
+                }
+                let (x, y) = (a.to_bits() as i64, b.to_bits() as i64);
+                (x - y).unsigned_abs() <= ulps
+            }
+            Tolerance::Strict => false,
+        }
+    }
+
+    fn floats_equal(self, a: Float, b: Float) -> bool {
+        let digits = max(a.digits(), b.digits());
+        let diff = (a.clone() - b.clone()).abs();
+        match self {
+            Tolerance::Absolute(eps) => diff <= Float::from_f64(eps, digits),
+            Tolerance::Relative(eps) => diff <= Float::from_f64(eps, digits) * max(a.abs(), b.abs()),
+            Tolerance::Ulps(ulps) => {
+                let ulp = max(a.ulp(), b.ulp());
+                diff <= Float::new(Long::from(ulps), 0, digits) * ulp
+            }
+            Tolerance::Strict => diff.is_zero(),
+        }
+    }
+}
+
+impl Display for Tolerance {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Tolerance::Absolute(eps) => write!(f, "absolute {:e}", eps),
+            Tolerance::Relative(eps) => write!(f, "relative {:e}", eps),
+            Tolerance::Ulps(ulps) => write!(f, "ulp {}", ulps),
+            Tolerance::Strict => "strict".fmt(f),
+        }
+    }
+}
+
+/// Most significant digits a session may ask reals to carry.
+pub const MAX_PRECISION: usize = 10000;
+
+thread_local! {
+    static PRECISION: Cell<Option<usize>> = const { Cell::new(None) };
+    static TOLERANCE: Cell<Option<Tolerance>> = const { Cell::new(None) };
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
 
 impl Real {
     pub fn new<T: Into<f64>>(real: T) -> Real {
-        Real(real.into())
+        let real = real.into();
+        match Real::precision() {
+            Some(digits) if real.is_finite() && !(real == 0.0 && real.is_sign_negative()) => {
+                Real(Value::Float(Float::from_f64(real, digits)))
+            }
+            _ => Real(Value::Double(real)),
+        }
+    }
+
+    /// Number of significant decimal digits used for new reals,
+    /// `None` while reals are plain `f64`.
+    pub fn precision() -> Option<usize> {
+        PRECISION.with(|precision| precision.get())
+    }
+
+    pub fn set_precision(digits: Option<usize>) {
+        PRECISION.with(|precision| precision.set(digits.map(|digits| digits.clamp(1, MAX_PRECISION))))
+    }
+
+    /// Tolerance of `==` set for the session, `None` for the default
+    /// absolute `1e-14`, or `10^-(digits - 2)` in multiprecision.
+    pub fn tolerance() -> Option<Tolerance> {
+        TOLERANCE.with(|tolerance| tolerance.get())
+    }
+
+    pub fn set_tolerance(tolerance: Option<Tolerance>) {
+        TOLERANCE.with(|cell| cell.set(tolerance))
+    }
+
+    /// Whether the session refuses infinities and NaN instead of carrying
+    /// them through like IEEE arithmetic does.
+    pub fn strict() -> bool {
+        STRICT.with(|strict| strict.get())
+    }
+
+    pub fn set_strict(strict: bool) {
+        STRICT.with(|cell| cell.set(strict))
+    }
+
+    /// Exact value of the decimal a real is shown as, so that `0.1` is
+    /// `1/10` rather than its binary approximation. `None` for infinities
+    /// and NaN.
+    pub fn to_decimal(&self) -> Option<Rational> {
+        Rational::from_decimal(&self.to_string())
     }
 
     pub fn zero() -> Real {
@@ -31,23 +156,350 @@ impl Real {
     }
 
     pub fn value(&self) -> f64 {
-        self.0
+        match self.0 {
+            Value::Double(val) => val,
+            Value::Float(ref val) => val.to_f64(),
+        }
     }
 
     pub fn abs(&self) -> Real {
-        Real::new(self.0.abs())
+        match self.0 {
+            Value::Double(val) => Real(Value::Double(val.abs())),
+            Value::Float(ref val) => Real(Value::Float(val.abs())),
+        }
+    }
+
+    /// Exactly zero, unlike `== Real::zero()` which allows for rounding.
+    pub fn is_zero(&self) -> bool {
+        match self.0 {
+            Value::Double(val) => val == 0.0,
+            Value::Float(ref val) => val.is_zero(),
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self.0 {
+            Value::Double(val) => val < 0.0,
+            Value::Float(ref val) => val.is_negative(),
+        }
+    }
+
+    /// Whether the sign bit is set, so also for `-0.0`.
+    pub fn is_sign_negative(&self) -> bool {
+        match self.0 {
+            Value::Double(val) => val.is_sign_negative(),
+            Value::Float(ref val) => val.is_negative(),
+        }
+    }
+
+    /// Multiprecision values are finite: infinities and NaN are kept as
+    /// `f64`, both by `Real::new` and by operations whose result leaves
+    /// the finite numbers, and so is `-0.0`.
+    pub fn is_finite(&self) -> bool {
+        match self.0 {
+            Value::Double(val) => val.is_finite(),
+            Value::Float(_) => true,
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        match self.0 {
+            Value::Double(val) => val.is_nan(),
+            Value::Float(_) => false,
+        }
+    }
+
+    /// Integer part, rounded toward zero.
+    pub fn trunc(&self) -> Real {
+        match self.0 {
+            Value::Double(val) => Real(Value::Double(val.trunc())),
+            Value::Float(ref val) => Real(Value::Float(val.trunc())),
+        }
+    }
+
+    /// `sqrt(self^2 + other^2)` without intermediate overflow.
+    pub fn hypot(&self, other: &Real) -> Real {
+        match floats(self, other) {
+            Some((a, b)) => Real(Value::Float((a.clone() * a + b.clone() * b).sqrt())),
+            None => Real(Value::Double(self.value().hypot(other.value()))),
+        }
+    }
+
+    /// `self` raised to a real power, for a `self` that is not negative.
+    pub fn powf(&self, exp: &Real) -> Real {
+        match floats(self, exp) {
+            Some((base, exp)) if !base.is_negative() && !base.is_zero() => {
+                let power = exp.to_f64() * base.to_f64().ln();
+                if power.abs() <= MAX_EXP_ARGUMENT {
+                    return Real(Value::Float(base.pow(&exp)));
+                }
+                Real(Value::Double(power.exp()))
+            }
+            _ => Real(Value::Double(self.value().powf(exp.value()))),
+        }
+    }
+
+    /// `pi` in the session precision.
+    pub fn pi() -> Real {
+        match Real::precision() {
+            Some(digits) => Real(Value::Float(Float::pi(digits))),
+            None => Real(Value::Double(PI)),
+        }
+    }
+
+    /// `double` of a plain real, `float` of a multiprecision one inside
+    /// `domain`. Outside of it the value is taken as `f64`, which gives
+    /// the infinities and NaN there.
+    fn map(&self, double: fn(f64) -> f64, float: fn(&Float) -> Float, domain: fn(&Float) -> bool) -> Real {
+        match self.0 {
+            Value::Float(ref val) if domain(val) => Real(Value::Float(float(val))),
+            _ => Real(Value::Double(double(self.value()))),
+        }
+    }
+
+    pub fn exp(&self) -> Real {
+        self.map(f64::exp, Float::exp, exp_domain)
+    }
+
+    pub fn ln(&self) -> Real {
+        self.map(f64::ln, Float::ln, positive)
+    }
+
+    pub fn log10(&self) -> Real {
+        self.map(f64::log10, Float::log10, positive)
+    }
+
+    pub fn cbrt(&self) -> Real {
+        self.map(f64::cbrt, Float::cbrt, |_| true)
+    }
+
+    pub fn sin(&self) -> Real {
+        self.map(f64::sin, |x| x.sin_cos().0, in_double_range)
+    }
+
+    pub fn cos(&self) -> Real {
+        self.map(f64::cos, |x| x.sin_cos().1, in_double_range)
+    }
+
+    pub fn tan(&self) -> Real {
+        let tan = |x: &Float| {
+            let (sin, cos) = x.sin_cos();
+            sin / cos
+        };
+        self.map(f64::tan, tan, in_double_range)
+    }
+
+    pub fn asin(&self) -> Real {
+        self.map(f64::asin, Float::asin, at_most_one)
+    }
+
+    pub fn acos(&self) -> Real {
+        self.map(f64::acos, Float::acos, at_most_one)
+    }
+
+    pub fn atan(&self) -> Real {
+        self.map(f64::atan, Float::atan, |_| true)
+    }
+
+    pub fn sinh(&self) -> Real {
+        self.map(f64::sinh, |x| x.sinh_cosh().0, exp_domain)
+    }
+
+    pub fn cosh(&self) -> Real {
+        self.map(f64::cosh, |x| x.sinh_cosh().1, exp_domain)
+    }
+
+    /// `sinh / cosh`, which is `+-1` to any precision far from zero.
+    pub fn tanh(&self) -> Real {
+        let tanh = |x: &Float| {
+            if !exp_domain(x) {
+                let one = Float::integer(1, x.digits());
+                return if x.is_negative() { -one } else { one };
+            }
+            let (sinh, cosh) = x.sinh_cosh();
+            sinh / cosh
+        };
+        self.map(f64::tanh, tanh, |_| true)
+    }
+
+    pub fn asinh(&self) -> Real {
+        self.map(f64::asinh, Float::asinh, |_| true)
+    }
+
+    pub fn acosh(&self) -> Real {
+        self.map(f64::acosh, Float::acosh, |x| *x >= Float::integer(1, x.digits()))
+    }
+
+    pub fn atanh(&self) -> Real {
+        self.map(f64::atanh, Float::atanh, |x| x.abs() < Float::integer(1, x.digits()))
+    }
+
+    /// Angle of the point `(other, self)`, in `(-pi, pi]`, or `-pi` for
+    /// `-0.0` on the negative axis.
+    pub fn atan2(&self, other: &Real) -> Real {
+        match floats(self, other) {
+            Some((y, x)) if self.is_sign_negative() && y.is_zero() => Real(Value::Float(-y.atan2(&x))),
+            Some((y, x)) => Real(Value::Float(y.atan2(&x))),
+            None => Real(Value::Double(self.value().atan2(other.value()))),
+        }
+    }
+
+    /// Largest integer not above `self`.
+    pub fn floor(&self) -> Real {
+        match self.0 {
+            Value::Double(val) => Real(Value::Double(val.floor())),
+            Value::Float(ref val) => {
+                let trunc = val.trunc();
+                if *val < trunc {
+                    Real(Value::Float(trunc - Float::from_f64(1.0, val.digits())))
+                } else {
+                    Real(Value::Float(trunc))
+                }
+            }
+        }
+    }
+
+    /// Nearest integer, halves are rounded to the even one.
+    pub fn round(&self) -> Real {
+        match self.0 {
+            Value::Double(val) => Real(Value::Double(val.round_ties_even())),
+            Value::Float(ref val) => {
+                let floor = match self.floor().0 {
+                    Value::Float(floor) => floor,
+                    Value::Double(_) => unreachable!(),
+                };
+                let (one, half) = (Float::from_f64(1.0, val.digits()), Float::from_f64(0.5, val.digits()));
+                let up = match (val.clone() - floor.clone()).cmp(&half) {
+                    Ordering::Greater => true,
+                    Ordering::Less => false,
+                    Ordering::Equal => (floor.clone() * half.clone()).trunc() != floor.clone() * half,
+                };
+                Real(Value::Float(if up { floor + one } else { floor }))
+            }
+        }
+    }
+
+    /// Ordering of the exact values, without any tolerance.
+    pub fn cmp_exact(&self, other: &Real) -> Option<Ordering> {
+        match floats(self, other) {
+            Some((a, b)) => Some(a.cmp(&b)),
+            None => self.value().partial_cmp(&other.value()),
+        }
+    }
+
+    /// Square root of the absolute value, in the precision of `self`.
+    pub fn sqrt_abs(&self) -> Real {
+        match self.0 {
+            Value::Double(val) => Real(Value::Double(val.abs().sqrt())),
+            Value::Float(ref val) => Real(Value::Float(val.sqrt())),
+        }
+    }
+}
+
+/// Largest `|x|` whose `e^x` is computed in multiprecision, beyond it the
+/// result is out of the range of literals and taken from `f64`.
+const MAX_EXP_ARGUMENT: f64 = 230_000.0;
+
+fn exp_domain(x: &Float) -> bool {
+    x.to_f64().abs() <= MAX_EXP_ARGUMENT
+}
+
+fn positive(x: &Float) -> bool {
+    !x.is_zero() && !x.is_negative()
+}
+
+/// Arguments of the trigonometric functions, which are reduced by a
+/// multiple of `pi/2` counted in `f64`.
+fn in_double_range(x: &Float) -> bool {
+    x.to_f64().is_finite()
+}
+
+fn at_most_one(x: &Float) -> bool {
+    x.abs() <= Float::integer(1, x.digits())
+}
+
+/// Both operands as `Float`s when at least one of them is multiprecision
+/// and the other one is finite.
+fn floats(a: &Real, b: &Real) -> Option<(Float, Float)> {
+    match (&a.0, &b.0) {
+        (Value::Float(x), Value::Float(y)) => Some((x.clone(), y.clone())),
+        (Value::Float(x), Value::Double(y)) if y.is_finite() => {
+            Some((x.clone(), Float::from_f64(*y, x.digits())))
+        }
+        (Value::Double(x), Value::Float(y)) if x.is_finite() => {
+            Some((Float::from_f64(*x, y.digits()), y.clone()))
+        }
+        _ => None,
     }
 }
 
+/// A multiprecision result, or `-0.0` for a zero that is negative in
+/// IEEE arithmetic, as `Float` has just one zero.
+fn signed(val: Float, negative: bool) -> Real {
+    if negative && val.is_zero() {
+        Real(Value::Double(-0.0))
+    } else {
+        Real(Value::Float(val))
+    }
+}
+
+/// Infinities are shown as `inf` and `-inf`, NaN as `nan`, like the
+/// literals.
 impl Display for Real {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.0.fmt(f)
+        match self.0 {
+            Value::Double(val) if val.is_nan() => "nan".fmt(f),
+            Value::Double(val) => val.fmt(f),
+            Value::Float(ref val) => val.fmt(f),
+        }
     }
 }
 
+impl FromStr for Real {
+    type Err = ParseFloatError;
+
+    /// Multiprecision literals are read from the decimal text itself,
+    /// so `0.1` does not inherit the binary error of `0.1f64`.
+    fn from_str(s: &str) -> Result<Real, ParseFloatError> {
+        let val = <f64>::from_str(s)?;
+        match Real::precision() {
+            Some(digits) => Ok(Float::parse(s, digits)
+                .map(|val| Real(Value::Float(val)))
+                .unwrap_or_else(|| Real::new(val))),
+            None => Ok(Real::new(val)),
+        }
+    }
+}
+
+/// Reals are equal when they differ by less than `1e-14`, or by less
+/// than `10^-(digits - 2)` in multiprecision, so that rounding errors of
+/// a few operations don't matter. The session may choose another
+/// `Tolerance`. Infinities only equal themselves and NaN equals nothing,
+/// as in IEEE arithmetic.
 impl PartialEq for Real {
     fn eq(&self, other: &Real) -> bool {
-        (*self - *other).abs() < EPS
+        if !self.is_finite() || !other.is_finite() {
+            return self.value() == other.value();
+        }
+        match (floats(self, other), Real::tolerance()) {
+            (Some((a, b)), Some(tolerance)) => tolerance.floats_equal(a, b),
+            (Some((a, b)), None) => {
+                let digits = max(a.digits(), b.digits());
+                (a - b).abs() < Float::epsilon(digits)
+            }
+            (None, Some(tolerance)) => tolerance.doubles_equal(self.value(), other.value()),
+            (None, None) => (self.value() - other.value()).abs() < EPS,
+        }
+    }
+}
+
+/// Consistent with equality: reals within the tolerance are `Equal`.
+impl PartialOrd for Real {
+    fn partial_cmp(&self, other: &Real) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        self.cmp_exact(other)
     }
 }
 
@@ -57,20 +509,89 @@ impl<T: Into<f64>> From<T> for Real {
     }
 }
 
+impl From<Long> for Real {
+    fn from(some: Long) -> Real {
+        match Real::precision() {
+            Some(digits) => Real(Value::Float(Float::new(some, 0, digits))),
+            None => Real::new(some.to_f64()),
+        }
+    }
+}
+
+impl From<Float> for Real {
+    fn from(some: Float) -> Real {
+        Real(Value::Float(some))
+    }
+}
+
 impl Neg for Real {
     type Output = Real;
 
     fn neg(self) -> Real {
-        Real::new(-self.0)
+        match self.0 {
+            Value::Double(val) => Real(Value::Double(-val)),
+            Value::Float(val) => signed(-val, true),
+        }
     }
 }
 
-impl_std_ops_for_tuple_struct! { Real: @all }
+/// The sign of a zero result follows IEEE arithmetic, from the signs of
+/// the operands.
+macro_rules! impl_real_ops {
+    ($($trait_:ident($method:ident, $op:tt, $negative:expr)),*) => {
+        $(
+            impl $trait_ for Real {
+                type Output = Real;
+
+                fn $method(self, other: Real) -> Real {
+                    let negative: fn(bool, bool) -> bool = $negative;
+                    match floats(&self, &other) {
+                        Some((a, b)) => signed(a $op b, negative(self.is_sign_negative(), other.is_sign_negative())),
+                        None => Real(Value::Double(self.value() $op other.value())),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_real_ops! {
+    Add(add, +, |a, b| a && b),
+    Sub(sub, -, |a, b| a && !b),
+    Mul(mul, *, |a, b| a != b)
+}
+
+impl Div for Real {
+    type Output = Real;
+
+    fn div(self, other: Real) -> Real {
+        let negative = self.is_sign_negative() != other.is_sign_negative();
+        match floats(&self, &other) {
+            Some((a, b)) if !b.is_zero() => signed(a / b, negative),
+            _ => Real(Value::Double(self.value() / other.value())),
+        }
+    }
+}
+
+/// Remainder of the division truncated toward zero, like `f64`'s `%`.
+impl Rem for Real {
+    type Output = Real;
+
+    fn rem(self, other: Real) -> Real {
+        match floats(&self, &other) {
+            Some((a, b)) if !b.is_zero() => {
+                let quot = (a.clone() / b.clone()).trunc();
+                signed(a - b * quot, self.is_sign_negative())
+            }
+            _ => Real(Value::Double(self.value() % other.value())),
+        }
+    }
+}
 
 #[macro_export]
 macro_rules! real {
     ($a:expr) => {
-        $crate::numbers::Real::new($a as f64)
+        $crate::numbers::Real::from($a)
     };
 }
 
@@ -12,6 +12,70 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[test]
+fn test_long() {
+    use super::Long;
+    use std::str::FromStr;
+
+    fn long(s: &str) -> Long {
+        Long::from_str(s).unwrap()
+    }
+
+    fn check_small() {
+        let values: Vec<i128> = vec![0, 1, -1, 7, -13, 4294967295, 4294967296, -9223372036854775809];
+        for a in values.iter() {
+            for b in values.iter() {
+                let (x, y) = (Long::from(*a), Long::from(*b));
+
+                assert_eq!(x.clone() + y.clone(), Long::from(a + b));
+                assert_eq!(x.clone() - y.clone(), Long::from(a - b));
+                assert_eq!(x.clone() * y.clone(), Long::from(a * b));
+                assert_eq!(x < y, a < b);
+                if *b != 0 {
+                    assert_eq!(x.clone() / y.clone(), Long::from(a / b));
+                    assert_eq!(x.clone() % y.clone(), Long::from(a % b));
+                }
+            }
+        }
+    }
+
+    fn check_division() {
+        let a = long("123456789012345678901234567890123456789");
+        let b = long("98765432109876543210987");
+        let r = long("12345678901234567");
+
+        let (q, m) = (a.clone() * b.clone() + r.clone()).div_rem(&b);
+        assert_eq!(q, a);
+        assert_eq!(m, r);
+
+        let n = -(a.clone() * a.clone()) + r.clone();
+        let (q, m) = n.div_rem(&-b.clone());
+        assert_eq!(q.clone() * -b + m.clone(), n);
+        assert!(m.is_negative());
+    }
+
+    fn check_format() {
+        let text = "-340282366920938463463374607431768211456000000001";
+
+        assert_eq!(format!("{}", long(text)), text);
+        assert_eq!(format!("{}", long("+000")), "0");
+        assert_eq!(format!("{:>5}", Long::from(42)), "   42");
+        assert!(Long::from_str("12a").is_err());
+        assert!(Long::from_str("-").is_err());
+    }
+
+    fn check_f64() {
+        assert_eq!(Long::from(-5).to_f64(), -5.0);
+        assert_eq!(long("1000000000000000000000000").to_f64(), 1e24);
+        assert_eq!(long("9007199254740993").to_f64(), 9007199254740992.0);
+    }
+
+    check_small();
+    check_division();
+    check_format();
+    check_f64();
+}
+
 #[test]
 fn test_naturals() {
     use super::Natural;
@@ -28,13 +92,13 @@ fn test_naturals() {
         let b = natural!(2);
         let i = integer!(2);
 
-        let c = a + b;
-        let d = a - b;
-        let e = a * b;
-        let f = a / b;
-        let g = a % b;
-        let h = a % i;
-        let m = -a;
+        let c = a.clone() + b.clone();
+        let d = a.clone() - b.clone();
+        let e = a.clone() * b.clone();
+        let f = a.clone() / b.clone();
+        let g = a.clone() % b.clone();
+        let h = a.clone() % i.clone();
+        let m = -a.clone();
 
         assert_eq!(c, natural!(5));
         assert_eq!(d, natural!(1));
@@ -49,12 +113,12 @@ fn test_naturals() {
         let a = natural!(3);
         let b = natural!(2);
 
-        assert_eq!(a < b, false);
-        assert_eq!(a > b, true);
-        assert_eq!(a >= b, true);
-        assert_eq!(a <= b, false);
-        assert_eq!(a == b, false);
-        assert_eq!(a != b, true);
+        assert!(b < a);
+        assert!(a > b);
+        assert!(a >= b);
+        assert!(b <= a);
+        assert_ne!(a, b);
+        assert!(a != b);
     }
 
     fn check_format() {
@@ -63,10 +127,18 @@ fn test_naturals() {
         assert_eq!(format!("{}", a), "13");
     }
 
+    fn check_big() {
+        let fact = (1..41).fold(natural!(1), |acc, n| acc * natural!(n));
+
+        assert_eq!(format!("{}", fact), "815915283247897734345611269596115894272000000000");
+        assert_eq!(fact / natural!(40), (1..40).fold(natural!(1), |acc, n| acc * natural!(n)));
+    }
+
     check_macro();
     check_ops();
     check_cmp();
     check_format();
+    check_big();
 }
 
 #[test]
@@ -85,13 +157,13 @@ fn test_integers() {
         let b = integer!(4);
         let n = natural!(2);
 
-        let c = a + b;
-        let d = a - b;
-        let e = a * b;
-        let f = a / b;
-        let g = a % b;
-        let h = a % n;
-        let m = -a;
+        let c = a.clone() + b.clone();
+        let d = a.clone() - b.clone();
+        let e = a.clone() * b.clone();
+        let f = a.clone() / b.clone();
+        let g = a.clone() % b.clone();
+        let h = a.clone() % n.clone();
+        let m = -a.clone();
 
         assert_eq!(c, integer!(7));
         assert_eq!(d, integer!(-1));
@@ -106,12 +178,12 @@ fn test_integers() {
         let a = integer!(3);
         let b = integer!(4);
 
-        assert_eq!(a > b, false);
-        assert_eq!(a < b, true);
-        assert_eq!(a <= b, true);
-        assert_eq!(a >= b, false);
-        assert_eq!(a == b, false);
-        assert_eq!(a != b, true);
+        assert!(b > a);
+        assert!(a < b);
+        assert!(a <= b);
+        assert!(b >= a);
+        assert_ne!(a, b);
+        assert!(a != b);
     }
 
     fn check_format() {
@@ -120,10 +192,19 @@ fn test_integers() {
         assert_eq!(format!("{}", a), "-13");
     }
 
+    fn check_big() {
+        let a = Integer::new(i128::MIN);
+        let b = a.clone() * a.clone() * integer!(-2);
+
+        assert_eq!(format!("{}", b), "-57896044618658097711785492504343953926634992332820282019728792003956564819968");
+        assert_eq!(b / a.clone(), a * integer!(-2));
+    }
+
     check_macro();
     check_ops();
     check_cmp();
     check_format();
+    check_big();
 }
 
 #[test]
@@ -145,11 +226,11 @@ fn test_rationals() {
         let a = rational!(3 / 4);
         let b = rational!(4 / 12);
 
-        let c = a + b;
-        let d = a - b;
-        let e = a * b;
-        let f = a / b;
-        let m = -a;
+        let c = a.clone() + b.clone();
+        let d = a.clone() - b.clone();
+        let e = a.clone() * b.clone();
+        let f = a.clone() / b.clone();
+        let m = -a.clone();
 
         assert_eq!(c, rational!(13 / 12));
         assert_eq!(d, rational!(5 / 12));
@@ -168,18 +249,42 @@ fn test_rationals() {
         let a = rational!(3 / 4);
         let b = rational!(1 / 3);
 
-        assert_eq!(a < b, false);
-        assert_eq!(a > b, true);
-        assert_eq!(a >= b, true);
-        assert_eq!(a <= b, false);
-        assert_eq!(a == b, false);
-        assert_eq!(a != b, true);
+        assert!(b < a);
+        assert!(a > b);
+        assert!(a >= b);
+        assert!(b <= a);
+        assert_ne!(a, b);
+        assert!(a != b);
     }
 
     fn check_format() {
         let a = rational!(2 / 3);
 
         assert_eq!(format!("{}", a), "2 / 3");
+        assert_eq!(format!("{}", rational!(-6 / 2)), "-3");
+    }
+
+    fn check_harmonic() {
+        let sum = (1..201).fold(rational!(0), |acc, k| acc + rational!(1 / k));
+
+        assert_eq!(
+            format!("{}", sum),
+            "73430450139366304745412892037069099001170161275640475032430988199840965762047744114895233 / \
+             12492355141960232023683917288697829904903495658709527193661000811749408076321384817296000"
+        );
+        assert_eq!(sum.value(), 5.878030948121444);
+
+        let back = (1..201).fold(sum, |acc, k| acc - rational!(1 / k));
+        assert_eq!(back, rational!(0));
+    }
+
+    fn check_big_cmp() {
+        let a = Rational::new(i128::MAX, i128::MAX - 1);
+        let b = Rational::new(i128::MAX - 1, i128::MAX - 2);
+
+        assert!(a < b);
+        assert_eq!(a.clone() * a.inv(), rational!(1));
+        assert_eq!((b.clone() / b).value(), 1.0);
     }
 
     check_macro();
@@ -188,6 +293,8 @@ fn test_rationals() {
     check_inv();
     check_cmp();
     check_format();
+    check_harmonic();
+    check_big_cmp();
 }
 
 #[test]
@@ -206,11 +313,11 @@ fn test_real() {
         let a = real!(2.5);
         let b = real!(1.5);
 
-        let c = a + b;
-        let d = a - b;
-        let e = a * b;
-        let f = a / b;
-        let m = -a;
+        let c = a.clone() + b.clone();
+        let d = a.clone() - b.clone();
+        let e = a.clone() * b.clone();
+        let f = a.clone() / b.clone();
+        let m = -a.clone();
 
         assert_eq!(c, real!(4));
         assert_eq!(d, real!(1));
@@ -245,10 +352,38 @@ fn test_real() {
         assert_eq!(format!("{}", a), "1.33");
     }
 
+    fn check_precision() {
+        use super::Sqrt;
+        use std::str::FromStr;
+
+        Real::set_precision(Some(60));
+
+        let two = Real::from_str("2").unwrap();
+        let root = <Real as Sqrt<Real>>::sqrt(&two);
+        assert_eq!(
+            format!("{}", root),
+            "1.41421356237309504880168872420969807856967187537694807317668"
+        );
+        assert_eq!(root.clone() * root, two);
+
+        let third: Real = rational!(1 / 3).into();
+        assert_eq!(format!("{}", third.clone() * real!(3)), "1");
+        assert!(third != real!(1.0 / 3.0));
+
+        let big: Real = integer!(10).into();
+        let big = big / Real::from_str("0.0000001").unwrap();
+        assert_eq!(format!("{}", big), "100000000");
+        assert_eq!(format!("{}", -real!(1) / big), "-1e-8");
+
+        Real::set_precision(None);
+        assert_eq!(format!("{}", real!(1) / real!(3)), "0.3333333333333333");
+    }
+
     check_macro();
     check_ops();
     check_sqrt();
     check_format();
+    check_precision();
 }
 
 #[test]
@@ -275,11 +410,11 @@ fn test_complex() {
         let a = complex!(4 + 3.i);
         let b = complex!(4 - 3.i);
 
-        let d = a + b;
-        let e = a - b;
-        let f = a * b;
-        let g = a / b;
-        let m = -a;
+        let d = a.clone() + b.clone();
+        let e = a.clone() - b.clone();
+        let f = a.clone() * b.clone();
+        let g = a.clone() / b.clone();
+        let m = -a.clone();
 
         assert_eq!(d, complex!(8));
         assert_eq!(e, complex!(6.i));
@@ -295,7 +430,7 @@ fn test_complex() {
 
     fn check_i2() {
         let i = complex!(1.i);
-        assert_eq!(i * i, complex!(-1));
+        assert_eq!(i.clone() * i, complex!(-1));
     }
 
     fn check_inv() {
@@ -317,6 +452,364 @@ fn test_complex() {
     check_format();
 }
 
+#[test]
+fn test_gaussian() {
+    use super::{Complex, Gaussian, Number};
+
+    fn check_macro() {
+        assert_eq!(complex!(exact 2 + 3.i), Gaussian::new(rational!(2), rational!(3)));
+        assert_eq!(complex!(exact 1/3 - 3/4.i), Gaussian::new(rational!(1 / 3), rational!(-3 / 4)));
+        assert_eq!(complex!(exact - 2 + 1/2.i), Gaussian::new(rational!(-2), rational!(1 / 2)));
+        assert_eq!(complex!(exact 5.i), Gaussian::new(rational!(0), rational!(5)));
+        assert_eq!(complex!(exact - 1/2), Gaussian::new(rational!(-1 / 2), rational!(0)));
+    }
+
+    fn check_ops() {
+        let a = complex!(exact 4 + 3.i);
+        let b = complex!(exact 4 - 3.i);
+
+        assert_eq!(a.clone() + b.clone(), complex!(exact 8));
+        assert_eq!(a.clone() - b.clone(), complex!(exact 6.i));
+        assert_eq!(a.clone() * b.clone(), complex!(exact 25));
+        assert_eq!(a.clone() / b.clone(), complex!(exact 7/25 + 24/25.i));
+        assert_eq!(complex!(exact 3 + 4.i).inv(), complex!(exact 3/25 - 4/25.i));
+        assert_eq!(a.norm_sqr(), rational!(25));
+        assert_eq!(-a, complex!(exact - 4 - 3.i));
+    }
+
+    fn check_numbers() {
+        let a = Number::Gaussian(complex!(exact 1 + 1.i));
+        let third = Number::Rational(rational!(1 / 3));
+
+        assert_eq!(
+            format!("{}", third.clone().checked_add(a.clone()).unwrap()),
+            "4 / 3 + 1i"
+        );
+        assert_eq!(
+            format!("{}", a.clone().checked_pow(Number::Integer(integer!(-2))).unwrap()),
+            "0 - (1 / 2)i"
+        );
+        let power = a.clone().checked_pow(Number::Natural(natural!(4))).unwrap();
+        assert_eq!(power.normalize(), Number::Integer(integer!(-4)));
+        match a.clone().checked_add(Number::Real(real!(0.5))).unwrap() {
+            Number::Complex(com) => assert_eq!(com, Complex::new(1.5, 1)),
+            number => panic!("{:?} is not a Complex", number),
+        }
+        assert!(a.checked_cmp(&third).is_err());
+    }
+
+    check_macro();
+    check_ops();
+    check_numbers();
+}
+
+#[test]
+fn test_decimal() {
+    use super::{Decimal, Long, Number, Rational, Rounding};
+
+    fn check_display() {
+        assert_eq!("1.50", decimal!(150, 2).to_string());
+        assert_eq!("-0.05", decimal!(-5, 2).to_string());
+        assert_eq!("12", decimal!(Long::from(12)).to_string());
+        assert_eq!(Decimal::parse("1.5e-3"), Some(decimal!(15, 4)));
+        assert_eq!(Decimal::parse("2.5e3"), Some(decimal!(2500, 0)));
+        assert_eq!(decimal!(150, 2), decimal!(15, 1));
+    }
+
+    fn check_ops() {
+        assert_eq!("0.3", (decimal!(1, 1) + decimal!(2, 1)).to_string());
+        assert_eq!("2.50", (decimal!(150, 2) + natural!(1)).to_string());
+        assert_eq!("-0.50", (natural!(1) - decimal!(150, 2)).to_string());
+        assert_eq!("6.25", (decimal!(25, 1) * decimal!(25, 1)).to_string());
+        assert_eq!("2.50", (decimal!(1000, 2) / integer!(4)).to_string());
+        assert_eq!("0.33333333333333333333", (decimal!(1, 0) / decimal!(3, 0)).to_string());
+        assert_eq!("1.5", (decimal!(75, 1) % decimal!(2, 0)).to_string());
+        assert_eq!(rational!(13 / 30), decimal!(1, 1) + rational!(1 / 3));
+        assert_eq!("2.25", decimal!(15, 1).powi(2).to_string());
+    }
+
+    fn check_rounding() {
+        let half = Rational::new(5, 2);
+        let tie = Rational::new(-7, 2);
+        assert_eq!("2", Decimal::from_rational(&half, 0, Rounding::HalfEven).to_string());
+        assert_eq!("3", Decimal::from_rational(&half, 0, Rounding::HalfUp).to_string());
+        assert_eq!("2", Decimal::from_rational(&half, 0, Rounding::Down).to_string());
+        assert_eq!("-4", Decimal::from_rational(&tie, 0, Rounding::HalfEven).to_string());
+        assert_eq!("-4", Decimal::from_rational(&tie, 0, Rounding::HalfUp).to_string());
+        assert_eq!("-3", Decimal::from_rational(&tie, 0, Rounding::Down).to_string());
+
+        Decimal::set_scale(2);
+        Decimal::set_rounding(Rounding::HalfUp);
+        assert_eq!("1.01", (decimal!(1005, 3) * natural!(1)).to_string());
+        assert_eq!("0.67", (decimal!(2, 0) / decimal!(3, 0)).to_string());
+        Decimal::set_rounding(Rounding::Down);
+        assert_eq!("-0.66", (decimal!(-2, 0) / decimal!(3, 0)).to_string());
+        Decimal::set_rounding(Rounding::HalfEven);
+        assert_eq!("1.00", (decimal!(1005, 3) * natural!(1)).to_string());
+        Decimal::set_scale(20);
+    }
+
+    fn check_numbers() {
+        let a = Number::fixed_point("0.1".to_owned()).unwrap();
+        let b = Number::fixed_point("0.2".to_owned()).unwrap();
+        let sum = a.clone().checked_add(b).unwrap();
+        assert_eq!("Decimal", sum.kind());
+        assert_eq!(Number::Rational(rational!(3 / 10)), sum);
+        assert_eq!("0.3", sum.normalize().to_string());
+        assert_eq!("Rational", a.clone().checked_add(Number::Rational(rational!(1 / 3))).unwrap().kind());
+        assert_eq!("Real", a.clone().checked_add(Number::Real(real!(0.5))).unwrap().kind());
+        assert_eq!("0.0001", a.clone().checked_pow(Number::Natural(natural!(4))).unwrap().to_string());
+        assert_eq!(
+            "0.33",
+            Number::Rational(rational!(1 / 3)).quantize(Number::Natural(natural!(2))).unwrap().to_string()
+        );
+        assert!(Number::Complex(complex!(1.i)).quantize(Number::Natural(natural!(2))).is_err());
+    }
+
+    check_display();
+    check_ops();
+    check_rounding();
+    check_numbers();
+}
+
+#[test]
+fn test_algebraic() {
+    use super::{Algebraic, Number, Rational};
+    use std::cmp::Ordering;
+
+    fn sqrt(n: i32) -> Algebraic {
+        Algebraic::from(Rational::new(n, 1)).root(2).unwrap()
+    }
+
+    fn check_simplification() {
+        assert_eq!("2*sqrt(2)", sqrt(8).to_string());
+        assert_eq!(sqrt(8), sqrt(2) * Algebraic::from(rational!(2)));
+        assert_eq!("sqrt(6)", (sqrt(2) * sqrt(3)).to_string());
+        assert_eq!(Some(rational!(2)), (sqrt(2) * sqrt(2)).to_rational());
+        assert_eq!("sqrt(3) / 2", Algebraic::from(Rational::new(3, 4)).root(2).unwrap().to_string());
+        assert_eq!("-2*cbrt(2)", Algebraic::from(rational!(-16)).root(3).unwrap().to_string());
+        assert_eq!("root(2, 4)", sqrt(2).root(2).unwrap().to_string());
+        assert_eq!("1 + sqrt(2)*cbrt(5)", (Algebraic::from(rational!(1)) + sqrt(2) * Algebraic::from(rational!(5)).root(3).unwrap()).to_string());
+        assert_eq!(None, Algebraic::from(rational!(-2)).root(2));
+        assert_eq!(None, (sqrt(2) + sqrt(3)).root(2));
+    }
+
+    fn check_field() {
+        let one = Algebraic::from(rational!(1));
+        assert_eq!("-1 + sqrt(2)", (one.clone() / (one.clone() + sqrt(2))).to_string());
+        let sum = sqrt(2) + sqrt(3);
+        assert_eq!(one, sum.clone() * sum.inv());
+        assert_eq!(4, sum.degree());
+        let polynomial = vec![integer!(1), integer!(0), integer!(-10), integer!(0), integer!(1)];
+        assert_eq!(polynomial, sum.minimal_polynomial());
+        assert_eq!(vec![integer!(2), integer!(-3)], Algebraic::from(Rational::new(3, 2)).minimal_polynomial());
+    }
+
+    fn check_order() {
+        assert_eq!((Rational::new(1414, 1000), Rational::new(1415, 1000)), sqrt(2).bounds(3));
+        assert_eq!(Ordering::Less, (sqrt(2) - Algebraic::from(Rational::new(3, 2))).signum());
+        assert_eq!(Ordering::Greater, (sqrt(3) - sqrt(2)).signum());
+        assert_eq!(integer!(1), sqrt(2).floor());
+        assert_eq!(integer!(-1), (-sqrt(2)).trunc());
+        assert_eq!(integer!(-2), (-sqrt(2)).floor());
+        let terms = vec![integer!(1), integer!(2), integer!(2), integer!(2)];
+        assert_eq!(terms, sqrt(2).continued_fraction(4));
+    }
+
+    fn check_numbers() {
+        let two = Number::Natural(natural!(2));
+        let root = two.clone().sqrt().unwrap();
+        assert_eq!("Algebraic", root.kind());
+        assert_eq!(two, root.clone().checked_mul(root.clone()).unwrap());
+        assert_eq!(root, two.clone().checked_pow(Number::Rational(Rational::new(1, 2))).unwrap());
+        assert_eq!("Real", root.clone().checked_add(Number::Real(real!(0.5))).unwrap().kind());
+        assert!(root < Number::Rational(Rational::new(3, 2)));
+        assert_eq!("Natural", root.clone().checked_mul(root).unwrap().normalize().kind());
+    }
+
+    check_simplification();
+    check_field();
+    check_order();
+    check_numbers();
+}
+
+#[test]
+fn test_interval() {
+    use super::{ArithmeticError, Certainty, Interval, Long, Number};
+    use std::cmp::Ordering;
+
+    fn check_macro() {
+        assert_eq!(interval!(1, 2), Interval::new(1.0, 2.0));
+        assert_eq!(interval!(0.5), Interval::new(0.5, 0.5));
+        assert_eq!(interval!(rational!(1 / 2)), Interval::new(0.5, 0.5));
+
+        let third = interval!(rational!(1 / 3));
+        assert_eq!(third.hi(), third.lo().next_up());
+        assert!(third.lo() < 1.0 / 3.0 || third.hi() > 1.0 / 3.0);
+    }
+
+    fn check_ops() {
+        let a = interval!(1, 2);
+        let b = interval!(-3, 4);
+
+        assert_eq!(a + b, interval!(-2, 6));
+        assert_eq!(a - b, interval!(-3, 5));
+        assert_eq!(a * b, interval!(-6, 8));
+        assert_eq!(b / a, interval!(-3, 4));
+        assert_eq!(-a, interval!(-2, -1));
+
+        let tenth = interval!(0.1) + interval!(0.2);
+        assert_eq!(tenth.lo(), 0.30000000000000004f64.next_down());
+        assert_eq!(tenth.hi(), 0.30000000000000004);
+        assert_eq!(a + natural!(1), interval!(2, 3));
+        assert_eq!(rational!(1 / 2) * a, interval!(0.5, 1));
+    }
+
+    fn check_functions() {
+        assert_eq!(interval!(-2, 3).powi(&Long::from(2)), Ok(interval!(0, 9)));
+        assert_eq!(interval!(-2, 3).powi(&Long::from(3)), Ok(interval!(-8, 27)));
+        assert_eq!(interval!(4, 9).sqrt(), Ok(interval!(2, 3)));
+        assert_eq!(interval!(-1, 1).cos().map(|cos| cos.hi()), Ok(1.0));
+        assert_eq!(interval!(0, 4).sin().map(|sin| sin.hi()), Ok(1.0));
+        assert!(interval!(-1, 4).ln().is_err());
+        assert!(interval!(1, 2).tan().is_err());
+        assert_eq!(interval!(-3, 2).abs(), interval!(0, 3));
+    }
+
+    fn check_relation() {
+        let less = |o| o == Ordering::Less;
+        assert_eq!(interval!(1, 2).relation(&interval!(3, 4), less), Certainty::Certainly);
+        assert_eq!(interval!(1, 3).relation(&interval!(2, 2), less), Certainty::Possibly);
+        assert_eq!(interval!(3, 4).relation(&interval!(1, 2), less), Certainty::Never);
+
+        let a = Number::Interval(interval!(1, 3));
+        assert_eq!(a.relation(&Number::Natural(natural!(5)), less), Ok(Certainty::Certainly));
+        assert!(a.checked_cmp(&Number::Natural(natural!(2))).is_err());
+        assert_eq!(
+            a.clone().checked_div(Number::Interval(interval!(-1, 1))),
+            Err(ArithmeticError::Domain("divisor [-1, 1] contains zero".to_owned()))
+        );
+        assert!(a.checked_add(Number::Complex(complex!(1.i))).is_err());
+    }
+
+    check_macro();
+    check_ops();
+    check_functions();
+    check_relation();
+}
+
+#[test]
+fn test_uncertain() {
+    use super::{ArithmeticError, Number, Uncertain};
+
+    #[allow(clippy::approx_constant)]
+    fn check_display() {
+        assert_eq!("9.81 ± 0.02", uncertain!(9.81, 0.02).to_string());
+        assert_eq!("3.14 ± 0.01", uncertain!(3.14159, 0.0123).to_string());
+        assert_eq!("1230 ± 30", uncertain!(1234, 30).to_string());
+        assert_eq!("0.00 ± 0.01", uncertain!(-0.001, 0.01).to_string());
+        assert_eq!("2 ± 0", uncertain!(2.0).to_string());
+    }
+
+    fn check_ops() {
+        let x = uncertain!(2, 0.1);
+        let y = uncertain!(3, 0.2);
+
+        assert!((x.clone() + y.clone()).error() - 0.05f64.sqrt() < 1e-15);
+        assert!(((x.clone() * y.clone()).error() - 0.5).abs() < 1e-15);
+        assert_eq!((x.clone() / y.clone()).value(), 2.0 / 3.0);
+        assert_eq!(x.clone() - x.clone(), uncertain!(0.0));
+        assert_eq!(x.clone() / x.clone(), uncertain!(1.0));
+        assert!(((x.clone() * x.clone()).error() - 0.4).abs() < 1e-15);
+        assert_eq!((-x.clone()).error(), 0.1);
+        assert_eq!((x.clone() + natural!(1)).value(), 3.0);
+        assert_eq!((x * rational!(1 / 2)).error(), 0.05);
+    }
+
+    fn check_functions() {
+        let x = uncertain!(1, 0.1);
+        assert_eq!(x.sin().map(|sin| sin.error()), Ok(1f64.cos() * 0.1));
+        assert_eq!(x.exp().map(|exp| exp.error()), Ok(1f64.exp() * 0.1));
+        assert_eq!(uncertain!(4, 0.4).sqrt().map(|sqrt| sqrt.error()), Ok(0.1));
+        assert!(uncertain!(-1, 0.1).ln().is_err());
+        let cube = uncertain!(2, 0.1).powf(&uncertain!(3.0));
+        assert_eq!(cube.map(|pow| pow.error()), Some(1.2000000000000002));
+        assert_eq!(uncertain!(-2, 0.1).powf(&uncertain!(0.5)), None);
+    }
+
+    fn check_numbers() {
+        let source = Uncertain::source();
+        let x = Number::uncertain(Number::Real(real!(2)), Number::Real(real!(0.1)), source).unwrap();
+        let y = Number::uncertain(Number::Real(real!(2)), Number::Real(real!(0.1)), source).unwrap();
+        assert!(x.clone().checked_sub(y).unwrap().is_zero());
+        assert_eq!(x.clone().uncertainty(), Ok(Number::Real(real!(0.1))));
+        assert_eq!(x.clone().nominal(), Ok(Number::Real(real!(2))));
+        let zero = Number::uncertain(Number::Natural(natural!(0)), Number::Real(real!(0.1)), source).unwrap();
+        assert_eq!(x.clone().checked_div(zero), Err(ArithmeticError::DivisionByZero));
+        assert!(x.clone().checked_add(Number::Complex(complex!(1.i))).is_err());
+        assert!(x.clone().checked_rem(Number::Natural(natural!(2))).is_err());
+        assert!(Number::uncertain(x, Number::Real(real!(-1)), source).is_err());
+    }
+
+    check_display();
+    check_ops();
+    check_functions();
+    check_numbers();
+}
+
+#[test]
+fn test_dual() {
+    use super::{ArithmeticError, Dual, Number};
+
+    fn check_ops() {
+        let x = Dual::variable(3.0, 0);
+        let y = Dual::variable(2.0, 1);
+
+        let product = x.clone() * y.clone();
+        assert_eq!((product.value(), product.derivative(0), product.derivative(1)), (6.0, 2.0, 3.0));
+        let quot = x.clone() / y.clone();
+        assert_eq!((quot.derivative(0), quot.derivative(1)), (0.5, -0.75));
+        assert!((x.clone() - x.clone()).is_constant());
+        assert_eq!((-x.clone()).derivative(0), -1.0);
+        assert_eq!((x.clone() + Dual::from(1.0)).derivative(1), 0.0);
+        assert_eq!(x.rem(&y).derivative(1), -1.0);
+    }
+
+    fn check_functions() {
+        let x = Dual::variable(1.0, 0);
+        assert_eq!(x.sin().map(|sin| sin.derivative(0)), Ok(1f64.cos()));
+        assert_eq!(x.exp().map(|exp| exp.derivative(0)), Ok(1f64.exp()));
+        assert_eq!(Dual::variable(4.0, 0).sqrt().map(|sqrt| sqrt.derivative(0)), Ok(0.25));
+        assert!(Dual::variable(-1.0, 0).ln().is_err());
+        let cube = Dual::variable(2.0, 0).powf(&Dual::from(3.0));
+        assert_eq!(cube.map(|pow| pow.derivative(0)), Some(12.0));
+        let power = Dual::from(2.0).powf(&Dual::variable(3.0, 0));
+        assert_eq!(power.map(|pow| pow.derivative(0)), Some(8.0 * 2f64.ln()));
+        assert_eq!(Dual::variable(-2.0, 0).powf(&Dual::from(0.5)), None);
+        assert_eq!(Dual::variable(-2.0, 0).abs().derivative(0), -1.0);
+    }
+
+    fn check_numbers() {
+        let variable = Dual::next_variable();
+        let x = Number::dual(Number::Natural(natural!(2)), variable).unwrap();
+        let square = x.clone().checked_mul(x.clone()).unwrap();
+        assert_eq!(square.derivative(variable), Ok(Number::Real(real!(4))));
+        let power = x.clone().checked_pow(Number::Natural(natural!(3))).unwrap();
+        assert_eq!(power.derivative(variable), Ok(Number::Real(real!(12))));
+        assert_eq!(Number::Natural(natural!(5)).derivative(variable), Ok(Number::Natural(natural!(0))));
+        assert!(x < Number::Rational(rational!(5 / 2)));
+        assert_eq!(x, Number::Natural(natural!(2)));
+        let zero = x.clone().checked_sub(x.clone()).unwrap();
+        assert_eq!(x.clone().checked_div(zero), Err(ArithmeticError::DivisionByZero));
+        assert!(x.clone().checked_add(Number::Complex(complex!(1.i))).is_err());
+        assert!(Number::dual(x, variable).is_err());
+    }
+
+    check_ops();
+    check_functions();
+    check_numbers();
+}
+
 #[test]
 fn test_cross_types_add() {
     let a = natural!(3);
@@ -325,35 +818,35 @@ fn test_cross_types_add() {
     let d = real!(15);
     let e = complex!(3 + 5.i);
 
-    let aa = a + a;
-    let ab = a + b;
-    let ac = a + c;
-    let ad = a + d;
-    let ae = a + e;
-
-    let ba = b + a;
-    let bb = b + b;
-    let bc = b + c;
-    let bd = b + d;
-    let be = b + e;
-
-    let ca = c + a;
-    let cb = c + b;
-    let cc = c + c;
-    let cd = c + d;
-    let ce = c + e;
-
-    let da = d + a;
-    let db = d + b;
-    let dc = d + c;
-    let dd = d + d;
-    let de = d + e;
-
-    let ea = e + a;
-    let eb = e + b;
-    let ec = e + c;
-    let ed = e + d;
-    let ee = e + e;
+    let aa = a.clone() + a.clone();
+    let ab = a.clone() + b.clone();
+    let ac = a.clone() + c.clone();
+    let ad = a.clone() + d.clone();
+    let ae = a.clone() + e.clone();
+
+    let ba = b.clone() + a.clone();
+    let bb = b.clone() + b.clone();
+    let bc = b.clone() + c.clone();
+    let bd = b.clone() + d.clone();
+    let be = b.clone() + e.clone();
+
+    let ca = c.clone() + a.clone();
+    let cb = c.clone() + b.clone();
+    let cc = c.clone() + c.clone();
+    let cd = c.clone() + d.clone();
+    let ce = c.clone() + e.clone();
+
+    let da = d.clone() + a.clone();
+    let db = d.clone() + b.clone();
+    let dc = d.clone() + c.clone();
+    let dd = d.clone() + d.clone();
+    let de = d.clone() + e.clone();
+
+    let ea = e.clone() + a.clone();
+    let eb = e.clone() + b.clone();
+    let ec = e.clone() + c.clone();
+    let ed = e.clone() + d.clone();
+    let ee = e.clone() + e.clone();
 
     assert_eq!(aa, natural!(6));
     assert_eq!(ab, integer!(-2));
@@ -394,35 +887,35 @@ fn test_cross_types_sub() {
     let d = real!(15);
     let e = complex!(3 + 5.i);
 
-    let aa = a - a;
-    let ab = a - b;
-    let ac = a - c;
-    let ad = a - d;
-    let ae = a - e;
-
-    let ba = b - a;
-    let bb = b - b;
-    let bc = b - c;
-    let bd = b - d;
-    let be = b - e;
-
-    let ca = c - a;
-    let cb = c - b;
-    let cc = c - c;
-    let cd = c - d;
-    let ce = c - e;
-
-    let da = d - a;
-    let db = d - b;
-    let dc = d - c;
-    let dd = d - d;
-    let de = d - e;
-
-    let ea = e - a;
-    let eb = e - b;
-    let ec = e - c;
-    let ed = e - d;
-    let ee = e - e;
+    let aa = a.clone() - a.clone();
+    let ab = a.clone() - b.clone();
+    let ac = a.clone() - c.clone();
+    let ad = a.clone() - d.clone();
+    let ae = a.clone() - e.clone();
+
+    let ba = b.clone() - a.clone();
+    let bb = b.clone() - b.clone();
+    let bc = b.clone() - c.clone();
+    let bd = b.clone() - d.clone();
+    let be = b.clone() - e.clone();
+
+    let ca = c.clone() - a.clone();
+    let cb = c.clone() - b.clone();
+    let cc = c.clone() - c.clone();
+    let cd = c.clone() - d.clone();
+    let ce = c.clone() - e.clone();
+
+    let da = d.clone() - a.clone();
+    let db = d.clone() - b.clone();
+    let dc = d.clone() - c.clone();
+    let dd = d.clone() - d.clone();
+    let de = d.clone() - e.clone();
+
+    let ea = e.clone() - a.clone();
+    let eb = e.clone() - b.clone();
+    let ec = e.clone() - c.clone();
+    let ed = e.clone() - d.clone();
+    let ee = e.clone() - e.clone();
 
     assert_eq!(aa, natural!(0));
     assert_eq!(ab, integer!(8));
@@ -463,35 +956,35 @@ fn test_cross_types_mul() {
     let d = real!(15);
     let e = complex!(3 + 5.i);
 
-    let aa = a * a;
-    let ab = a * b;
-    let ac = a * c;
-    let ad = a * d;
-    let ae = a * e;
-
-    let ba = b * a;
-    let bb = b * b;
-    let bc = b * c;
-    let bd = b * d;
-    let be = b * e;
-
-    let ca = c * a;
-    let cb = c * b;
-    let cc = c * c;
-    let cd = c * d;
-    let ce = c * e;
-
-    let da = d * a;
-    let db = d * b;
-    let dc = d * c;
-    let dd = d * d;
-    let de = d * e;
-
-    let ea = e * a;
-    let eb = e * b;
-    let ec = e * c;
-    let ed = e * d;
-    let ee = e * e;
+    let aa = a.clone() * a.clone();
+    let ab = a.clone() * b.clone();
+    let ac = a.clone() * c.clone();
+    let ad = a.clone() * d.clone();
+    let ae = a.clone() * e.clone();
+
+    let ba = b.clone() * a.clone();
+    let bb = b.clone() * b.clone();
+    let bc = b.clone() * c.clone();
+    let bd = b.clone() * d.clone();
+    let be = b.clone() * e.clone();
+
+    let ca = c.clone() * a.clone();
+    let cb = c.clone() * b.clone();
+    let cc = c.clone() * c.clone();
+    let cd = c.clone() * d.clone();
+    let ce = c.clone() * e.clone();
+
+    let da = d.clone() * a.clone();
+    let db = d.clone() * b.clone();
+    let dc = d.clone() * c.clone();
+    let dd = d.clone() * d.clone();
+    let de = d.clone() * e.clone();
+
+    let ea = e.clone() * a.clone();
+    let eb = e.clone() * b.clone();
+    let ec = e.clone() * c.clone();
+    let ed = e.clone() * d.clone();
+    let ee = e.clone() * e.clone();
 
     assert_eq!(aa, natural!(9));
     assert_eq!(ab, integer!(-15));
@@ -532,35 +1025,35 @@ fn test_cross_types_div() {
     let d = real!(15);
     let e = complex!(3 + 5.i);
 
-    let aa = a / a;
-    let ab = a / b;
-    let ac = a / c;
-    let ad = a / d;
-    let ae = a / e;
-
-    let ba = b / a;
-    let bb = b / b;
-    let bc = b / c;
-    let bd = b / d;
-    let be = b / e;
-
-    let ca = c / a;
-    let cb = c / b;
-    let cc = c / c;
-    let cd = c / d;
-    let ce = c / e;
-
-    let da = d / a;
-    let db = d / b;
-    let dc = d / c;
-    let dd = d / d;
-    let de = d / e;
-
-    let ea = e / a;
-    let eb = e / b;
-    let ec = e / c;
-    let ed = e / d;
-    let ee = e / e;
+    let aa = a.clone() / a.clone();
+    let ab = a.clone() / b.clone();
+    let ac = a.clone() / c.clone();
+    let ad = a.clone() / d.clone();
+    let ae = a.clone() / e.clone();
+
+    let ba = b.clone() / a.clone();
+    let bb = b.clone() / b.clone();
+    let bc = b.clone() / c.clone();
+    let bd = b.clone() / d.clone();
+    let be = b.clone() / e.clone();
+
+    let ca = c.clone() / a.clone();
+    let cb = c.clone() / b.clone();
+    let cc = c.clone() / c.clone();
+    let cd = c.clone() / d.clone();
+    let ce = c.clone() / e.clone();
+
+    let da = d.clone() / a.clone();
+    let db = d.clone() / b.clone();
+    let dc = d.clone() / c.clone();
+    let dd = d.clone() / d.clone();
+    let de = d.clone() / e.clone();
+
+    let ea = e.clone() / a.clone();
+    let eb = e.clone() / b.clone();
+    let ec = e.clone() / c.clone();
+    let ed = e.clone() / d.clone();
+    let ee = e.clone() / e.clone();
 
     assert_eq!(aa, natural!(1));
     assert_eq!(ab, integer!(0));
@@ -603,10 +1096,10 @@ fn test_number_ops() {
     let d = Number::Real(real!(0.5));
     let e = Number::Complex(complex!(2.i));
 
-    let ab = a + b;
-    let bc = b - c;
-    let cd = c * d;
-    let de = d / e;
+    let ab = a.clone() + b.clone();
+    let bc = b.clone() - c.clone();
+    let cd = c.clone() * d.clone();
+    let de = d.clone() / e.clone();
 
     assert_eq!(ab, Number::Integer(integer!(8)));
     assert_eq!(bc, Number::Rational(rational!(14 / 3)));
@@ -614,22 +1107,564 @@ fn test_number_ops() {
     assert_eq!(de, Number::Complex(complex!(-0.25.i)));
 }
 
+#[test]
+fn test_promotion() {
+    use super::Number;
+
+    fn check_sub() {
+        let two = Number::Natural(natural!(2));
+        let five = Number::Natural(natural!(5));
+        let diff = two.clone() - five.clone();
+        assert_eq!(diff.kind(), "Integer");
+        assert_eq!(diff, Number::Integer(integer!(-3)));
+
+        let diff = five - two;
+        assert_eq!(diff.kind(), "Natural");
+        assert_eq!(diff, Number::Natural(natural!(3)));
+    }
+
+    fn check_div() {
+        let six = Number::Natural(natural!(6));
+        let three = Number::Natural(natural!(3));
+        let four = Number::Integer(integer!(-4));
+        assert_eq!((six.clone() / three.clone()).kind(), "Natural");
+        assert_eq!(six.clone() / four.clone(), Number::Rational(rational!(-3 / 2)));
+        assert_eq!(four.clone() / three.clone(), Number::Rational(rational!(-4 / 3)));
+        assert_eq!(three.clone() / six.clone(), Number::Rational(rational!(1 / 2)));
+
+        let quot = Number::Integer(integer!(-8)) / four;
+        assert_eq!(quot.kind(), "Integer");
+        assert_eq!(quot, Number::Integer(integer!(2)));
+    }
+
+    check_sub();
+    check_div();
+}
+
+#[test]
+fn test_normalize() {
+    use super::Number;
+
+    fn check(number: Number, kind: &str, expected: &str) {
+        let number = number.normalize();
+        assert_eq!(number.kind(), kind);
+        assert_eq!(format!("{}", number), expected);
+    }
+
+    check(Number::Rational(rational!(4 / 2)), "Natural", "2");
+    check(Number::Rational(rational!(-4 / 2)), "Integer", "-2");
+    check(Number::Rational(rational!(3 / 2)), "Rational", "3 / 2");
+    check(Number::Integer(integer!(7)), "Natural", "7");
+    check(Number::Integer(integer!(-7)), "Integer", "-7");
+    check(Number::Complex(complex!(1.5 + 0.i)), "Real", "1.5");
+    check(Number::Complex(complex!(1.i)), "Complex", "0 + 1i");
+    check(Number::Real(real!(2.0)), "Real", "2");
+}
+
+#[test]
+fn test_pow() {
+    use super::{ArithmeticError, Number};
+
+    fn check_exact() {
+        let two = Number::Natural(natural!(2));
+        let power = two.clone().checked_pow(Number::Natural(natural!(100))).unwrap();
+        assert_eq!(power.kind(), "Natural");
+        assert_eq!(format!("{}", power), "1267650600228229401496703205376");
+
+        let base = Number::Integer(integer!(-3));
+        let power = base.checked_pow(Number::Natural(natural!(3))).unwrap();
+        assert_eq!(power, Number::Integer(integer!(-27)));
+
+        let base = Number::Rational(rational!(2 / 3));
+        let power = base.checked_pow(Number::Integer(integer!(-2))).unwrap();
+        assert_eq!(power, Number::Rational(rational!(9 / 4)));
+
+        let power = two.checked_pow(Number::Integer(integer!(-3))).unwrap();
+        assert_eq!(power, Number::Rational(rational!(1 / 8)));
+    }
+
+    fn check_real() {
+        let base = Number::Real(real!(2.0));
+        let power = base.clone().checked_pow(Number::Real(real!(0.5))).unwrap();
+        assert_eq!(power, Number::Real(real!(2f64.sqrt())));
+
+        let power = base.checked_pow(Number::Integer(integer!(-1))).unwrap();
+        assert_eq!(power, Number::Real(real!(0.5)));
+
+        let base = Number::Real(real!(-2.0));
+        let power = base.checked_pow(Number::Real(real!(3.0))).unwrap();
+        assert_eq!(power, Number::Real(real!(-8.0)));
+    }
+
+    fn check_complex() {
+        let base = Number::Integer(integer!(-4));
+        let power = base.checked_pow(Number::Rational(rational!(1 / 2))).unwrap();
+        assert_eq!(power, Number::Complex(complex!(0 + 2.i)));
+
+        let i = Number::Complex(complex!(1.i));
+        let power = i.clone().checked_pow(i).unwrap();
+        assert_eq!(power, Number::Complex(super::Complex::new((-std::f64::consts::FRAC_PI_2).exp(), 0)));
+    }
+
+    fn check_errors() {
+        let zero = Number::Natural(natural!(0));
+        let power = zero.clone().checked_pow(zero.clone()).unwrap();
+        assert_eq!(power, Number::Natural(natural!(1)));
+        let power = zero.checked_pow(Number::Integer(integer!(-1)));
+        assert_eq!(power, Err(ArithmeticError::DivisionByZero));
+
+        let ten = Number::Natural(natural!(10));
+        let power = ten.checked_pow(Number::Natural(natural!(1000000000)));
+        assert_eq!(power.unwrap_err().to_string(), "overflow in Natural exponentiation");
+
+        let minus_one = Number::Integer(integer!(-1));
+        let power = minus_one.checked_pow(Number::Natural(natural!(1000000000001u64)));
+        assert_eq!(power, Ok(Number::Integer(integer!(-1))));
+    }
+
+    check_exact();
+    check_real();
+    check_complex();
+    check_errors();
+}
+
+#[test]
+fn test_functions() {
+    use super::{Complex, Number, Real};
+    use std::f64::consts::{E, FRAC_PI_2, FRAC_PI_4, PI};
+
+    fn check_real() {
+        let one = Number::Natural(natural!(1));
+        assert_eq!(one.clone().exp(), Ok(Number::Real(real!(E))));
+        assert_eq!(one.clone().atan(), Ok(Number::Real(real!(FRAC_PI_4))));
+        assert_eq!(one.clone().asin(), Ok(Number::Real(real!(FRAC_PI_2))));
+        assert_eq!(one.clone().atan2(one), Ok(Number::Real(real!(FRAC_PI_4))));
+        assert_eq!(Number::Real(real!(PI)).cos(), Ok(Number::Real(real!(-1.0))));
+        assert_eq!(Number::Natural(natural!(1000)).log(), Ok(Number::Real(real!(3.0))));
+        assert_eq!(Number::Integer(integer!(-8)).cbrt(), Ok(Number::Real(real!(-2.0))));
+    }
+
+    fn check_sqrt() {
+        let root = Number::Rational(rational!(9 / 4)).sqrt().unwrap();
+        assert_eq!(root, Number::Rational(rational!(3 / 2)));
+        let root = Number::Natural(natural!(16)).sqrt().unwrap();
+        assert_eq!(root.kind(), "Natural");
+        let root = Number::Integer(integer!(-4)).sqrt().unwrap();
+        assert_eq!(root, Number::Complex(complex!(2.i)));
+        let root = Number::Natural(natural!(2)).sqrt().unwrap();
+        assert_eq!(root, Number::Real(real!(2f64.sqrt())));
+    }
+
+    fn check_complex() {
+        let ln = Number::Integer(integer!(-1)).ln().unwrap();
+        assert_eq!(ln, Number::Complex(Complex::new(0, PI)));
+        let acosh = Number::Real(real!(0.5)).acosh().unwrap();
+        assert_eq!(acosh, Number::Complex(Complex::new(0, PI / 3.0)));
+        let atanh = Number::Natural(natural!(2)).atanh().unwrap();
+        assert_eq!(atanh, Number::Complex(Complex::new(0.5 * 3f64.ln(), FRAC_PI_2)));
+        let atanh = Number::Integer(integer!(-2)).atanh().unwrap();
+        assert_eq!(atanh, Number::Complex(Complex::new(-0.5 * 3f64.ln(), FRAC_PI_2)));
+
+        let z = Complex::new(0.5, -1.25);
+        assert_eq!(z.sin().asin(), z);
+        assert_eq!(z.cos().acos(), z);
+        assert_eq!(z.tan().atan(), z);
+        assert_eq!(z.sinh().asinh(), z);
+        assert_eq!(z.tanh().atanh(), z);
+        assert_eq!(z.exp().ln(), z);
+        assert_eq!(z.sqrt() * z.sqrt(), z);
+        assert_eq!(z.cbrt() * z.cbrt() * z.cbrt(), z);
+    }
+
+    fn check_abs() {
+        assert_eq!(Number::Integer(integer!(-3)).abs(), Number::Natural(natural!(3)));
+        assert_eq!(Number::Rational(rational!(-1 / 2)).abs(), Number::Rational(rational!(1 / 2)));
+        assert_eq!(Number::Complex(complex!(3 - 4.i)).abs(), Number::Real(real!(5.0)));
+    }
+
+    fn check_errors() {
+        let zero = Number::Natural(natural!(0));
+        assert_eq!(zero.clone().ln().unwrap().to_string(), "-inf");
+        assert_eq!(Number::Real(real!(1000.0)).exp().unwrap().to_string(), "inf");
+        Real::set_strict(true);
+        assert_eq!(zero.clone().ln().unwrap_err().to_string(), "ln of 0 is not finite");
+        assert!(Number::Natural(natural!(1)).atanh().is_err());
+        assert!(Number::Real(real!(1000.0)).exp().is_err());
+        Real::set_strict(false);
+        assert!(Number::Complex(complex!(1.i)).atan2(zero).is_err());
+    }
+
+    check_real();
+    check_sqrt();
+    check_complex();
+    check_abs();
+    check_errors();
+}
+
+#[test]
+fn test_complex_toolkit() {
+    use super::{Complex, Number};
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    fn check_polar() {
+        let z = complex!(3 - 4.i);
+        assert_eq!(z.abs(), real!(5.0));
+        assert_eq!(z.norm_sqr(), real!(25.0));
+        assert_eq!(complex!(1 + 1.i).arg(), real!(FRAC_PI_4));
+        assert_eq!(complex!(-1).arg(), real!(PI));
+        assert_eq!(complex!(-2.i).arg(), real!(-FRAC_PI_2));
+
+        let z = Complex::from_polar(2, FRAC_PI_2);
+        assert_eq!(z, complex!(2.i));
+        let (modulus, angle) = (complex!(-1 + 1.i).abs(), complex!(-1 + 1.i).arg());
+        assert_eq!(modulus, real!(2f64.sqrt()));
+        assert_eq!(angle, real!(3.0 * FRAC_PI_4));
+        assert_eq!(Complex::from_polar(modulus, angle), complex!(-1 + 1.i));
+    }
+
+    fn check_principal() {
+        assert_eq!(complex!(-4).sqrt(), complex!(2.i));
+        assert_eq!(complex!(-4 - 0.i).sqrt(), complex!(2.i));
+        assert_eq!(complex!(-1).ln(), Complex::new(0, PI));
+        assert_eq!(Complex::new(0, PI).exp(), complex!(-1));
+        assert_eq!(complex!(1.i).pow(&complex!(2)), complex!(-1));
+        assert_eq!(complex!(-8).pow(&Complex::new(1.0 / 3.0, 0)), Complex::new(1, 3f64.sqrt()));
+        assert_eq!(complex!(0).pow(&complex!(2)), complex!(0));
+    }
+
+    fn check_numbers() {
+        let z = Number::Complex(complex!(3 - 4.i));
+        assert_eq!(z.clone().re(), Number::Real(real!(3.0)));
+        assert_eq!(z.clone().im(), Number::Real(real!(-4.0)));
+        assert_eq!(z.conj(), Number::Complex(complex!(3 + 4.i)));
+        assert_eq!(Number::Integer(integer!(-2)).arg(), Number::Real(real!(PI)));
+
+        let polar = Number::from_polar(Number::Natural(natural!(2)), Number::Real(real!(PI)));
+        assert_eq!(polar, Ok(Number::Complex(complex!(-2))));
+        assert!(Number::cis(Number::Complex(complex!(1.i))).is_err());
+    }
+
+    check_polar();
+    check_principal();
+    check_numbers();
+}
+
+#[test]
+fn test_division() {
+    use super::Number;
+
+    fn nat(n: u32) -> Number {
+        Number::Natural(natural!(n))
+    }
+
+    fn int(n: i32) -> Number {
+        Number::Integer(integer!(n))
+    }
+
+    fn check_integers() {
+        for &(a, b, rem, modulo, div, floor_div) in &[
+            (7, 3, 1, 1, 2, 2),
+            (-7, 3, -1, 2, -3, -3),
+            (7, -3, 1, 1, -2, -3),
+            (-7, -3, -1, 2, 3, 2),
+            (6, -3, 0, 0, -2, -2),
+        ] {
+            assert_eq!(int(a).checked_rem(int(b)), Ok(int(rem)));
+            assert_eq!(int(a).checked_euclid_rem(int(b)).map(Number::normalize), Ok(int(modulo).normalize()));
+            assert_eq!(int(a).checked_euclid_div(int(b)).map(Number::normalize), Ok(int(div).normalize()));
+            assert_eq!(int(a).checked_floor_div(int(b)).map(Number::normalize), Ok(int(floor_div).normalize()));
+
+            let (quot, rem) = int(a).checked_divmod(int(b)).unwrap();
+            assert_eq!(quot * int(b) + rem.clone(), int(a));
+            assert!(rem >= nat(0));
+        }
+    }
+
+    fn check_rationals() {
+        let a = Number::Rational(rational!(-7 / 2));
+        let b = Number::Rational(rational!(2 / 3));
+        let (quot, rem) = a.checked_divmod(b).unwrap();
+        assert_eq!(quot.normalize(), int(-6));
+        assert_eq!(rem, Number::Rational(rational!(1 / 2)));
+        assert_eq!(Number::Rational(rational!(-1 / 2)).floor(), Ok(int(-1)));
+    }
+
+    fn check_reals() {
+        let a = Number::Real(real!(-3.5));
+        assert_eq!(a.clone().checked_euclid_rem(nat(2)), Ok(Number::Real(real!(0.5))));
+        assert_eq!(a.clone().checked_euclid_div(int(-2)), Ok(Number::Real(real!(2.0))));
+        assert_eq!(a.checked_floor_div(nat(2)), Ok(Number::Real(real!(-2.0))));
+        assert_eq!(real!(-2.0).floor(), real!(-2.0));
+        assert_eq!(real!(-2.5).floor(), real!(-3.0));
+    }
+
+    fn check_errors() {
+        assert!(nat(5).checked_euclid_rem(nat(0)).is_err());
+        assert!(Number::Complex(complex!(1.i)).checked_divmod(nat(2)).is_err());
+    }
+
+    check_integers();
+    check_rationals();
+    check_reals();
+    check_errors();
+}
+
+#[test]
+fn test_comparison() {
+    use super::{Integer, Long, Number};
+    use std::cmp::Ordering;
+
+    fn check_exact() {
+        let big = Number::Integer(Integer::new(Long::from(10).pow(17)));
+        let next = big.clone() + Number::Natural(natural!(1));
+        assert!(big != next);
+        assert!(big < next);
+        assert_eq!(big.checked_cmp(&next), Ok(Ordering::Less));
+
+        let a = Number::Rational(rational!(100000000000000001i64 / 100000000000000000i64));
+        assert!(a > Number::Natural(natural!(1)));
+        assert_eq!(Number::Rational(rational!(1 / 3)), Number::Rational(rational!(2 / 6)));
+    }
+
+    fn check_real() {
+        let a = Number::Real(real!(0.1)) + Number::Real(real!(0.2));
+        let b = Number::Real(real!(0.3));
+        assert!(a == b);
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+        assert!(Number::Real(real!(1.0)) < Number::Real(real!(1.0 + 1e-13)));
+        assert_eq!(real!(1.0).partial_cmp(&real!(1.0 + 1e-15)), Some(Ordering::Equal));
+    }
+
+    fn check_complex() {
+        let i = Number::Complex(complex!(1.i));
+        let two = Number::Natural(natural!(2));
+        let err = i.checked_cmp(&two).unwrap_err();
+        assert_eq!(err.to_string(), "cannot order complex numbers");
+        assert_eq!(i.partial_cmp(&two), None);
+        assert_eq!(i.checked_cmp(&i), Ok(Ordering::Equal));
+
+        let real = Number::Complex(complex!(3));
+        assert_eq!(real.checked_cmp(&two), Ok(Ordering::Greater));
+        assert_eq!(complex!(1.i).partial_cmp(&complex!(2.i)), None);
+    }
+
+    check_exact();
+    check_real();
+    check_complex();
+}
+
+#[test]
+fn test_tolerance() {
+    use super::{Real, Tolerance};
+
+    fn check_double() {
+        let a = real!(0.1) + real!(0.2);
+        let b = real!(0.3);
+
+        Real::set_tolerance(Some(Tolerance::Strict));
+        assert!(a != b);
+        assert!(a > b);
+        Real::set_tolerance(Some(Tolerance::Ulps(1)));
+        assert!(a == b);
+        Real::set_tolerance(Some(Tolerance::Absolute(1e-20)));
+        assert!(a != b);
+        Real::set_tolerance(Some(Tolerance::Relative(1e-6)));
+        assert!(real!(1e9) == real!(1e9 + 100.0));
+        assert!(real!(1.0) != real!(1.0 + 1e-5));
+        assert!(real!(-1e-300) != real!(1e-300));
+        Real::set_tolerance(None);
+        assert!(a == b);
+    }
+
+    fn check_float() {
+        Real::set_precision(Some(30));
+        let third = real!(1) / real!(3);
+        let near = third.clone() + "0.0000000000000000000000000001".parse::<Real>().unwrap();
+
+        Real::set_tolerance(Some(Tolerance::Strict));
+        assert!(third != near);
+        Real::set_tolerance(Some(Tolerance::Ulps(1000000000)));
+        assert!(third == near);
+        Real::set_tolerance(Some(Tolerance::Absolute(1e-20)));
+        assert!(third == near);
+        Real::set_tolerance(None);
+        Real::set_precision(None);
+    }
+
+    check_double();
+    check_float();
+}
+
+#[test]
+fn test_special_values() {
+    use super::{Complex, Number, Real, Tolerance};
+    use std::cmp::Ordering;
+
+    fn inf() -> Number {
+        Number::Real(Real::new(f64::INFINITY))
+    }
+
+    fn nan() -> Number {
+        Number::Real(Real::new(f64::NAN))
+    }
+
+    fn check_equality() {
+        let (inf, nan) = (real!(f64::INFINITY), real!(f64::NAN));
+        assert!(inf == inf.clone());
+        assert!(inf != -inf.clone());
+        assert!(inf != real!(1e308));
+        assert!(nan != nan.clone());
+        assert_eq!(nan.partial_cmp(&nan), None);
+        Real::set_tolerance(Some(Tolerance::Relative(1e-6)));
+        assert!(inf == inf.clone());
+        assert!(nan != nan.clone());
+        Real::set_tolerance(None);
+        assert!(real!(0.0) == real!(-0.0));
+        Real::set_precision(Some(30));
+        assert_eq!(real!(-0.0).to_string(), "-0");
+        assert_eq!((real!(-1.5) * real!(0.0)).to_string(), "-0");
+        assert!(!(real!(1.0) / real!(0.0)).is_finite());
+        assert!((real!(0.0) / real!(0.0)).is_nan());
+        Real::set_precision(None);
+        assert_eq!(nan.to_string(), "nan");
+        assert_eq!((-inf).to_string(), "-inf");
+    }
+
+    fn check_arithmetic() {
+        let one = Number::Natural(natural!(1));
+        assert_eq!(inf().checked_add(one.clone()).unwrap().to_string(), "inf");
+        assert!(inf().checked_sub(inf()).unwrap().is_nan());
+        assert!(Number::Natural(natural!(0)).checked_mul(inf()).unwrap().is_nan());
+        assert!(one.clone().checked_div(inf()).unwrap().is_zero());
+        assert_eq!(one.clone().checked_div(Number::Real(Real::zero())).unwrap().to_string(), "inf");
+        Real::set_strict(true);
+        assert!(one.checked_div(Number::Real(Real::zero())).is_err());
+        Real::set_strict(false);
+        assert!(nan().checked_cmp(&nan()).is_err());
+        assert!(nan() != nan());
+        assert!(inf() == inf());
+    }
+
+    fn check_complex_infinity() {
+        let i = Number::Complex(complex!(1.i));
+        let infinity = inf().checked_mul(i.clone()).unwrap().normalize();
+        assert_eq!(infinity.to_string(), "complex infinity");
+        assert!(!infinity.is_finite() && !infinity.is_nan());
+        assert_eq!(infinity, Number::Complex(Complex::infinity()));
+        assert_eq!(infinity.clone().checked_mul(i.clone()).unwrap().normalize(), infinity);
+        assert!(infinity.clone().checked_sub(infinity.clone()).unwrap().is_nan());
+        assert!(infinity.clone().checked_mul(Number::Natural(natural!(0))).unwrap().is_nan());
+        assert!(i.clone().checked_div(infinity.clone()).unwrap().is_zero());
+        assert!(infinity.clone().checked_div(Number::Natural(natural!(0))).is_err());
+        assert_eq!(infinity.clone().checked_pow(Number::Integer(integer!(-2))).unwrap(), Number::Natural(natural!(0)));
+        assert!(infinity.clone().arg().is_nan());
+        assert_eq!(infinity.checked_cmp(&Number::Complex(Complex::infinity())), Ok(Ordering::Equal));
+        assert!(nan().checked_add(i).unwrap().normalize().is_nan());
+    }
+
+    fn check_strict() {
+        assert!(inf().check_finite().is_ok());
+        Real::set_strict(true);
+        assert_eq!(inf().check_finite().unwrap_err().to_string(), "inf is not finite");
+        assert!(Number::Real(real!(1.5)).check_finite().is_ok());
+        Real::set_strict(false);
+    }
+
+    check_equality();
+    check_arithmetic();
+    check_complex_infinity();
+    check_strict();
+}
+
+#[test]
+fn test_checked_ops() {
+    use super::{ArithmeticError, Integer, Long, Number, Operation, Rational, Real};
+
+    fn check_division_by_zero() {
+        let zeros = vec![
+            Number::Natural(natural!(0)),
+            Number::Rational(Rational::default()),
+            Number::Real(real!(0.0)),
+            Number::Complex(complex!(0)),
+        ];
+        Real::set_strict(true);
+        for zero in zeros {
+            let one = Number::Integer(integer!(1));
+            assert_eq!(one.clone().checked_div(zero.clone()), Err(ArithmeticError::DivisionByZero));
+            assert_eq!(one.checked_rem(zero), Err(ArithmeticError::DivisionByZero));
+        }
+        Real::set_strict(false);
+        let one = Number::Integer(integer!(1));
+        assert!(one.clone().checked_div(Number::Natural(natural!(0))).is_err());
+        assert!(!one.checked_div(Number::Real(real!(0.0))).unwrap().is_finite());
+    }
+
+    fn check_overflow() {
+        let big = Number::Real(real!(1e308));
+        let err = ArithmeticError::Overflow { kind: "Real", operation: Operation::Multiplication };
+        assert!(!big.clone().checked_mul(Number::Natural(natural!(10))).unwrap().is_finite());
+        Real::set_strict(true);
+        assert_eq!(big.checked_mul(Number::Natural(natural!(10))), Err(err.clone()));
+        Real::set_strict(false);
+        assert_eq!(err.to_string(), "overflow in Real multiplication");
+
+        let huge = Number::Integer(Integer::new(Long::one() << (1 << 19)));
+        let err = ArithmeticError::Overflow { kind: "Integer", operation: Operation::Multiplication };
+        assert_eq!(huge.clone().checked_mul(huge.clone()), Err(err));
+        assert!(huge.checked_add(Number::Natural(natural!(1))).is_ok());
+    }
+
+    fn check_underflow() {
+        let tiny = Number::Real(real!(1e-300));
+        let err = ArithmeticError::Underflow { kind: "Real", operation: Operation::Multiplication };
+        assert!(tiny.clone().checked_mul(tiny.clone()).unwrap().is_zero());
+        Real::set_strict(true);
+        assert_eq!(tiny.clone().checked_mul(tiny), Err(err));
+        Real::set_strict(false);
+    }
+
+    fn check_domain() {
+        let i = Number::Complex(complex!(1.i));
+        assert!(i.checked_rem(Number::Natural(natural!(2))).is_err());
+    }
+
+    fn check_rem() {
+        let a = Number::Rational(rational!(7 / 2));
+        let b = Number::Integer(integer!(-2));
+        assert_eq!(a.checked_rem(b), Ok(Number::Rational(rational!(3 / 2))));
+
+        let a = Number::Real(real!(-3.5));
+        let b = Number::Natural(natural!(2));
+        assert_eq!(a.checked_rem(b), Ok(Number::Real(real!(-1.5))));
+
+        let a = Number::Integer(integer!(-7));
+        let b = Number::Natural(natural!(3));
+        assert_eq!(a % b, Number::Integer(integer!(-1)));
+    }
+
+    check_division_by_zero();
+    check_overflow();
+    check_underflow();
+    check_domain();
+    check_rem();
+}
+
 #[test]
 fn test_assign_ops() {
     fn check_natural() {
         let mut a = natural!(1);
         let b = natural!(2);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, natural!(3));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, natural!(1));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, natural!(2));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, natural!(1));
     }
 
@@ -637,16 +1672,16 @@ fn test_assign_ops() {
         let mut a = integer!(1);
         let b = integer!(3);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, integer!(4));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, integer!(1));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, integer!(3));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, integer!(1));
     }
 
@@ -654,16 +1689,16 @@ fn test_assign_ops() {
         let mut a = rational!(1);
         let b = rational!(3);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, rational!(4));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, rational!(1));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, rational!(3));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, rational!(1));
     }
 
@@ -671,16 +1706,16 @@ fn test_assign_ops() {
         let mut a = real!(1);
         let b = real!(3);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, real!(4));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, real!(1));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, real!(3));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, real!(1));
     }
 
@@ -688,16 +1723,16 @@ fn test_assign_ops() {
         let mut a = complex!(1);
         let b = complex!(3);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, complex!(4));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, complex!(1));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, complex!(3));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, complex!(1));
     }
 
@@ -714,19 +1749,19 @@ fn test_cross_type_assign_ops() {
         let mut a = integer!(5);
         let b = natural!(2);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, integer!(7));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, integer!(5));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, integer!(10));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, integer!(5));
 
-        a %= b;
+        a %= b.clone();
         assert_eq!(a, integer!(1));
     }
 
@@ -734,16 +1769,16 @@ fn test_cross_type_assign_ops() {
         let mut a = rational!(5 / 3);
         let b = natural!(2);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, rational!(11 / 3));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, rational!(5 / 3));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, rational!(10 / 3));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, rational!(5 / 3));
     }
 
@@ -751,16 +1786,16 @@ fn test_cross_type_assign_ops() {
         let mut a = real!(2.5);
         let b = natural!(2);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, real!(4.5));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, real!(2.5));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, real!(5));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, real!(2.5));
     }
 
@@ -768,16 +1803,16 @@ fn test_cross_type_assign_ops() {
         let mut a = complex!(3 + 2.i);
         let b = natural!(2);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, complex!(5 + 2.i));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, complex!(3 + 2.i));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, complex!(6 + 4.i));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, complex!(3 + 2.i));
     }
 
@@ -785,16 +1820,16 @@ fn test_cross_type_assign_ops() {
         let mut a = rational!(5 / 2);
         let b = integer!(3);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, rational!(11 / 2));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, rational!(5 / 2));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, rational!(15 / 2));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, rational!(5 / 2));
     }
 
@@ -802,16 +1837,16 @@ fn test_cross_type_assign_ops() {
         let mut a = real!(3.2);
         let b = integer!(5);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, real!(8.2));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, real!(3.2));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, real!(16));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, real!(3.2));
     }
 
@@ -819,16 +1854,16 @@ fn test_cross_type_assign_ops() {
         let mut a = complex!(3 + 2.i);
         let b = integer!(2);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, complex!(5 + 2.i));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, complex!(3 + 2.i));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, complex!(6 + 4.i));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, complex!(3 + 2.i));
     }
 
@@ -836,16 +1871,16 @@ fn test_cross_type_assign_ops() {
         let mut a = real!(3.2);
         let b = rational!(4 / 5);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, real!(4));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, real!(3.2));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, real!(2.56));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, real!(3.2));
     }
 
@@ -853,16 +1888,16 @@ fn test_cross_type_assign_ops() {
         let mut a = complex!(1.25 + 2.75.i);
         let b = rational!(3 / 4);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, complex!(2 + 2.75.i));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, complex!(1.25 + 2.75.i));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, complex!(0.9375 + 2.0625.i));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, complex!(1.25 + 2.75.i));
     }
 
@@ -870,16 +1905,16 @@ fn test_cross_type_assign_ops() {
         let mut a = complex!(2.5 + 3.i);
         let b = real!(0.5);
 
-        a += b;
+        a += b.clone();
         assert_eq!(a, complex!(3 + 3.i));
 
-        a -= b;
+        a -= b.clone();
         assert_eq!(a, complex!(2.5 + 3.i));
 
-        a *= b;
+        a *= b.clone();
         assert_eq!(a, complex!(1.25 + 1.5.i));
 
-        a /= b;
+        a /= b.clone();
         assert_eq!(a, complex!(2.5 + 3.i));
     }
 
@@ -898,6 +1933,261 @@ fn test_cross_type_assign_ops() {
     check_complex_real();
 }
 
+#[test]
+fn test_parse() {
+    use super::{Float, Integer, Long, Rational};
+
+    assert_eq!(Long::from_str_radix("ff", 16), Ok(Long::from(255)));
+    assert_eq!(Long::from_str_radix("777777777777777777777", 8), Ok((Long::one() << 63) - Long::one()));
+    assert!(Long::from_str_radix("102", 2).is_err());
+    assert!(Long::from_str_radix("", 16).is_err());
+
+    assert_eq!(Rational::from_decimal("0.1"), Some(rational!(1 / 10)));
+    assert_eq!(Rational::from_decimal("-1.25e-2"), Some(rational!(-1 / 80)));
+    assert_eq!(Rational::from_decimal("2.5E3"), Some(Rational::new(Integer::new(2500), integer!(1))));
+    assert_eq!(Rational::from_decimal("inf"), None);
+
+    assert_eq!(Float::parse("1e-25", 30).map(|val| val.to_string()), Some("1e-25".to_owned()));
+    assert_eq!(Float::parse("1e999999", 30), None);
+}
+
+#[test]
+fn test_continued_fractions() {
+    use super::{Integer, Long, Rational};
+
+    fn check_terms() {
+        let terms = rational!(415 / 93).continued_fraction(10);
+        let expected: Vec<Integer> = vec![integer!(4), integer!(2), integer!(6), integer!(7)];
+        assert_eq!(terms, expected);
+        assert_eq!(rational!(-7 / 3).continued_fraction(2), vec![integer!(-3), integer!(1)]);
+        assert_eq!(rational!(1 / 3).continued_fraction(0), vec![]);
+    }
+
+    fn check_convergents() {
+        let convergents = rational!(415 / 93).convergents(10);
+        assert_eq!(convergents.len(), 4);
+        assert_eq!(convergents[1], rational!(9 / 2));
+        assert_eq!(convergents[3], rational!(415 / 93));
+    }
+
+    fn check_limit_denominator() {
+        let pi = Rational::from_decimal("3.141592653589793").unwrap();
+        assert_eq!(pi.limit_denominator(&Long::from(1000)), rational!(355 / 113));
+        assert_eq!(pi.limit_denominator(&Long::from(100)), rational!(311 / 99));
+        assert_eq!(pi.limit_denominator(&Long::from(1)), rational!(3 / 1));
+        assert_eq!(rational!(1 / 3).limit_denominator(&Long::from(3)), rational!(1 / 3));
+    }
+
+    fn check_simplest_between() {
+        let simplest = |lo: Rational, hi: Rational| Rational::simplest_between(&lo, &hi);
+        assert_eq!(simplest(rational!(31 / 100), rational!(34 / 100)), rational!(1 / 3));
+        assert_eq!(simplest(rational!(-34 / 100), rational!(-31 / 100)), rational!(-1 / 3));
+        assert_eq!(simplest(rational!(-1 / 2), rational!(3 / 1)), rational!(0 / 1));
+        assert_eq!(simplest(rational!(5 / 2), rational!(7 / 2)), rational!(3 / 1));
+        assert_eq!(simplest(rational!(2 / 7), rational!(2 / 7)), rational!(2 / 7));
+    }
+
+    check_terms();
+    check_convergents();
+    check_limit_denominator();
+    check_simplest_between();
+}
+
+#[test]
+fn test_number_theory() {
+    use super::{Long, Natural};
+    use utils::{gcd, lcm};
+
+    fn check_gcd() {
+        assert_eq!(lcm(natural!(4), natural!(6)), natural!(12));
+        assert_eq!(gcd(integer!(-4), integer!(6)), natural!(2));
+        assert_eq!(gcd(integer!(-4), integer!(-6)), natural!(2));
+        assert_eq!(lcm(integer!(-4), natural!(6)), natural!(12));
+        assert_eq!(gcd(integer!(0), integer!(-5)), natural!(5));
+        assert_eq!(natural!(0).lcm(&natural!(5)), natural!(0));
+        assert_eq!(integer!(240).xgcd(&integer!(46)), (natural!(2), integer!(-9), integer!(47)));
+        assert_eq!(integer!(-3).xgcd(&integer!(7)), (natural!(1), integer!(2), integer!(1)));
+    }
+
+    fn check_modular() {
+        assert_eq!(natural!(4).modpow(&natural!(13), &natural!(497)), natural!(445));
+        assert_eq!(natural!(5).modpow(&natural!(0), &natural!(1)), natural!(0));
+        assert_eq!(natural!(3).modinv(&natural!(7)), Some(natural!(5)));
+        assert_eq!(natural!(3).modinv(&natural!(6)), None);
+    }
+
+    fn check_primes() {
+        let primes: Vec<u32> = (0..50).filter(|n| Natural::new(*n).is_prime()).collect();
+        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]);
+        assert!(!natural!(561).is_prime());
+        assert!(!natural!(3215031751u64).is_prime());
+        assert!(Natural::new((Long::one() << 61) - Long::one()).is_prime());
+        // strong pseudoprime to every base up to 41
+        let psi_13 = "3317044064679887385961981".parse::<Long>().unwrap();
+        assert!(!Natural::new(psi_13).is_prime());
+        assert!(Natural::new((Long::one() << 127) - Long::one()).is_prime());
+        assert_eq!(natural!(13).next_prime(), natural!(17));
+        assert_eq!(natural!(1).next_prime(), natural!(2));
+    }
+
+    fn check_factors() {
+        let factors = |n: u64| natural!(n).factorize().map(|f| f.iter().map(|p| p.to_string()).collect::<Vec<_>>());
+        assert_eq!(factors(360), Some(vec!["2", "2", "2", "3", "3", "5"].into_iter().map(String::from).collect()));
+        assert_eq!(factors(1), Some(vec![]));
+        assert_eq!(factors(0), None);
+        assert_eq!(
+            factors(998244353 * 1000000007),
+            Some(vec!["998244353".to_owned(), "1000000007".to_owned()])
+        );
+        assert_eq!(natural!(36).totient(), Some(natural!(12)));
+        assert_eq!(natural!(1).totient(), Some(natural!(1)));
+        let divisors: Option<Vec<Natural>> = natural!(28).divisors();
+        assert_eq!(
+            divisors,
+            Some(vec![natural!(1), natural!(2), natural!(4), natural!(7), natural!(14), natural!(28)])
+        );
+    }
+
+    fn check_roots() {
+        assert_eq!(natural!(99).isqrt(), natural!(9));
+        assert_eq!(natural!(1000).iroot(3), natural!(10));
+        assert_eq!(natural!(999).iroot(3), natural!(9));
+        assert_eq!(natural!(0).iroot(5), natural!(0));
+        assert_eq!(Natural::new(Long::one() << 100).iroot(10), natural!(1024));
+    }
+
+    check_gcd();
+    check_modular();
+    check_primes();
+    check_factors();
+    check_roots();
+}
+
+#[test]
+fn test_as_num() {
+    use super::{Algebraic, AsNum, Complex, Integer, Natural, Rational, Real};
+
+    fn check_wider() {
+        let nat = natural!(3);
+        assert_eq!(AsNum::<Integer>::try_as_num(&nat), Some(integer!(3)));
+        assert_eq!(AsNum::<Rational>::try_as_num(&nat), Some(rational!(3)));
+        assert_eq!(AsNum::<Algebraic>::try_as_num(&nat), Some(Algebraic::from(rational!(3))));
+        assert_eq!(AsNum::<Real>::try_as_num(&integer!(-3)), Some(real!(-3)));
+        assert_eq!(AsNum::<Complex>::try_as_num(&rational!(1 / 2)), Some(complex!(0.5)));
+        assert_eq!(AsNum::<Complex>::try_as_num(&real!(2.5)), Some(complex!(2.5)));
+    }
+
+    fn check_narrower() {
+        assert_eq!(AsNum::<Natural>::try_as_num(&integer!(-3)), None);
+        assert_eq!(AsNum::<Natural>::try_as_num(&rational!(6 / 3)), Some(natural!(2)));
+        assert_eq!(AsNum::<Integer>::try_as_num(&rational!(7 / 2)), None);
+        assert_eq!(AsNum::<Rational>::try_as_num(&real!(0.1)), Some(Rational::new(1, 10)));
+        assert_eq!(AsNum::<Integer>::try_as_num(&real!(-4)), Some(integer!(-4)));
+        assert_eq!(AsNum::<Natural>::try_as_num(&real!(2.5)), None);
+        assert_eq!(AsNum::<Integer>::try_as_num(&complex!(5)), Some(integer!(5)));
+        assert_eq!(AsNum::<Rational>::try_as_num(&complex!(1 + 1.i)), None);
+        let root = Algebraic::from(rational!(2)).root(2).unwrap();
+        assert_eq!(AsNum::<Rational>::try_as_num(&root), None);
+        assert_eq!(AsNum::<Natural>::try_as_num(&(root.clone() * root)), Some(natural!(2)));
+    }
+
+    fn check_rounding() {
+        assert_eq!(rational!(5 / 2).round(), integer!(2));
+        assert_eq!(rational!(7 / 2).round(), integer!(4));
+        assert_eq!(Rational::new(-5, 2).round(), integer!(-2));
+        assert_eq!(Rational::new(-7, 3).round(), integer!(-2));
+        assert_eq!(real!(2.5).round(), real!(2));
+        assert_eq!(real!(-3.5).round(), real!(-4));
+    }
+
+    check_wider();
+    check_narrower();
+    check_rounding();
+}
+
+#[test]
+fn test_modular() {
+    use super::{ArithmeticError, Modular, Number};
+
+    let residue = |value: i32, modulus: u32| Number::Modular(Modular::new(value, natural!(modulus)));
+
+    fn check_ring(residue: &dyn Fn(i32, u32) -> Number) {
+        assert_eq!(Modular::new(-1, natural!(7)).value(), natural!(6));
+        assert_eq!(residue(5, 7).checked_add(residue(4, 7)), Ok(residue(2, 7)));
+        assert_eq!(residue(3, 7).checked_sub(Number::Natural(natural!(5))), Ok(residue(5, 7)));
+        assert_eq!(residue(3, 7).checked_mul(residue(5, 7)), Ok(residue(1, 7)));
+        assert_eq!(residue(1, 7).checked_div(residue(3, 7)), Ok(residue(5, 7)));
+        assert_eq!(residue(3, 7).checked_pow(Number::Integer(integer!(-1))), Ok(residue(5, 7)));
+        assert_eq!(-residue(3, 7), residue(4, 7));
+        assert_eq!(Modular::new(2, natural!(9)).inv(), Some(Modular::new(5, natural!(9))));
+        assert_eq!(Modular::new(3, natural!(9)).inv(), None);
+    }
+
+    fn check_errors(residue: &dyn Fn(i32, u32) -> Number) {
+        assert_eq!(
+            residue(2, 7).checked_add(residue(2, 5)),
+            Err(ArithmeticError::Domain("moduli 7 and 5 do not match".to_owned()))
+        );
+        assert_eq!(
+            residue(1, 6).checked_div(residue(3, 6)),
+            Err(ArithmeticError::Domain("3 has no inverse modulo 6".to_owned()))
+        );
+        assert_eq!(residue(1, 6).checked_div(residue(0, 6)), Err(ArithmeticError::DivisionByZero));
+        assert!(residue(1, 7).checked_add(Number::Real(real!(0.5))).is_err());
+        assert!(residue(1, 7).checked_cmp(&residue(2, 7)).is_err());
+    }
+
+    check_ring(&residue);
+    check_errors(&residue);
+}
+
+#[test]
+fn test_display() {
+    use super::{Long, Notation, Number};
+
+    fn check(text: &str, notation: Notation, expected: &str) {
+        let number = match text.parse::<Long>() {
+            Ok(_) => Number::natural(text.to_owned()).unwrap(),
+            Err(_) => Number::real(text.to_owned()).unwrap(),
+        };
+        assert_eq!(number.display(notation), expected);
+    }
+
+    fn check_radix() {
+        assert_eq!(format!("{:#x}", Long::from(-255)), "-0xff");
+        assert_eq!(format!("{:#o}", Long::from(8)), "0o10");
+        assert_eq!(format!("{:b}", Long::from(0)), "0");
+        assert_eq!(format!("{:x}", Long::one() << 64), "10000000000000000");
+    }
+
+    fn check_rationals() {
+        let third = Number::rational("1".to_owned(), "3".to_owned()).unwrap();
+        assert_eq!(third.display(Notation::Default), "1 / 3");
+        assert_eq!(third.display(Notation::Fraction), "1/3");
+        assert_eq!(third.display(Notation::Mixed), "1/3");
+        assert_eq!(third.display(Notation::Decimal), "0.(3)");
+        assert_eq!(third.display(Notation::Fixed(3)), "0.333");
+        assert_eq!(third.display(Notation::Scientific(None)), "3.33333333333333e-1");
+        assert_eq!(third.display(Notation::Engineering(Some(4))), "333.3e-3");
+        assert_eq!(third.display(Notation::Hex), "1 / 3");
+    }
+
+    fn check_reals() {
+        check("0.125", Notation::Fraction, "1/8");
+        check("2.5", Notation::Fixed(0), "2");
+        check("3.5", Notation::Fixed(0), "4");
+        check("0.0001", Notation::Scientific(None), "1e-4");
+        check("999.96", Notation::Scientific(Some(4)), "1.000e3");
+        check("1234", Notation::Engineering(None), "1.234e3");
+        check("100", Notation::Engineering(Some(1)), "100e0");
+        check("255", Notation::Hex, "0xff");
+    }
+
+    check_radix();
+    check_rationals();
+    check_reals();
+}
+
 #[test]
 fn test_default() {
     use super::{Complex, Integer, Natural, Rational, Real};
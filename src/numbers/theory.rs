@@ -0,0 +1,550 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Integer, Long, Natural, Number};
+use utils::{gcd_nat, lcm};
+
+/// Miller–Rabin bases that decide primality of every number below
+/// `3.3 * 10^24`. Larger numbers also take a strong Lucas test, which
+/// together with base 2 is the Baillie–PSW test, without a known
+/// counterexample.
+const WITNESSES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Factors below this are found by trial division, larger ones by
+/// Pollard's rho.
+const TRIAL_LIMIT: u32 = 1000;
+
+/// Steps of one Pollard's rho walk, and walks with different
+/// polynomials, before a number is given up as out of reach. They find
+/// factors of up to about ten digits.
+const RHO_STEPS: usize = 1 << 16;
+const RHO_ATTEMPTS: u32 = 4;
+
+/// Steps of a walk whose differences are multiplied together before
+/// their gcd with the number is taken.
+const RHO_BATCH: usize = 64;
+
+/// Most bits of the part of a number left after trial division that is
+/// factored further.
+const MAX_FACTOR_BITS: usize = 512;
+
+/// `a mod m` in `[0, m)`, for a positive `m`.
+fn modulo(a: &Long, m: &Long) -> Long {
+    let rem = a % m;
+    if rem.is_negative() {
+        rem + m.clone()
+    } else {
+        rem
+    }
+}
+
+fn mod_pow(base: &Long, exp: &Long, modulus: &Long) -> Long {
+    let mut result = modulo(&Long::one(), modulus);
+    let mut base = modulo(base, modulus);
+    let mut exp = exp.clone();
+    while !exp.is_zero() {
+        if exp.is_odd() {
+            result = &(&result * &base) % modulus;
+        }
+        base = &(&base * &base) % modulus;
+        exp = exp >> 1;
+    }
+    result
+}
+
+/// `(g, x, y)` with `a * x + b * y = g = gcd(a, b)` and `g >= 0`.
+fn ext_gcd(a: &Long, b: &Long) -> (Long, Long, Long) {
+    let (mut r0, mut r1) = (a.clone(), b.clone());
+    let (mut s0, mut s1) = (Long::one(), Long::zero());
+    let (mut t0, mut t1) = (Long::zero(), Long::one());
+    while !r1.is_zero() {
+        let (quot, rem) = r0.div_rem(&r1);
+        let s = &s0 - &(&quot * &s1);
+        let t = &t0 - &(&quot * &t1);
+        r0 = r1;
+        r1 = rem;
+        s0 = s1;
+        s1 = s;
+        t0 = t1;
+        t1 = t;
+    }
+    if r0.is_negative() {
+        (-r0, -s0, -t0)
+    } else {
+        (r0, s0, t0)
+    }
+}
+
+pub(crate) fn is_prime(n: &Long) -> bool {
+    if *n < Long::from(2) {
+        return false;
+    }
+    for &p in WITNESSES.iter() {
+        let p = Long::from(p);
+        if *n == p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+
+    let n_minus_one = n - &Long::one();
+    let mut odd = n_minus_one.clone();
+    let mut twos = 0;
+    while !odd.is_odd() {
+        odd = odd >> 1;
+        twos += 1;
+    }
+    'witness: for &a in WITNESSES.iter() {
+        let mut x = mod_pow(&Long::from(a), &odd, n);
+        if x == Long::one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..twos {
+            x = &(&x * &x) % n;
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    is_strong_lucas_probable_prime(n)
+}
+
+/// Jacobi symbol `(a/n)` for an odd positive `n`.
+fn jacobi(a: &Long, n: &Long) -> i32 {
+    let (mut a, mut n) = (modulo(a, n), n.clone());
+    let mut result = 1;
+    while !a.is_zero() {
+        while !a.is_odd() {
+            a = a >> 1;
+            if matches!(n.low_u64() % 8, 3 | 5) {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a.low_u64() % 4 == 3 && n.low_u64() % 4 == 3 {
+            result = -result;
+        }
+        a = &a % &n;
+    }
+    if n == Long::one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// `x / 2 mod n` for `x` in `[0, n)` and an odd `n`.
+fn halve(x: Long, n: &Long) -> Long {
+    if x.is_odd() {
+        (x + n.clone()) >> 1
+    } else {
+        x >> 1
+    }
+}
+
+/// Strong Lucas test of an odd `n` with Selfridge's parameters: the
+/// first `D` of `5, -7, 9, -11, ...` with `(D/n) = -1`, `P = 1` and
+/// `Q = (1 - D) / 4`. With `n + 1 = d 2^s`, a prime divides `U_d` or
+/// one of `V_(d 2^r)` for `r < s`.
+fn is_strong_lucas_probable_prime(n: &Long) -> bool {
+    let root = n.isqrt();
+    if &root * &root == *n {
+        return false;
+    }
+    let mut d = Long::from(5);
+    loop {
+        match jacobi(&d, n) {
+            -1 => break,
+            0 if d.abs() != *n => return false,
+            _ => {}
+        }
+        d = if d.is_negative() { -d + Long::from(2) } else { -(d + Long::from(2)) };
+    }
+    let q = modulo(&(&(Long::one() - d.clone()) / &Long::from(4)), n);
+    let d = modulo(&d, n);
+
+    let mut odd = n + &Long::one();
+    let mut twos = 0;
+    while !odd.is_odd() {
+        odd = odd >> 1;
+        twos += 1;
+    }
+
+    // U_k, V_k and Q^k for the leading bits of `odd`, with P = 1
+    let (mut u, mut v, mut q_power) = (Long::one(), Long::one(), q.clone());
+    for bit in (0..odd.bits() - 1).rev() {
+        u = &(&u * &v) % n;
+        v = modulo(&(&(&v * &v) - &(&q_power * &Long::from(2))), n);
+        q_power = &(&q_power * &q_power) % n;
+        if !(odd.clone() >> bit).is_odd() {
+            continue;
+        }
+        let next_u = halve(&(&u + &v) % n, n);
+        v = halve(&(&(&d * &u) + &v) % n, n);
+        u = next_u;
+        q_power = &(&q_power * &q) % n;
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..twos {
+        v = modulo(&(&(&v * &v) - &(&q_power * &Long::from(2))), n);
+        if v.is_zero() {
+            return true;
+        }
+        q_power = &(&q_power * &q_power) % n;
+    }
+    false
+}
+
+/// A nontrivial factor of the odd composite `n` by Pollard's rho,
+/// `None` when every walk runs out of steps.
+fn rho(n: &Long) -> Option<Long> {
+    let gcd = |a: &Long| gcd_nat(Natural::new(a.abs()), Natural::new(n.clone())).value();
+    for c in 1..=RHO_ATTEMPTS {
+        let c = Long::from(c);
+        let step = |x: &Long| &(&(x * x) + &c) % n;
+        let (mut x, mut y) = (Long::from(2), Long::from(2));
+        for _ in 0..RHO_STEPS / RHO_BATCH {
+            let (mut slow, mut fast) = (x.clone(), y.clone());
+            let mut product = Long::one();
+            for _ in 0..RHO_BATCH {
+                x = step(&x);
+                y = step(&step(&y));
+                product = &(&product * &(&x - &y)) % n;
+            }
+            let d = gcd(&product);
+            if d == Long::one() {
+                continue;
+            }
+            if d != *n {
+                return Some(d);
+            }
+            // The batch went past a factor, retrace it a step at a time
+            for _ in 0..RHO_BATCH {
+                slow = step(&slow);
+                fast = step(&step(&fast));
+                let d = gcd(&(&slow - &fast));
+                if d != Long::one() && d != *n {
+                    return Some(d);
+                }
+            }
+            break;
+        }
+    }
+    None
+}
+
+/// Primes below `TRIAL_LIMIT` dividing a positive `n`, in ascending order
+/// and repeated by multiplicity, with the cofactor left.
+pub(crate) fn trial_division(n: &Long) -> (Vec<Long>, Long) {
+    let mut factors = Vec::new();
+    let mut rest = n.clone();
+    for p in (2..TRIAL_LIMIT).filter(|p| *p == 2 || p % 2 == 1) {
+        let p = Long::from(p);
+        if &p * &p > rest {
+            break;
+        }
+        while (&rest % &p).is_zero() {
+            rest = &rest / &p;
+            factors.push(p.clone());
+        }
+    }
+    (factors, rest)
+}
+
+/// Prime factors of a positive `n` in ascending order, repeated by
+/// multiplicity, `None` when they are out of reach.
+pub(crate) fn factorize(n: &Long) -> Option<Vec<Long>> {
+    let (mut factors, rest) = trial_division(n);
+    if rest.bits() > MAX_FACTOR_BITS {
+        return None;
+    }
+    let mut composites = vec![rest];
+    while let Some(m) = composites.pop() {
+        if m == Long::one() {
+            continue;
+        }
+        if is_prime(&m) {
+            factors.push(m);
+        } else {
+            let d = rho(&m)?;
+            composites.push(&m / &d);
+            composites.push(d);
+        }
+    }
+    factors.sort();
+    Some(factors)
+}
+
+/// Distinct primes of a sorted factorization with their multiplicities.
+pub(crate) fn powers(factors: Vec<Long>) -> Vec<(Long, u32)> {
+    let mut powers: Vec<(Long, u32)> = Vec::new();
+    for p in factors {
+        match powers.last_mut() {
+            Some((last, count)) if *last == p => *count += 1,
+            _ => powers.push((p, 1)),
+        }
+    }
+    powers
+}
+
+pub(crate) fn iroot(n: &Long, k: u32) -> Long {
+    if n.is_zero() || k == 1 {
+        return n.clone();
+    }
+    // Newton's iteration from above decreases to the floor of the root
+    let mut x = Long::one() << n.bits().div_ceil(k as usize);
+    loop {
+        let y = (&(&Long::from(k - 1) * &x) + &(n / &x.pow(k - 1))) / Long::from(k);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+impl Natural {
+    pub fn lcm(&self, other: &Natural) -> Natural {
+        lcm(self.clone(), other.clone())
+    }
+
+    /// `self^exp mod modulus`, the modulus must be positive.
+    pub fn modpow(&self, exp: &Natural, modulus: &Natural) -> Natural {
+        Natural::new(mod_pow(&self.value(), &exp.value(), &modulus.value()))
+    }
+
+    /// `x` in `[0, modulus)` with `self * x = 1 (mod modulus)`, `None`
+    /// when they have a common factor. The modulus must be positive.
+    pub fn modinv(&self, modulus: &Natural) -> Option<Natural> {
+        let (gcd, x, _) = ext_gcd(&self.value(), &modulus.value());
+        if gcd == Long::one() {
+            Some(Natural::new(modulo(&x, &modulus.value())))
+        } else {
+            None
+        }
+    }
+
+    /// Deterministic Miller–Rabin below `3.3 * 10^24`.
+    pub fn is_prime(&self) -> bool {
+        is_prime(&self.value())
+    }
+
+    /// Smallest prime above `self`.
+    pub fn next_prime(&self) -> Natural {
+        let mut candidate = self.value() + Long::one();
+        if candidate <= Long::from(2) {
+            return natural!(2);
+        }
+        if !candidate.is_odd() {
+            candidate = candidate + Long::one();
+        }
+        while !is_prime(&candidate) {
+            candidate = candidate + Long::from(2);
+        }
+        Natural::new(candidate)
+    }
+
+    /// Prime factors in ascending order, repeated by multiplicity, by
+    /// trial division and Pollard's rho. `None` for zero and for numbers
+    /// whose factors are out of reach.
+    pub fn factorize(&self) -> Option<Vec<Natural>> {
+        if self.value().is_zero() {
+            return None;
+        }
+        let factors = factorize(&self.value())?;
+        Some(factors.into_iter().map(Natural::new).collect())
+    }
+
+    /// Euler's totient, the count of numbers up to `self` coprime to it.
+    pub fn totient(&self) -> Option<Natural> {
+        if self.value().is_zero() {
+            return Some(Natural::zero());
+        }
+        let mut result = Long::one();
+        for (p, count) in powers(factorize(&self.value())?) {
+            result = result * p.pow(count - 1) * (&p - &Long::one());
+        }
+        Some(Natural::new(result))
+    }
+
+    /// Divisors in ascending order, `None` for zero and for numbers whose
+    /// factors are out of reach.
+    pub fn divisors(&self) -> Option<Vec<Natural>> {
+        if self.value().is_zero() {
+            return None;
+        }
+        let mut divisors = vec![Long::one()];
+        for (p, count) in powers(factorize(&self.value())?) {
+            let mut multiples = Vec::new();
+            let mut power = Long::one();
+            for _ in 0..=count {
+                multiples.extend(divisors.iter().map(|d| d * &power));
+                power = &power * &p;
+            }
+            divisors = multiples;
+        }
+        divisors.sort();
+        Some(divisors.into_iter().map(Natural::new).collect())
+    }
+
+    /// Floor of the square root.
+    pub fn isqrt(&self) -> Natural {
+        Natural::new(self.value().isqrt())
+    }
+
+    /// Floor of the `k`-th root, `k` must be positive.
+    pub fn iroot(&self, k: u32) -> Natural {
+        Natural::new(iroot(&self.value(), k))
+    }
+}
+
+impl Integer {
+    /// `(g, x, y)` with `self * x + other * y = g = gcd(self, other)`.
+    pub fn xgcd(&self, other: &Integer) -> (Natural, Integer, Integer) {
+        let (gcd, x, y) = ext_gcd(&self.value(), &other.value());
+        (Natural::new(gcd), Integer::new(x), Integer::new(y))
+    }
+}
+
+fn needs(name: &str, what: &str) -> ArithmeticError {
+    ArithmeticError::Domain(format!("{} needs {}", name, what))
+}
+
+impl Number {
+    fn natural_arg(&self, name: &str) -> Result<Natural, ArithmeticError> {
+        match self {
+            Number::Natural(nat) => Ok(nat.clone()),
+            Number::Integer(int) if !int.value().is_negative() => Ok(int.abs()),
+            _ => Err(needs(name, "natural numbers")),
+        }
+    }
+
+    fn integer_arg(&self, name: &str) -> Result<Integer, ArithmeticError> {
+        match self {
+            Number::Natural(nat) => Ok(Integer::new(nat.value())),
+            Number::Integer(int) => Ok(int.clone()),
+            _ => Err(needs(name, "integers")),
+        }
+    }
+
+    /// A positive modulus.
+    fn modulus_arg(&self, name: &str) -> Result<Natural, ArithmeticError> {
+        match self.natural_arg(name) {
+            Ok(modulus) if !modulus.value().is_zero() => Ok(modulus),
+            _ => Err(ArithmeticError::Domain("modulus must be positive".to_owned())),
+        }
+    }
+
+    /// Prime factors, or an error naming why there are none.
+    fn factors(&self, name: &str) -> Result<Natural, ArithmeticError> {
+        let n = self.natural_arg(name)?;
+        if n.value().is_zero() {
+            return Err(ArithmeticError::Domain(format!("{} of 0 is not defined", name)));
+        }
+        Ok(n)
+    }
+
+    pub fn gcd(self, other: Number) -> Result<Number, ArithmeticError> {
+        let (a, b) = (self.integer_arg("gcd")?, other.integer_arg("gcd")?);
+        Ok(Number::Natural(gcd_nat(a.abs(), b.abs())))
+    }
+
+    pub fn lcm(self, other: Number) -> Result<Number, ArithmeticError> {
+        let (a, b) = (self.integer_arg("lcm")?, other.integer_arg("lcm")?);
+        Ok(Number::Natural(a.abs().lcm(&b.abs())))
+    }
+
+    /// `(g, x, y)` with `self * x + other * y = g = gcd(self, other)`.
+    pub fn xgcd(self, other: Number) -> Result<Vec<Number>, ArithmeticError> {
+        let (a, b) = (self.integer_arg("xgcd")?, other.integer_arg("xgcd")?);
+        let (gcd, x, y) = a.xgcd(&b);
+        Ok(vec![Number::Natural(gcd), Number::Integer(x), Number::Integer(y)])
+    }
+
+    /// `self^exp mod modulus`, a negative exponent raises the inverse.
+    pub fn modpow(self, exp: Number, modulus: Number) -> Result<Number, ArithmeticError> {
+        let base = self.integer_arg("modpow")?;
+        let exp = exp.integer_arg("modpow")?;
+        let modulus = modulus.modulus_arg("modpow")?;
+        let mut base = Natural::new(modulo(&base.value(), &modulus.value()));
+        if exp.value().is_negative() {
+            base = base
+                .modinv(&modulus)
+                .ok_or_else(|| no_inverse(&self, &modulus))?;
+        }
+        Ok(Number::Natural(base.modpow(&exp.abs(), &modulus)))
+    }
+
+    pub fn modinv(self, modulus: Number) -> Result<Number, ArithmeticError> {
+        let a = self.integer_arg("modinv")?;
+        let modulus = modulus.modulus_arg("modinv")?;
+        let a = Natural::new(modulo(&a.value(), &modulus.value()));
+        match a.modinv(&modulus) {
+            Some(inverse) => Ok(Number::Natural(inverse)),
+            None => Err(no_inverse(&self, &modulus)),
+        }
+    }
+
+    pub fn is_prime(&self) -> Result<bool, ArithmeticError> {
+        Ok(self.natural_arg("is_prime")?.is_prime())
+    }
+
+    pub fn next_prime(self) -> Result<Number, ArithmeticError> {
+        Ok(Number::Natural(self.natural_arg("next_prime")?.next_prime()))
+    }
+
+    pub fn factor(self) -> Result<Vec<Number>, ArithmeticError> {
+        let n = self.factors("factor")?;
+        let factors = n.factorize().ok_or_else(|| out_of_reach(&n))?;
+        Ok(factors.into_iter().map(Number::Natural).collect())
+    }
+
+    pub fn totient(self) -> Result<Number, ArithmeticError> {
+        let n = self.natural_arg("totient")?;
+        n.totient().map(Number::Natural).ok_or_else(|| out_of_reach(&n))
+    }
+
+    pub fn divisors(self) -> Result<Vec<Number>, ArithmeticError> {
+        let n = self.factors("divisors")?;
+        let divisors = n.divisors().ok_or_else(|| out_of_reach(&n))?;
+        Ok(divisors.into_iter().map(Number::Natural).collect())
+    }
+
+    pub fn isqrt(self) -> Result<Number, ArithmeticError> {
+        Ok(Number::Natural(self.natural_arg("isqrt")?.isqrt()))
+    }
+
+    pub fn iroot(self, k: Number) -> Result<Number, ArithmeticError> {
+        let n = self.natural_arg("iroot")?;
+        match k {
+            Number::Natural(ref k) if !k.value().is_zero() && k.value().bits() <= 32 => {
+                Ok(Number::Natural(n.iroot(k.value().low_u64() as u32)))
+            }
+            _ => Err(ArithmeticError::Domain("degree of a root must be a positive natural number".to_owned())),
+        }
+    }
+}
+
+fn no_inverse(a: &Number, modulus: &Natural) -> ArithmeticError {
+    ArithmeticError::Domain(format!("{} has no inverse modulo {}", a, modulus))
+}
+
+fn out_of_reach(n: &Natural) -> ArithmeticError {
+    ArithmeticError::Domain(format!("factors of {} are out of reach", n))
+}
@@ -12,16 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Complex, Integer, Natural, Rational, Real};
+use super::{Algebraic, Complex, Integer, Natural, Rational, Real};
 
+/// Conversion between the types of the tower, `None` when the value
+/// has no representation in `T`.
 pub trait AsNum<T> {
     fn as_num(&self) -> T {
-        let x = self.try_as_num();
-        if let Some(n) = x {
-            n
-        } else {
-            panic!("oops")
-        }
+        self.try_as_num().expect("value out of the range of the type")
     }
 
     fn try_as_num(&self) -> Option<T>;
@@ -43,8 +40,7 @@ pub trait Sqrt<T> {
 impl Sqrt<Real> for Real {
     fn try_sqrt(&self) -> Option<Real> {
         if self >= &Real::zero() {
-            let val = self.value().sqrt();
-            Some(real!(val))
+            Some(self.sqrt_abs())
         } else {
             None
         }
@@ -54,11 +50,9 @@ impl Sqrt<Real> for Real {
 impl Sqrt<Complex> for Real {
     fn try_sqrt(&self) -> Option<Complex> {
         Some(if self >= &Real::zero() {
-            let val = self.value().sqrt();
-            complex!(val)
+            Complex::new(self.sqrt_abs(), 0)
         } else {
-            let val = (-self.value()).sqrt();
-            complex!(val.i)
+            Complex::new(0, self.sqrt_abs())
         })
     }
 }
@@ -67,23 +61,67 @@ macro_rules! impl_as_num_self {
     ($($name:ty)*) => { $(impl_as_num_self!{ @impl $name })* };
     (@impl $name:ty) => {
         impl AsNum<$name> for $name {
-            fn try_as_num(&self) -> Option<$name> { Some(*self) }
+            fn try_as_num(&self) -> Option<$name> { Some(self.clone()) }
+        }
+    }
+}
+
+/// Conversions up the tower, which always succeed.
+macro_rules! impl_as_num_wider {
+    ($($name:ty => $($wider:ty)*;)*) => { $($(impl_as_num_wider!{ @impl $name; $wider })*)* };
+    (@impl $name:ty; $wider:ty) => {
+        impl AsNum<$wider> for $name {
+            fn try_as_num(&self) -> Option<$wider> { Some(self.clone().into()) }
+        }
+    }
+}
+
+/// Conversions down the tower by way of a type in between,
+/// `Rational => Integer => Natural` goes through `Integer`.
+macro_rules! impl_as_num_through {
+    ($($name:ident => $through:ident => $($narrower:ident)*;)*) => {
+        $($(impl_as_num_through!{ @impl $name; $through; $narrower })*)*
+    };
+    (@impl $name:ident; $through:ident; $narrower:ident) => {
+        impl AsNum<$narrower> for $name {
+            fn try_as_num(&self) -> Option<$narrower> {
+                AsNum::<$through>::try_as_num(self)?.try_as_num()
+            }
         }
     }
 }
 
-impl_as_num_self! { Natural Integer Rational Real Complex }
+impl_as_num_self! { Natural Integer Rational Algebraic Real Complex }
+
+impl_as_num_wider! {
+    Natural => Integer Rational Algebraic Real Complex;
+    Integer => Rational Algebraic Real Complex;
+    Rational => Algebraic Real Complex;
+    Algebraic => Real Complex;
+    Real => Complex;
+}
+
+impl_as_num_through! {
+    Rational => Integer => Natural;
+    Algebraic => Rational => Natural Integer;
+    Real => Rational => Natural Integer Algebraic;
+    Complex => Real => Natural Integer Rational Algebraic;
+}
 
 impl AsNum<Natural> for Integer {
     fn try_as_num(&self) -> Option<Natural> {
-        Some(self.abs())
+        if self.value().is_negative() {
+            None
+        } else {
+            Some(self.abs())
+        }
     }
 }
 
 impl AsNum<Integer> for Rational {
     fn try_as_num(&self) -> Option<Integer> {
-        if self.numer % self.denom == integer!(0) {
-            Some(self.numer / self.denom)
+        if self.numer.clone() % self.denom.clone() == integer!(0) {
+            Some(self.numer.clone() / self.denom.clone())
         } else {
             None
         }
@@ -92,10 +130,23 @@ impl AsNum<Integer> for Rational {
 
 impl AsNum<Real> for Complex {
     fn try_as_num(&self) -> Option<Real> {
-        if self.is_real() {
-            Some(self.real)
+        if self.imag.is_zero() {
+            Some(self.real.clone())
         } else {
             None
         }
     }
 }
+
+impl AsNum<Rational> for Algebraic {
+    fn try_as_num(&self) -> Option<Rational> {
+        self.to_rational()
+    }
+}
+
+/// A real is taken as the decimal it is shown as.
+impl AsNum<Rational> for Real {
+    fn try_as_num(&self) -> Option<Rational> {
+        self.to_decimal()
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2018 Vlad Yermakov
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ArithmeticError, Long, Number, Operation, Rational, Real};
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Measured value with a standard uncertainty, propagated to first order.
+///
+/// The uncertainty is kept as the contributions of independent sources
+/// of error, one for every measurement, so that correlated errors cancel:
+/// `x - x` is exact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uncertain {
+    value: f64,
+    sources: BTreeMap<u64, f64>,
+}
+
+thread_local! {
+    static NEXT_SOURCE: Cell<u64> = const { Cell::new(0) };
+}
+
+
+/// Derivatives of the elementary functions, for `f(x) ± |f'(x)| dx`.
+macro_rules! impl_differentiable {
+    ($($name:ident: $domain:expr, $derivative:expr),*) => {
+        impl Uncertain {
+            $(
+                pub fn $name(&self) -> Result<Uncertain, ArithmeticError> {
+                    let domain: fn(f64) -> bool = $domain;
+                    let derivative: fn(f64) -> f64 = $derivative;
+                    if !domain(self.value) {
+                        return Err(ArithmeticError::Domain(format!(
+                            "{} of {} is not real",
+                            stringify!($name),
+                            self
+                        )));
+                    }
+                    Ok(self.chain(self.value.$name(), derivative(self.value)))
+                }
+            )*
+        }
+    };
+}
+
+impl_differentiable! {
+    exp: |_| true, f64::exp,
+    ln: |x| x > 0.0, |x| 1.0 / x,
+    sin: |_| true, f64::cos,
+    cos: |_| true, |x| -x.sin(),
+    tan: |_| true, |x| 1.0 + x.tan() * x.tan(),
+    asin: |x| x.abs() <= 1.0, |x| 1.0 / (1.0 - x * x).sqrt(),
+    acos: |x| x.abs() <= 1.0, |x| -1.0 / (1.0 - x * x).sqrt(),
+    atan: |_| true, |x| 1.0 / (1.0 + x * x),
+    sinh: |_| true, f64::cosh,
+    cosh: |_| true, f64::sinh,
+    tanh: |_| true, |x| 1.0 - x.tanh() * x.tanh(),
+    asinh: |_| true, |x| 1.0 / (x * x + 1.0).sqrt(),
+    acosh: |x| x >= 1.0, |x| 1.0 / (x * x - 1.0).sqrt(),
+    atanh: |x| x.abs() < 1.0, |x| 1.0 / (1.0 - x * x),
+    sqrt: |x| x >= 0.0, |x| 0.5 / x.sqrt(),
+    cbrt: |_| true, |x| 1.0 / (3.0 * x.cbrt() * x.cbrt())
+}
+
+impl Uncertain {
+    /// A new measurement `value ± error`, independent of all others.
+    pub fn new(value: f64, error: f64) -> Uncertain {
+        Uncertain::measured(value, error, Uncertain::source())
+    }
+
+    /// Measurement `value ± error` with the error coming from `source`,
+    /// measurements of the same source are correlated.
+    pub fn measured(value: f64, error: f64, source: u64) -> Uncertain {
+        if error.is_nan() || error < 0.0 {
+            panic!("negative uncertainty {} in Uncertain", error)
+        }
+        let mut sources = BTreeMap::new();
+        if error > 0.0 {
+            sources.insert(source, error);
+        }
+        Uncertain { value, sources }
+    }
+
+    /// A source of error independent of all others.
+    pub fn source() -> u64 {
+        NEXT_SOURCE.with(|next| {
+            let source = next.get();
+            next.set(source + 1);
+            source
+        })
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Standard uncertainty, the sources add in quadrature.
+    pub fn error(&self) -> f64 {
+        self.sources.values().fold(0.0, |sum, part| sum.hypot(*part))
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.value.is_finite() && self.sources.values().all(|part| part.is_finite())
+    }
+
+    pub fn abs(&self) -> Uncertain {
+        self.chain(self.value.abs(), if self.value < 0.0 { -1.0 } else { 1.0 })
+    }
+
+    /// `self^exp`, `None` unless a negative base has an exact whole
+    /// exponent and a zero one an exact exponent.
+    pub fn powf(&self, exp: &Uncertain) -> Option<Uncertain> {
+        let (x, y) = (self.value, exp.value);
+        let whole = exp.is_exact() && y.fract() == 0.0;
+        if x < 0.0 && !whole || x == 0.0 && !exp.is_exact() {
+            return None;
+        }
+        let value = x.powf(y);
+        let base = if y == 0.0 { 0.0 } else { y * x.powf(y - 1.0) };
+        let power = if exp.is_exact() { 0.0 } else { x.ln() * value };
+        Some(self.linear(base, exp, power, value))
+    }
+
+    /// `f(self)` for a function with the derivative `slope` at `self`.
+    fn chain(&self, value: f64, slope: f64) -> Uncertain {
+        self.linear(slope, &Uncertain::from(0.0), 0.0, value)
+    }
+
+    /// Sources of `a * self + b * other` around `value`, the ones that
+    /// cancel are dropped.
+    fn linear(&self, a: f64, other: &Uncertain, b: f64, value: f64) -> Uncertain {
+        let mut sources = BTreeMap::new();
+        for (source, part) in &self.sources {
+            sources.insert(*source, a * part);
+        }
+        for (source, part) in &other.sources {
+            *sources.entry(*source).or_insert(0.0) += b * part;
+        }
+        sources.retain(|_, part| *part != 0.0);
+        Uncertain { value, sources }
+    }
+}
+
+/// The uncertainty is rounded to one significant digit and the value to
+/// the same place, `3.14 ± 0.01`.
+impl Display for Uncertain {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let error = self.error();
+        if error == 0.0 || !error.is_finite() || !self.value.is_finite() {
+            let show = |x: f64| if x.is_nan() { "nan".to_owned() } else { x.to_string() };
+            return write!(f, "{} ± {}", show(self.value), show(error));
+        }
+        let rounded = format!("{:.0e}", error);
+        let (digit, exponent) = rounded.split_at(1);
+        let exponent: i32 = exponent[1..].parse().unwrap();
+        let (value, error) = if exponent < 0 {
+            let places = exponent.unsigned_abs() as usize;
+            let error = format!("0.{}{}", "0".repeat(places - 1), digit);
+            (format!("{:.*}", places, self.value), error)
+        } else {
+            let scale = 10f64.powi(exponent);
+            let value = (self.value / scale).round() * scale;
+            (value.to_string(), digit.to_owned() + &"0".repeat(exponent as usize))
+        };
+        match value.trim_start_matches(['-', '0', '.']).is_empty() {
+            true => write!(f, "{} ± {}", value.trim_start_matches('-'), error),
+            false => write!(f, "{} ± {}", value, error),
+        }
+    }
+}
+
+impl From<f64> for Uncertain {
+    fn from(value: f64) -> Uncertain {
+        Uncertain::new(value, 0.0)
+    }
+}
+
+impl From<Long> for Uncertain {
+    fn from(value: Long) -> Uncertain {
+        Uncertain::from(value.to_f64())
+    }
+}
+
+impl From<Rational> for Uncertain {
+    fn from(rat: Rational) -> Uncertain {
+        Uncertain::from(rat.value())
+    }
+}
+
+impl Neg for Uncertain {
+    type Output = Uncertain;
+
+    fn neg(self) -> Uncertain {
+        self.chain(-self.value, -1.0)
+    }
+}
+
+impl Add for Uncertain {
+    type Output = Uncertain;
+
+    fn add(self, other: Uncertain) -> Uncertain {
+        self.linear(1.0, &other, 1.0, self.value + other.value)
+    }
+}
+
+impl Sub for Uncertain {
+    type Output = Uncertain;
+
+    fn sub(self, other: Uncertain) -> Uncertain {
+        self.linear(1.0, &other, -1.0, self.value - other.value)
+    }
+}
+
+impl Mul for Uncertain {
+    type Output = Uncertain;
+
+    fn mul(self, other: Uncertain) -> Uncertain {
+        self.linear(other.value, &other, self.value, self.value * other.value)
+    }
+}
+
+impl Div for Uncertain {
+    type Output = Uncertain;
+
+    fn div(self, other: Uncertain) -> Uncertain {
+        let (x, y) = (self.value, other.value);
+        self.linear(1.0 / y, &other, -x / (y * y), x / y)
+    }
+}
+
+#[macro_export]
+macro_rules! uncertain {
+    ($value:expr, $error:expr) => {
+        $crate::numbers::Uncertain::new($value as f64, $error as f64)
+    };
+    ($value:expr) => {
+        $crate::numbers::Uncertain::from($value)
+    };
+}
+
+impl Number {
+    /// The measurement `value ± error` with the error coming from
+    /// `source`, an uncertain `value` gains it on top of its own.
+    pub fn uncertain(value: Number, error: Number, source: u64) -> Result<Number, ArithmeticError> {
+        let error = match error.to_real() {
+            Some(error) if !error.is_negative() => error.value(),
+            Some(_) => return Err(ArithmeticError::Domain("uncertainty must not be negative".to_owned())),
+            None => return Err(ArithmeticError::Domain("uncertainty must be real".to_owned())),
+        };
+        match value.to_uncertain() {
+            Some(value) => Ok(Number::Uncertain(value + Uncertain::measured(0.0, error, source))),
+            None => Err(ArithmeticError::Domain(format!(
+                "{} numbers can't be measured",
+                value.kind()
+            ))),
+        }
+    }
+
+    pub(crate) fn is_uncertain(&self) -> bool {
+        matches!(self, Number::Uncertain(_))
+    }
+
+    /// A real number as a measurement without error, `None` for complex
+    /// numbers, intervals and residues.
+    pub(crate) fn to_uncertain(&self) -> Option<Uncertain> {
+        match self.clone() {
+            Number::Natural(nat) => Some(nat.into()),
+            Number::Integer(int) => Some(int.into()),
+            Number::Decimal(dec) => Some(dec.into()),
+            Number::Rational(rat) => Some(rat.into()),
+            Number::Algebraic(alg) => Some(alg.into()),
+            Number::Real(rea) => Some(rea.into()),
+            Number::Uncertain(unc) => Some(unc),
+            _ => None,
+        }
+    }
+
+    /// Measured value without its uncertainty, a real number is its own.
+    pub fn nominal(self) -> Result<Number, ArithmeticError> {
+        match self {
+            Number::Uncertain(unc) => Ok(Number::Real(Real::new(unc.value))),
+            number => number.measure("nominal", |_| number.clone()),
+        }
+    }
+
+    pub fn uncertainty(self) -> Result<Number, ArithmeticError> {
+        match self {
+            Number::Uncertain(unc) => Ok(Number::Real(Real::new(unc.error()))),
+            number => number.measure("uncertainty", |_| Number::Natural(natural!(0))),
+        }
+    }
+
+    fn measure(&self, name: &str, f: impl Fn(&Number) -> Number) -> Result<Number, ArithmeticError> {
+        match self.to_real() {
+            Some(_) => Ok(f(self)),
+            None => Err(ArithmeticError::Domain(format!(
+                "{} is not defined for {} numbers",
+                name,
+                self.kind()
+            ))),
+        }
+    }
+}
+
+fn mixed(a: &Number, b: &Number) -> ArithmeticError {
+    let other = if a.is_uncertain() { b } else { a };
+    ArithmeticError::Domain(format!("{} numbers do not combine with Uncertain numbers", other.kind()))
+}
+
+/// Checks operands of an operation with a measurement that the `Number`
+/// operators can't turn into one.
+pub(crate) fn check_uncertain(operation: Operation, a: &Number, b: &Number) -> Result<(), ArithmeticError> {
+    let divisor = match (a.to_uncertain(), b.to_uncertain()) {
+        (Some(_), Some(divisor)) => divisor,
+        _ => return Err(mixed(a, b)),
+    };
+    match operation {
+        Operation::Division if divisor.value == 0.0 => Err(ArithmeticError::DivisionByZero),
+        Operation::Remainder => Err(ArithmeticError::Domain(
+            "remainder is not defined for Uncertain numbers".to_owned(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Measurements are ordered by their values.
+pub(crate) fn uncertain_cmp(a: &Number, b: &Number) -> Result<Ordering, ArithmeticError> {
+    match (a.to_uncertain(), b.to_uncertain()) {
+        (Some(x), Some(y)) => Real::new(x.value).partial_cmp(&Real::new(y.value)).ok_or_else(|| {
+            ArithmeticError::Domain(format!("comparison of {} and {} is not defined", a, b))
+        }),
+        _ => Err(mixed(a, b)),
+    }
+}
+
+/// Power with a measured base or exponent.
+pub(crate) fn uncertain_pow(base: Number, exp: Number) -> Result<Number, ArithmeticError> {
+    match (base.to_uncertain(), exp.to_uncertain()) {
+        (Some(x), Some(y)) => x.powf(&y).map(Number::Uncertain).ok_or_else(|| {
+            ArithmeticError::Domain(format!("{} raised to {} is not real", base, exp))
+        }),
+        _ => Err(mixed(&base, &exp)),
+    }
+}
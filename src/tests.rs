@@ -80,6 +80,15 @@ fn test_div() {
     assert_eq!("1", interpret(text))
 }
 
+#[test]
+fn test_big_numbers() {
+    let text = "170141183460469231731687303715884105727 * 170141183460469231731687303715884105727";
+    assert_eq!(
+        "28948022309329048855892746252171976962977213799489202546401021394546514198529",
+        interpret(text)
+    );
+}
+
 #[test]
 fn test_long() {
     let text = " 2 + 2 * 2 - 4 / 2 ";
@@ -105,12 +114,12 @@ fn test_ops() {
 
     fn test_add_rationals() {
         let text = "3//8 + 5//8";
-        assert_eq!("1 / 1", interpret(text));
+        assert_eq!("1", interpret(text));
     }
 
     fn test_add_rational_complex() {
         let text = "3//4 + 2i";
-        assert_eq!("0.75 + 2i", interpret(text));
+        assert_eq!("3 / 4 + 2i", interpret(text));
     }
 
     fn test_sub_complex() {
@@ -135,12 +144,12 @@ fn test_ops() {
 
     fn test_sub_rational_complex() {
         let text = "3//4 - 2i";
-        assert_eq!("0.75 - 2i", interpret(text));
+        assert_eq!("3 / 4 - 2i", interpret(text));
     }
 
     fn test_mul_complex() {
         let text = "(2 + 3i) * (2 - 3i)";
-        assert_eq!("13 + 0i", interpret(text));
+        assert_eq!("13", interpret(text));
     }
 
     fn test_mul_rational() {
@@ -155,12 +164,12 @@ fn test_ops() {
 
     fn test_mul_rational_complex() {
         let text = "3//4 * (1 + 2i)";
-        assert_eq!("0.75 + 1.5i", interpret(text));
+        assert_eq!("3 / 4 + (3 / 2)i", interpret(text));
     }
 
     fn test_div_complex() {
         let text = "(1 + 3i) / 2";
-        assert_eq!("0.5 + 1.5i", interpret(text));
+        assert_eq!("1 / 2 + (3 / 2)i", interpret(text));
     }
 
     fn test_div_rational() {
@@ -204,6 +213,711 @@ fn test_ops() {
     test_mul_real();
 }
 
+#[test]
+fn test_precision() {
+    let mut interpreter = Interpreter::with_text(":precision 50");
+    assert_eq!("# precision 50", interpreter.interpret());
+
+    interpreter.append_text("1 / 3.0");
+    assert_eq!(
+        "< 0.33333333333333333333333333333333333333333333333333",
+        interpreter.interpret()
+    );
+
+    interpreter.append_text("0.1 + 0.2");
+    assert_eq!("< 0.3", interpreter.interpret());
+
+    interpreter.append_text("2//3 * 1.5");
+    assert_eq!("< 1", interpreter.interpret());
+
+    interpreter.append_text("2.0 ** 0.5");
+    assert_eq!(
+        "< 1.4142135623730950488016887242096980785696718753769",
+        interpreter.interpret()
+    );
+
+    interpreter.append_text("sin(1)");
+    assert_eq!(
+        "< 0.84147098480789650665250232163029899962256306079837",
+        interpreter.interpret()
+    );
+
+    interpreter.append_text("exp(1)");
+    assert_eq!("< 2.7182818284590452353602874713526624977572470937", interpreter.interpret());
+
+    interpreter.append_text("4 * atan(1)");
+    assert_eq!(
+        "< 3.1415926535897932384626433832795028841971693993751",
+        interpreter.interpret()
+    );
+
+    interpreter.append_text("cis(1)");
+    assert_eq!(
+        "< 0.54030230586813971740093660744297660373231042061792 + 0.84147098480789650665250232163029899962256306079837i",
+        interpreter.interpret()
+    );
+
+    interpreter.append_text("cos(1e22)");
+    assert_eq!(
+        "< 0.52321478539513894549759447338470949214091997243939",
+        interpreter.interpret()
+    );
+
+    interpreter.append_text("-1.0 * 0.0");
+    assert_eq!("< -0", interpreter.interpret());
+
+    interpreter.append_text("1 / -0.0");
+    assert_eq!("< -inf", interpreter.interpret());
+
+    interpreter.append_text("is_finite(atanh(1.0))");
+    assert_eq!("< false", interpreter.interpret());
+
+    interpreter.append_text(":precision double");
+    assert_eq!("# precision double", interpreter.interpret());
+
+    interpreter.append_text("0.1 + 0.2");
+    assert_eq!("< 0.30000000000000004", interpreter.interpret());
+
+    interpreter.append_text(":precision many");
+    assert_eq!("# bad precision many", interpreter.interpret());
+
+    interpreter.append_text(":precision 100000");
+    assert_eq!("# precision 100000 is above the limit of 10000 digits", interpreter.interpret());
+}
+
+#[test]
+fn test_separate_sessions() {
+    let mut first = Interpreter::new();
+    let mut second = Interpreter::new();
+    let check = |interpreter: &mut Interpreter, text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+
+    assert_eq!("# precision 30", check(&mut first, ":precision 30"));
+    assert_eq!("# tolerance strict", check(&mut first, ":tolerance strict"));
+    assert_eq!("< 0.30000000000000004", check(&mut second, "0.1 + 0.2"));
+    assert_eq!("< 1", check(&mut second, "if 0.1 + 0.2 == 0.3 { 1 } else { 0 }"));
+    assert_eq!("< 0.333333333333333333333333333333", check(&mut first, "1 / 3.0"));
+    assert_eq!("# precision double", check(&mut second, ":precision"));
+    assert_eq!("# precision 30", check(&mut first, ":precision"));
+
+    assert_eq!("# format mixed", check(&mut first, ":format mixed"));
+    assert_eq!("# decimal on, scale 20, rounding half-even", check(&mut first, ":decimal on"));
+    assert_eq!("# decimal on, scale 2, rounding half-even", check(&mut first, ":decimal scale 2"));
+    assert_eq!("< 7 / 2", check(&mut second, "7/2"));
+    assert_eq!("< 0.3333333333333333", check(&mut second, "1 / 3.0"));
+    assert_eq!("# format default", check(&mut second, ":format"));
+    assert_eq!("# decimal off, scale 20, rounding half-even", check(&mut second, ":decimal"));
+    assert_eq!("< 3 1/2", check(&mut first, "7/2"));
+    assert_eq!("< 33/100", check(&mut first, "1 / 3.0"));
+
+    assert_eq!("# strict on", check(&mut first, ":strict on"));
+    assert_eq!("< inf", check(&mut second, "1 / 0.0"));
+    assert_eq!("! division by zero", check(&mut first, "1 / 0.0"));
+}
+
+#[test]
+fn test_promotion() {
+    assert_eq!("-3", interpret("2 - 5"));
+    assert_eq!("2", interpret("6 / 3"));
+    assert_eq!("7 / 2", interpret("7 / 2"));
+    assert_eq!("-5 / 3", interpret("(1 - 6) / 3"));
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!("2", interpret("4//2"));
+    assert_eq!("1", interpret("(1 + 2i) - 2i"));
+    assert_eq!("-2", interpret("1 - 6//2"));
+    assert_eq!("3", interpret("(2 - 5) * (0 - 1)"));
+}
+
+#[test]
+fn test_pow() {
+    assert_eq!("1024", interpret("2 ** 10"));
+    assert_eq!("512", interpret("2 ** 3 ** 2"));
+    assert_eq!("-4", interpret("-2 ** 2"));
+    assert_eq!("1 / 4", interpret("2 ** -2"));
+    assert_eq!("12", interpret("3 * 2 ** 2"));
+    assert_eq!("8 / 27", interpret("(2//3) ** 3"));
+    assert_eq!("1.4142135623730951", interpret("2.0 ** 0.5"));
+    assert_eq!("2", interpret("4 ** (1//2)"));
+    assert_eq!("0 + 2i", interpret("(0 - 4) ** (1//2)"));
+    assert_eq!("0 - 8i", interpret("(0 - 4) ** (3//2)"));
+    assert_eq!("0 - (1 / 2)i", interpret("(0 - 4) ** (-1//2)"));
+    assert_eq!("0 + (3 / 2)i", interpret("(0 - 9//4) ** (1//2)"));
+    assert_eq!("0 + 1.4142135623730951i", interpret("(0 - 2) ** (1//2)"));
+    assert_eq!("1.0000000000000002 + 1.7320508075688772i", interpret("(0 - 8) ** (1//3)"));
+    assert_eq!("-1", interpret("1i ** 2"));
+    assert_eq!("division by zero", interpret("0 ** -1"));
+}
+
+#[test]
+fn test_builtins() {
+    assert_eq!("0", interpret("sin(0)"));
+    assert_eq!("3", interpret("2 * cos(0) + 1"));
+    assert_eq!("3 / 2", interpret("sqrt(9//4)"));
+    assert_eq!("0 + 2i", interpret("sqrt(-4)"));
+    assert_eq!("0 + 3.141592653589793i", interpret("ln(-1)"));
+    assert_eq!("3", interpret("log(8, 2)"));
+    assert_eq!("5", interpret("abs(3 - 4i)"));
+    assert_eq!("-inf", interpret("ln(0)"));
+
+    let mut interpreter = Interpreter::new();
+    interpreter.append_text("fn abs(x) { 0 - x }");
+    interpreter.interpret();
+    interpreter.append_text("abs(2)");
+    assert_eq!("< -2", interpreter.interpret());
+
+    interpreter.append_text("sin(1, 2)");
+    assert_eq!("! sin takes 1 argument, found 2", interpreter.interpret());
+    interpreter.append_text("modpow(1)");
+    assert_eq!("! modpow takes 3 arguments, found 1", interpreter.interpret());
+    interpreter.append_text("log()");
+    assert_eq!("! log takes 1 or 2 arguments, found 0", interpreter.interpret());
+    interpreter.append_text("sin(0)");
+    assert_eq!("< 0", interpreter.interpret());
+}
+
+#[test]
+fn test_polar() {
+    assert_eq!("2", interpret("2 cis 0"));
+    assert_eq!("-3", interpret("re(3 cis atan2(0, -1))"));
+    assert_eq!("1", interpret("abs(5 cis 1) / 5"));
+    assert_eq!("3.141592653589793", interpret("arg(-1)"));
+    assert_eq!("0.8775825618903728 + 0.479425538604203i", interpret("cis(0.5)"));
+    assert_eq!("-1", interpret("re(2 cis (atan2(0, -1)) + cis(0))"));
+    assert_eq!("-4", interpret("im(3 - 4i)"));
+}
+
+#[test]
+fn test_division() {
+    assert_eq!("-1", interpret("-7 % 3"));
+    assert_eq!("2", interpret("2 * 7 % 4"));
+    assert_eq!("2", interpret("mod(-7, 3)"));
+    assert_eq!("-3", interpret("div(-7, 3)"));
+    assert_eq!("-3", interpret("floor_div(7, -3)"));
+    assert_eq!("-1", interpret("rem(-7, 3)"));
+    assert_eq!("(-3, 2)", interpret("divmod(-7, 3)"));
+    assert_eq!("(-2, 0.5)", interpret("divmod(-3.5, 2)"));
+    assert_eq!("divmod gives several numbers", interpret("1 + divmod(1, 2)"));
+    assert_eq!("division by zero", interpret("mod(5, 0)"));
+}
+
+#[test]
+fn test_rounding() {
+    assert_eq!("-3", interpret("floor(-2.5)"));
+    assert_eq!("-2", interpret("ceil(-2.5)"));
+    assert_eq!("-2", interpret("trunc(-2.7)"));
+    assert_eq!("-0.75", interpret("frac(-2.75)"));
+    assert_eq!("2", interpret("round(2.5)"));
+    assert_eq!("4", interpret("round(3.5)"));
+    assert_eq!("-2", interpret("round(-5/2)"));
+    assert_eq!("4", interpret("ceil(7/2)"));
+    assert_eq!("-3", interpret("trunc(-7/2)"));
+    assert_eq!("-1 / 2", interpret("frac(-7/2)"));
+    assert_eq!("14", interpret("round(10 * sqrt(2))"));
+    assert_eq!("-1 + sqrt(2)", interpret("frac(sqrt(2))"));
+    assert_eq!("0.75", interpret("frac(2.75d)"));
+    assert_eq!("[-1, 2]", interpret("trunc([-1.5, 2.5])"));
+    assert_eq!("[0, 2]", interpret("round([0.5, 2.5])"));
+    assert_eq!("frac is not defined for Interval numbers", interpret("frac([1, 2])"));
+    assert_eq!("round is not defined for Uncertain numbers", interpret("round(1 ± 0.1)"));
+    assert_eq!("ceil is not defined for Complex numbers", interpret("ceil(1 + 1i)"));
+}
+
+#[test]
+fn test_conversion() {
+    assert_eq!("2", interpret("nat(2.0)"));
+    assert_eq!("-3", interpret("int(-3.0)"));
+    assert_eq!("100000000000000000000", interpret("int(1e20)"));
+    assert_eq!("1 / 10", interpret("rat(0.1)"));
+    assert_eq!("5 / 2", interpret("rat(2.5d)"));
+    assert_eq!("0.3333333333333333", interpret("real(1/3)"));
+    assert_eq!("1.4142135623730951", interpret("real(sqrt(2))"));
+    assert_eq!("0.5 + 1i", interpret("complex(1//2 + 1i)"));
+    assert_eq!("2 + 0i", interpret("complex(2)"));
+    assert_eq!("2", interpret("rat(2)"));
+    assert_eq!(format!("1{}", "0".repeat(300)), interpret("rat(1e300)"));
+    assert_eq!("3", interpret("complex(2) + 1"));
+    assert_eq!("cannot convert -3 to Natural", interpret("nat(-3)"));
+    assert_eq!("cannot convert 7 / 2 to Integer", interpret("int(7/2)"));
+    assert_eq!("cannot convert sqrt(2) to Rational", interpret("rat(sqrt(2))"));
+    assert_eq!("cannot convert 1 + 1i to Real", interpret("real(1 + 1i)"));
+    assert_eq!("cannot convert [1, 2] to Complex", interpret("complex([1, 2])"));
+}
+
+#[test]
+fn test_exact_comparison() {
+    fn check(text: &str) -> Option<bool> {
+        let mut interpreter = Interpreter::with_text(text);
+        interpreter.parser.condition().is_true(BTreeMap::new())
+    }
+
+    assert_eq!(check("10 ** 17 + 1 == 10 ** 17"), Some(false));
+    assert_eq!(check("10 ** 17 + 1 > 10 ** 17"), Some(true));
+    assert_eq!(check("1 < (10 ** 30 + 1) / 10 ** 30"), Some(true));
+    assert_eq!(check("1//3 == 2//6"), Some(true));
+    assert_eq!(check("0.1 + 0.2 == 0.3"), Some(true));
+    assert_eq!(check("2i == 2i"), Some(true));
+    assert_eq!(check("2i < 3i"), None);
+
+    let text = "if 1i < 2 { 1 } else { 2 }";
+    assert_eq!("cannot order complex numbers", interpret(text));
+}
+
+#[test]
+fn test_exact_complex() {
+    assert_eq!("1 / 3 + 2i", interpret("(1//3) + 2i"));
+    assert_eq!("11 + 2i", interpret("(1 + 2i) * (3 - 4i)"));
+    assert_eq!("1 / 2 - (1 / 2)i", interpret("1 / (1 + 1i)"));
+    assert_eq!("16", interpret("(1 + 1i) ** 8"));
+    assert_eq!("0 - (1 / 2)i", interpret("(1 + 1i) ** -2"));
+    assert_eq!("0 + 3i", interpret("sqrt(-9)"));
+    assert_eq!("5", interpret("abs(3 + 4i)"));
+    assert_eq!("1 / 2", interpret("re(1//2 + 3i)"));
+    assert_eq!("1 / 2 + (3 / 4)i", interpret("conj(1//2 - 3//4 * 1i)"));
+    assert_eq!("1.5 + 2i", interpret("(1 + 2i) + 0.5"));
+    assert_eq!("remainder is not defined for Complex numbers", interpret("(1 + 2i) % 2"));
+}
+
+#[test]
+fn test_decimal() {
+    assert_eq!("0.3", interpret("0.1d + 0.2d"));
+    assert_eq!("2.50", interpret("1.50d + 1"));
+    assert_eq!("2.50", interpret("10.00d / 4"));
+    assert_eq!("0.33333333333333333333", interpret("1d / 3"));
+    assert_eq!("0.0015", interpret("1.5e-3d"));
+    assert_eq!("13 / 30", interpret("0.1d + 1//3"));
+    assert_eq!("0.6", interpret("0.1d + 0.5"));
+    assert_eq!("-1.5", interpret("-7.5d % 2"));
+    assert_eq!("0.00", interpret("1.50d - 1.50d"));
+    assert_eq!("2", interpret("quantize(2.5d, 0)"));
+    assert_eq!("4", interpret("quantize(3.5d, 0)"));
+    assert_eq!("2.68", interpret("quantize(2.675, 2)"));
+    assert_eq!("1", interpret("if 0.1d + 0.2d == 0.3d { 1 } else { 0 }"));
+
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+    assert_eq!("# decimal off, scale 20, rounding half-even", check(":decimal"));
+    assert_eq!("! bad number 1e999999d: exponent too large for an exact number", check("1e999999d"));
+    assert_eq!("< 0.30000000000000004", check("0.1 + 0.2"));
+    assert_eq!("# decimal on, scale 20, rounding half-even", check(":decimal on"));
+    assert_eq!("< 0.3", check("0.1 + 0.2"));
+    assert_eq!("# decimal on, scale 2, rounding half-even", check(":decimal scale 2"));
+    assert_eq!("< 0.33", check("1 / 3.00"));
+    assert_eq!("< 1.00", check("1.005 * 1"));
+    assert_eq!("# decimal on, scale 2, rounding half-up", check(":decimal rounding half-up"));
+    assert_eq!("< 1.01", check("1.005 * 1"));
+    assert_eq!("# decimal on, scale 2, rounding down", check(":decimal rounding down"));
+    assert_eq!("< -0.66", check("-2 / 3.0"));
+    assert_eq!("# bad rounding up", check(":decimal rounding up"));
+    assert_eq!("# bad scale many", check(":decimal scale many"));
+    assert_eq!("# decimal off, scale 2, rounding down", check(":decimal off"));
+}
+
+#[test]
+fn test_algebraic() {
+    assert_eq!("2*sqrt(2)", interpret("sqrt(8)"));
+    assert_eq!("3*sqrt(2)", interpret("sqrt(2) + sqrt(8)"));
+    assert_eq!("2", interpret("sqrt(2) * sqrt(2)"));
+    assert_eq!("3 + 2*sqrt(2)", interpret("(1 + sqrt(2)) ** 2"));
+    assert_eq!("-1 + sqrt(2)", interpret("1 / (1 + sqrt(2))"));
+    assert_eq!("-1 + cbrt(2)", interpret("(1 + cbrt(2) + cbrt(4)) ** -1"));
+    assert_eq!("sqrt(2) / 2", interpret("sqrt(1/2)"));
+    assert_eq!("-2*cbrt(2)", interpret("cbrt(-16)"));
+    assert_eq!("sqrt(2)*cbrt(5)", interpret("sqrt(2) * cbrt(5)"));
+    assert_eq!("2*root(2, 4)", interpret("root(32, 4)"));
+    assert_eq!("-2", interpret("root(-32, 5)"));
+    assert_eq!("root(2, 4)", interpret("sqrt(sqrt(2))"));
+    assert_eq!("sqrt(2)", interpret("2 ** (1/2)"));
+    assert_eq!("4", interpret("8 ** (2/3)"));
+    assert_eq!("100000000000000000000*sqrt(2)", interpret("sqrt(2 * 10 ** 40)"));
+    assert_eq!("0 + 1.4142135623730951i", interpret("sqrt(-2)"));
+    assert_eq!("1.9142135623730951", interpret("sqrt(2) + 0.5"));
+    assert_eq!("1", interpret("if sqrt(2) * sqrt(3) == sqrt(6) { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if sqrt(3) - sqrt(2) < 1//3 { 1 } else { 0 }"));
+
+    assert_eq!("1.41421356237309504880168872420969807856967187537695", interpret("fixed(sqrt(2), 50)"));
+    assert_eq!("1.7099759466766969894e0", interpret("sci(cbrt(5), 20)"));
+    assert_eq!("1.41421", interpret("quantize(sqrt(2), 5)"));
+    assert_eq!("(1, 0, -10, 0, 1)", interpret("minpoly(sqrt(2) + sqrt(3))"));
+    assert_eq!("(1, 3, 1, 5, 1, 1, 4, 1, 1, 8)", interpret("cfrac(cbrt(2), 10)"));
+    assert_eq!("1393 / 985", interpret("rationalize(sqrt(2), 1000)"));
+    assert_eq!("7", interpret("floor_div(10, sqrt(2))"));
+    assert_eq!("minimal polynomial is not defined for Real numbers", interpret("minpoly(1.5)"));
+    assert_eq!("index of a root must be a natural number from 1 to 64", interpret("root(2, 0)"));
+    assert_eq!("Algebraic numbers do not combine with Modular numbers", interpret("modular(sqrt(2), 5)"));
+}
+
+#[test]
+fn test_interval() {
+    assert_eq!("[4, 6]", interpret("[1, 2] + [3, 4]"));
+    assert_eq!("[-6, 8]", interpret("[1, 2] * [-3, 4]"));
+    assert_eq!("[0.25, 0.5]", interpret("1 / [2, 4]"));
+    assert_eq!("[0.3333333333333333, 0.33333333333333337]", interpret("[1//3, 1//3]"));
+    assert_eq!("[0, 9]", interpret("[-2, 3] ** 2"));
+    assert_eq!("[0, 5e-324]", interpret("[0.5, 0.5] ** 1000000"));
+    assert_eq!("[2, 3]", interpret("sqrt([4, 9])"));
+    assert_eq!("[-0.7568024953079283, 1]", interpret("sin([0, 4])"));
+    assert_eq!("[0, 0.6931471805599454]", interpret("ln([1, 2])"));
+    assert_eq!("0.5", interpret("width([1, 1.5])"));
+    assert_eq!("2", interpret("mid([1, 3])"));
+
+    assert_eq!("divisor [-1, 1] contains zero", interpret("1 / [-1, 1]"));
+    assert_eq!("sqrt is not defined on all of [-1, 4]", interpret("sqrt([-1, 4])"));
+    assert_eq!("tan is unbounded on [1, 2]", interpret("tan([1, 2])"));
+    assert_eq!("lower bound 3 is above upper bound 1", interpret("[3, 1]"));
+    assert_eq!("Complex numbers do not combine with Interval numbers", interpret("[1, 2] + 1i"));
+    assert_eq!("overflow in Interval multiplication", interpret("[1e308, 1e308] * 10"));
+}
+
+#[test]
+fn test_interval_comparisons() {
+    assert_eq!("1", interpret("if [1, 2] < [3, 4] { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if [2, 2] == 2 { 1 } else { 0 }"));
+    assert_eq!("comparison of [1, 3] and 2 is uncertain", interpret("if [1, 3] < 2 { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if possibly [1, 3] < 2 { 1 } else { 0 }"));
+    assert_eq!("0", interpret("if certainly [1, 3] < 2 { 1 } else { 0 }"));
+    assert_eq!("0", interpret("if certainly [1, 3] < 4 & [1, 2] == 1 { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if possibly [1, 3] < 4 & [1, 2] == 1 { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if certainly !([1, 2] > 3) { 1 } else { 0 }"));
+}
+
+#[test]
+fn test_uncertain() {
+    assert_eq!("9.81 ± 0.02", interpret("9.81 ± 0.02"));
+    assert_eq!("3.14 ± 0.01", interpret("3.14159 +/- 0.0123"));
+    assert_eq!("19.62 ± 0.04", interpret("2 * 9.81 ± 0.02"));
+    assert_eq!("0.84 ± 0.05", interpret("sin(1 ± 0.1)"));
+    assert_eq!("8 ± 1", interpret("(2 ± 0.1) ** 3"));
+    assert_eq!("8.0 ± 0.6", interpret("2 ** (3 ± 0.1)"));
+    assert_eq!("2.000 ± 0.004", interpret("log(100 ± 1)"));
+    assert_eq!("1230 ± 30", interpret("1234 ± 30"));
+    assert_eq!("0.0 ± 0.1", interpret("2 ± 0.1 - 2 ± 0.1"));
+    assert_eq!("0.1", interpret("uncertainty(2 ± 0.1)"));
+    assert_eq!("2", interpret("nominal(2 ± 0.1)"));
+
+    assert_eq!("division by zero", interpret("1 / (0 ± 0.1)"));
+    assert_eq!("uncertainty must not be negative", interpret("1 ± -1"));
+    assert_eq!("ln of -1.0 ± 0.1 is not real", interpret("ln(-1 ± 0.1)"));
+    assert_eq!("-8.0 ± 0.1 raised to 1 / 3 is not real", interpret("(-8 ± 0.1) ** (1/3)"));
+    assert_eq!("Complex numbers do not combine with Uncertain numbers", interpret("(1 ± 0.1) + 1i"));
+    assert_eq!("Uncertain numbers do not combine with Interval numbers", interpret("(1 ± 0.1) + [1, 2]"));
+    assert_eq!("remainder is not defined for Uncertain numbers", interpret("(1 ± 0.1) % 2"));
+
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+    check("fn cancel(x) { x - x }");
+    check("fn square(x) { x * x }");
+    check("fn product(x, y) { x * y }");
+    assert_eq!("< 0 ± 0", check("cancel(2 ± 0.1)"));
+    assert_eq!("< 4.0 ± 0.4", check("square(2 ± 0.1)"));
+    assert_eq!("< 6.0 ± 0.5", check("product(2 ± 0.1, 3 ± 0.2)"));
+}
+
+#[test]
+fn test_derivative() {
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+    check("fn cube(x) { x ** 3 + 2 * x }");
+    check("fn product(x, y) { x * y + sin(x) }");
+    check("fn power(x) { x ** x }");
+    check("fn piecewise(x) { if x < 0 { -x } else { x * x } }");
+    check("fn angle(x, y) { atan2(y, x) }");
+    check("fn logarithm(x) { ln(x) }");
+    check("fn nested(x) { diff(logarithm, x) }");
+    check("fn whole(x) { gcd(x, 4) }");
+    check("fn constant(x) { 5 }");
+    check("fn fractional(x) { frac(x) + round(x) }");
+    assert_eq!("< 14", check("diff(cube, 2)"));
+    assert_eq!("< 15", check("diff(cube, 2) + 1"));
+    assert_eq!("< (4, 0)", check("grad(product, 0, 3)"));
+    assert_eq!("< 6.772588722239782", check("diff(power, 2)"));
+    assert_eq!("< -1", check("diff(piecewise, -3)"));
+    assert_eq!("< 6", check("diff(piecewise, 3)"));
+    assert_eq!("< (-0.5, 0.5)", check("grad(angle, 1, 1)"));
+    assert_eq!("< 0.5", check("diff(logarithm, 2)"));
+    assert_eq!("< 0", check("diff(constant, 1)"));
+    assert_eq!("< 1", check("diff(fractional, 2.3)"));
+    assert_eq!("! ln of -1 is not real", check("diff(logarithm, -1)"));
+    assert_eq!("! derivatives can't be nested", check("diff(nested, 2)"));
+    assert_eq!("! gcd can't be differentiated", check("diff(whole, 2)"));
+    assert_eq!("! Complex numbers can't be differentiated", check("diff(logarithm, 1i)"));
+    assert_eq!("! grad gives several numbers", check("grad(product, 2, 3) + 1"));
+    assert_eq!("! diff needs a function of one argument, product takes 2", check("diff(product, 1, 2)"));
+    assert_eq!("! function product takes 2 arguments, found 1", check("grad(product, 1)"));
+    assert_eq!("! function missing is not exist", check("diff(missing, 1)"));
+    assert_eq!("! diff needs the name of a function", check("diff(2, 1)"));
+    assert_eq!("! grad needs the name of a function", check("grad(cube + 1, 1)"));
+    assert_eq!("! function cube takes 1 argument, found 2", check("grad(cube, 1, 2)"));
+    assert_eq!("< 1", check("diff(sin, 0)"));
+    assert_eq!("< (0.5, -0.5)", check("grad(atan2, 1, 1)"));
+    assert_eq!("< (0.18033688011112042, -2.1640425613334453)", check("grad(log, 8, 2)"));
+    assert_eq!("! gcd can't be differentiated", check("diff(gcd, 1)"));
+    assert_eq!("! fraction can't be differentiated", check("diff(fraction, 1)"));
+    check("fn sin(x) { 2 * x }");
+    assert_eq!("< 2", check("diff(sin, 0)"));
+    assert_eq!("< 14", check("diff(cube, 2)"));
+}
+
+#[test]
+fn test_arithmetic_errors() {
+    assert_eq!("division by zero", interpret("1 / 0"));
+    assert_eq!("inf", interpret("2.5 / (1 - 1)"));
+    assert_eq!("inf", interpret(format!("{}.0 * 10", "9".repeat(308))));
+
+    let mut interpreter = Interpreter::new();
+    interpreter.append_text("1 / 0");
+    assert_eq!("! division by zero", interpreter.interpret());
+    interpreter.append_text("1 / 2.0");
+    assert_eq!("< 0.5", interpreter.interpret());
+}
+
+#[test]
+fn test_tolerance() {
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+    let sum = "if 0.1 + 0.2 == 0.3 { 1 } else { 0 }";
+
+    assert_eq!("# tolerance default", check(":tolerance"));
+    assert_eq!("< 1", check(sum));
+    assert_eq!("# tolerance strict", check(":tolerance strict"));
+    assert_eq!("< 0", check(sum));
+    assert_eq!("# tolerance ulp 1", check(":tolerance ulp 1"));
+    assert_eq!("< 1", check(sum));
+    assert_eq!("# tolerance absolute 1e-9", check(":tolerance 0.000000001"));
+    assert_eq!("< 1", check("if 1.0 == 1.0000000001 { 1 } else { 0 }"));
+    assert_eq!("# tolerance relative 1e-3", check(":tolerance relative 0.001"));
+    assert_eq!("< 1", check("if 1000.0 == 1000.5 { 1 } else { 0 }"));
+    assert_eq!("< 0", check("if 1.0 == 1.5 { 1 } else { 0 }"));
+    assert_eq!("# bad tolerance loose", check(":tolerance loose"));
+    assert_eq!("# tolerance default", check(":tolerance default"));
+
+    assert_eq!("< false", check("approx(0.1 + 0.2, 0.3, 0.00000000000000000001)"));
+    assert_eq!("< true", check("approx(1//3, 0.3333, 0.001)"));
+    assert_eq!("< 1", check("if approx(1, 1.05, 0.1) { 1 } else { 0 }"));
+    assert_eq!("! approx gives a truth value", check("approx(1, 2, 1) + 1"));
+    assert_eq!("! tolerance must not be negative", check("approx(1, 1, -0.1)"));
+}
+
+#[test]
+fn test_special_values() {
+    assert_eq!("inf", interpret("inf"));
+    assert_eq!("-inf", interpret("-inf"));
+    assert_eq!("nan", interpret("nan"));
+    assert_eq!("inf", interpret("inf * 2 + 1"));
+    assert_eq!("nan", interpret("inf - inf"));
+    assert_eq!("0", interpret("1 / inf"));
+    assert_eq!("-0", interpret("-1.0 / inf"));
+    assert_eq!("inf", interpret("1 / 0.0"));
+    assert_eq!("-inf", interpret("-1.0 / 0.0"));
+    assert_eq!("nan", interpret("0.0 / 0.0"));
+    assert_eq!("nan", interpret("5.0 % 0.0"));
+    assert_eq!("inf", interpret("1e308 * 10.0"));
+    assert_eq!("0", interpret("1e-300 * 1e-300"));
+    assert_eq!("inf", interpret("0.0 ** -1"));
+    assert_eq!("-inf", interpret("ln(0.0)"));
+    assert_eq!("inf", interpret("exp(1000.0)"));
+    assert_eq!("true", interpret("is_finite(2.5)"));
+    assert_eq!("false", interpret("is_finite(-inf)"));
+    assert_eq!("true", interpret("is_nan(0 * inf)"));
+    assert_eq!("false", interpret("is_nan(inf)"));
+
+    assert_eq!("1", interpret("if inf == inf { 1 } else { 0 }"));
+    assert_eq!("0", interpret("if nan == nan { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if nan != nan { 1 } else { 0 }"));
+    assert_eq!("1", interpret("if -0.0 == 0 { 1 } else { 0 }"));
+    assert_eq!("cannot order nan", interpret("if nan < 1 { 1 } else { 0 }"));
+    assert_eq!("bounds of an interval must not be nan", interpret("interval(nan, 1)"));
+
+    assert_eq!("complex infinity", interpret("inf * 1i"));
+    assert_eq!("complex infinity", interpret("(inf + 1i) * (2 - 3i)"));
+    assert_eq!("0", interpret("1 / (inf * 1i)"));
+    assert_eq!("nan", interpret("inf * 1i - inf * 1i"));
+    assert_eq!("inf", interpret("abs(inf * 1i)"));
+    assert_eq!("false", interpret("is_nan(inf * 1i)"));
+    assert_eq!("nan", interpret("1 + nan * 1i"));
+}
+
+#[test]
+fn test_strict() {
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+
+    assert_eq!("# strict off", check(":strict"));
+    assert_eq!("< inf", check("1e999"));
+    assert_eq!("# strict on", check(":strict on"));
+    assert_eq!("! inf is not finite", check("1e999"));
+    assert_eq!("! nan is not finite", check("2 + nan"));
+    assert_eq!("! inf is not finite", check("if inf > 1 { 1 } else { 0 }"));
+    assert_eq!("! overflow in Real multiplication", check("1e308 * 10"));
+    assert_eq!("! underflow in Real multiplication", check("1e-300 * 1e-300"));
+    assert_eq!("! division by zero", check("1 / 0.0"));
+    assert_eq!("! division by zero", check("0.0 / 0.0"));
+    assert_eq!("! division by zero", check("0.0 ** -1"));
+    assert_eq!("! ln of 0 is not finite", check("ln(0.0)"));
+    assert_eq!("! exp of 1000 is not finite", check("exp(1000.0)"));
+    assert_eq!("< 3", check("1.5 * 2"));
+    assert_eq!("# bad strict maybe", check(":strict maybe"));
+    assert_eq!("# strict off", check(":strict off"));
+    assert_eq!("< -inf", check("-inf"));
+}
+
+#[test]
+fn test_rationalize() {
+    assert_eq!("355 / 113", interpret("rationalize(3.141592653589793, 1000)"));
+    assert_eq!("201 / 64", interpret("rationalize(3.141592653589793, 0.001)"));
+    assert_eq!("1 / 3", interpret("rationalize(0.3333333333, 0.000001)"));
+    assert_eq!("1 / 10", interpret("rationalize(0.1, 0)"));
+    assert_eq!("(3, 7, 15, 1, 292)", interpret("cfrac(3.141592653589793, 5)"));
+    assert_eq!("(-3, 1, 2)", interpret("cfrac(-7/3, 10)"));
+    assert_eq!(
+        "(1, 3 / 2, 7 / 5, 17 / 12, 41 / 29)",
+        interpret("convergents(sqrt(2), 5)")
+    );
+    assert_eq!("rationalize is not defined for Complex numbers", interpret("rationalize(1i, 3)"));
+    assert_eq!("tolerance must not be negative", interpret("rationalize(1, -1)"));
+    assert_eq!("number of terms must be a natural number up to 10000", interpret("cfrac(1, 1/2)"));
+}
+
+#[test]
+fn test_number_theory() {
+    assert_eq!("6", interpret("gcd(12, -18)"));
+    assert_eq!("12", interpret("lcm(4, 6)"));
+    assert_eq!("(2, -9, 47)", interpret("xgcd(240, 46)"));
+    assert_eq!("445", interpret("modpow(4, 13, 497)"));
+    assert_eq!("5", interpret("modpow(3, -1, 7)"));
+    assert_eq!("2", interpret("modinv(-3, 7)"));
+    assert_eq!("true", interpret("is_prime(2 ** 61 - 1)"));
+    assert_eq!("17", interpret("next_prime(13)"));
+    assert_eq!("(274177, 67280421310721)", interpret("factor(2 ** 64 + 1)"));
+    assert_eq!("12", interpret("totient(36)"));
+    assert_eq!("(1, 2, 4, 7, 14, 28)", interpret("divisors(28)"));
+    assert_eq!("9", interpret("isqrt(99)"));
+    assert_eq!("10", interpret("iroot(1000, 3)"));
+    assert_eq!("1", interpret("if is_prime(7) { 1 } else { 0 }"));
+
+    assert_eq!("3 has no inverse modulo 6", interpret("modinv(3, 6)"));
+    assert_eq!("modulus must be positive", interpret("modpow(2, 3, 0)"));
+    assert_eq!("factor of 0 is not defined", interpret("factor(0)"));
+    assert_eq!(
+        "factors of 340282366920938463463374607431768211457 are out of reach",
+        interpret("factor(2 ** 128 + 1)")
+    );
+    assert_eq!("isqrt needs natural numbers", interpret("isqrt(-4)"));
+    assert_eq!("gcd needs integers", interpret("gcd(1.5, 2)"));
+    assert_eq!("degree of a root must be a positive natural number", interpret("iroot(8, 0)"));
+}
+
+#[test]
+fn test_modular() {
+    assert_eq!("2 (mod 7)", interpret("modular(5, 7) + modular(4, 7)"));
+    assert_eq!("5 (mod 7)", interpret("modular(-2, 7)"));
+    assert_eq!("5", interpret("mod(-2, 7)"));
+    assert_eq!("6 (mod 7)", interpret("2 - modular(3, 7)"));
+    assert_eq!("5 (mod 7)", interpret("1 / modular(3, 7)"));
+    assert_eq!("4 (mod 7)", interpret("modular(3, 7) ** 100"));
+    assert_eq!("4 (mod 7)", interpret("modular(1/2, 7)"));
+    assert_eq!("1", interpret("if modular(10, 7) == 3 { 1 } else { 0 }"));
+
+    assert_eq!("moduli 7 and 5 do not match", interpret("modular(2, 7) + modular(2, 5)"));
+    assert_eq!("3 has no inverse modulo 6", interpret("modular(1, 6) / modular(3, 6)"));
+    assert_eq!("Real numbers do not combine with Modular numbers", interpret("modular(3, 7) + 0.5"));
+    assert_eq!("cannot order Modular numbers", interpret("if modular(3, 7) < 4 { 1 } else { 0 }"));
+    assert_eq!("sin is not defined for Modular numbers", interpret("sin(modular(3, 7))"));
+    assert_eq!("3 (mod 7)", interpret("fraction(modular(3, 7))"));
+    assert_eq!("modulus must be positive", interpret("modular(3, 0)"));
+}
+
+#[test]
+fn test_literals() {
+    assert_eq!("0.000000001", interpret("1e-9"));
+    assert_eq!("602000000000000000000000", interpret("6.02E23"));
+    assert_eq!("255", interpret("0xff"));
+    assert_eq!("15", interpret("0o1_7"));
+    assert_eq!("11", interpret("0b1011"));
+    assert_eq!("1000000", interpret("1_000_000"));
+    assert_eq!("3 / 10", interpret("0.1r + 0.2r"));
+    assert_eq!("1 / 1000000000", interpret("1e-9r"));
+    assert_eq!("2500", interpret("2.5e3r"));
+    assert_eq!("0 + 150i", interpret("1.5e2i"));
+
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+    assert_eq!("! bad number 1//0: zero denominator", check("1//0"));
+    assert_eq!("! bad number 0x: expected digits after 0x", check("1 + 0x"));
+    assert_eq!("! bad number 0b102: 2 is not a binary digit", check("0b102"));
+    assert_eq!("! bad number 1__0: _ must stand between two digits", check("1__0"));
+    assert_eq!("! bad number 1_: _ must stand between two digits", check("1_ + 1"));
+    assert_eq!("! bad number 1e+: expected digits after e", check("1e+"));
+    assert_eq!("! bad number 1.2.3: unexpected .", check("1.2.3"));
+    assert_eq!("! bad number 0.5ri: unexpected i", check("0.5ri"));
+    assert_eq!("! bad number 1.5//2: unexpected /", check("1.5//2"));
+    assert_eq!("! bad number 0x1.5: unexpected .", check("0x1.5"));
+    assert_eq!("! bad number 0x10//3: unexpected /", check("0x10//3"));
+    assert_eq!("! bad number 1//2//3: unexpected /", check("1//2//3"));
+    assert_eq!("! bad number .5: expected digits before .", check("1 + .5"));
+    assert_eq!("! bad number 1e999999r: exponent too large for an exact number", check("1e999999r"));
+    assert_eq!("< 2", check("1 + 1"));
+}
+
+#[test]
+fn test_format() {
+    assert_eq!("2/3", interpret("fraction(2/3)"));
+    assert_eq!("-1 1/3", interpret("mixed(-4/3)"));
+    assert_eq!("1.1(6)", interpret("decimal(7/6)"));
+    assert_eq!("0.(142857)", interpret("decimal(1/7)"));
+    assert_eq!("0.6667", interpret("fixed(2/3, 4)"));
+    assert_eq!("6.67e-1", interpret("sci(2/3, 3)"));
+    assert_eq!("12.345678e3", interpret("eng(12345.678)"));
+    assert_eq!("-0xff", interpret("hex(-255)"));
+    assert_eq!("0b1010", interpret("bin(10)"));
+    assert_eq!("1/10", interpret("fraction(0.1)"));
+    assert_eq!("5 / 3", interpret("fraction(2/3) + 1"));
+    assert_eq!("hex notation needs an integer", interpret("hex(1/2)"));
+
+    let mut interpreter = Interpreter::new();
+    let mut check = |text: &str| {
+        interpreter.append_text(text);
+        interpreter.interpret()
+    };
+    assert_eq!("# format default", check(":format"));
+    assert_eq!("# format mixed", check(":format mixed"));
+    assert_eq!("< 2 1/2", check("5/2"));
+    assert_eq!("< (-3, 1/2)", check("divmod(-5/2, 1)"));
+    assert_eq!("# format fixed 2", check(":format fixed 2"));
+    assert_eq!("< 0.33 + 1.00i", check("1/3 + 1i"));
+    assert_eq!("# format hex", check(":format hex"));
+    assert_eq!("< 0x400000000000000000", check("2 ** 70"));
+    assert_eq!("< 1 / 2", check("1/2"));
+    assert_eq!("< 0.5", check("decimal(1/2)"));
+    assert_eq!("# bad format sci 0", check(":format sci 0"));
+    assert_eq!("# format sci 3", check(":format scientific 3"));
+    assert_eq!("< 1.23e3", check("1234"));
+    assert_eq!("# format eng", check(":format engineering"));
+    assert_eq!("# format default", check(":format default"));
+}
+
 #[test]
 fn test_bad_numbers() {
     let text = "2.5 - 3.2i";
@@ -12,26 +12,58 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use numbers::{AsNum, Natural};
+use numbers::{AsNum, Integer, Natural};
 
+/// Greatest common divisor of the magnitudes, the signs don't matter.
 pub fn gcd<U, V>(a: U, b: V) -> Natural
 where
-    U: AsNum<Natural>,
-    V: AsNum<Natural>,
+    U: AsNum<Integer>,
+    V: AsNum<Integer>,
 {
-    return gcd_nat(a.as_num(), b.as_num());
+    return gcd_nat(a.as_num().abs(), b.as_num().abs());
 }
 
 pub fn gcd_nat(a: Natural, b: Natural) -> Natural {
-    let mut a = a;
-    let mut b = b;
-    while a > Natural::zero() && b > Natural::zero() {
-        if a > b {
-            a = a % b
-        } else {
-            b = b % a
-        }
+    let mut a = a.value();
+    let mut b = b.value();
+    while !b.is_zero() {
+        let r = &a % &b;
+        a = b;
+        b = r;
     }
 
-    return a + b;
+    return Natural::new(a);
+}
+
+/// Least common multiple of the magnitudes.
+pub fn lcm<U, V>(a: U, b: V) -> Natural
+where
+    U: AsNum<Integer>,
+    V: AsNum<Integer>,
+{
+    lcm_nat(a.as_num().abs(), b.as_num().abs())
+}
+
+/// Least common multiple, `0` when either is `0`.
+pub fn lcm_nat(a: Natural, b: Natural) -> Natural {
+    if a.value().is_zero() || b.value().is_zero() {
+        return Natural::zero();
+    }
+    let gcd = gcd_nat(a.clone(), b.clone());
+    Natural::new(&a.value() / &gcd.value() * b.value())
+}
+
+/// `x * 2^exp` without overflowing the intermediate power of two.
+pub fn ldexp(x: f64, exp: i64) -> f64 {
+    let mut x = x;
+    let mut exp = exp;
+    while exp > 1000 {
+        x *= 2f64.powi(1000);
+        exp -= 1000;
+    }
+    while exp < -1000 {
+        x *= 2f64.powi(-1000);
+        exp += 1000;
+    }
+    x * 2f64.powi(exp as i32)
 }